@@ -14,7 +14,13 @@
 
 //! A helper crate brings easy life to write tugraph rust procedure.
 
-pub use tugraph_plugin_util_proc_macros::tugraph_plugin;
+pub use tugraph_plugin_util_proc_macros::{tugraph_plugin, Edge, Vertex};
+
+mod model;
+pub use model::{Edge, Vertex};
+
+#[doc(hidden)]
+pub use ::tugraph;
 
 #[doc(hidden)]
 pub use ::libtugraph_sys::lgraph_api_graph_db_t;
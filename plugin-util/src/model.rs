@@ -0,0 +1,149 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits mapping Rust structs to vertex and edge labels.
+//!
+//! They are usually implemented by `#[derive(Vertex)]` and `#[derive(Edge)]`.
+
+use tugraph::{
+    cursor::{EdgeCursor, VertexCursor},
+    db::Graph,
+    field::{FieldData, FieldSpec},
+    txn::{RwTxn, TxnWrite},
+    types::EdgeUid,
+    Result,
+};
+
+/// A Rust struct mapped to a vertex label.
+///
+/// # Derive
+/// `#[derive(Vertex)]` implements it for a struct with named fields:
+/// - each field becomes a [`FieldSpec`] whose type is given by [`FieldValue`], and `Option<T>`
+///   becomes an optional field
+/// - exactly one field must be marked by `#[tugraph(primary)]`
+/// - the label name is the struct name, unless overridden by `#[tugraph(label = "name")]`
+///
+/// ```no_run
+/// use tugraph_plugin_util::Vertex;
+///
+/// #[derive(Vertex)]
+/// struct Person {
+///     #[tugraph(primary)]
+///     name: String,
+///     age: Option<i32>,
+/// }
+/// ```
+///
+/// [`FieldValue`]: tugraph::field::FieldValue
+pub trait Vertex: Sized {
+    /// The label name.
+    const LABEL: &'static str;
+
+    /// The primary field name.
+    const PRIMARY_FIELD: &'static str;
+
+    /// The field names, in the order of [`Vertex::field_specs`].
+    const FIELD_NAMES: &'static [&'static str];
+
+    /// Get the field specs of the label.
+    fn field_specs() -> Vec<FieldSpec>;
+
+    /// Get the field values of `self`, in the order of [`Vertex::FIELD_NAMES`].
+    fn field_values(&self) -> Vec<FieldData>;
+
+    /// Read a value from the vertex the cursor points to.
+    ///
+    /// # Errors
+    /// If a field is missing, or its value can not be converted to the type of the struct
+    /// field, an error is returned.
+    fn from_cursor<C: VertexCursor>(cursor: &C) -> Result<Self>;
+
+    /// Create the vertex label in `graph`.
+    ///
+    /// Returns `false` if the label already exists.
+    fn create_label(graph: &Graph<'_>) -> Result<bool> {
+        graph.add_vertex_label(Self::LABEL, &Self::field_specs(), Self::PRIMARY_FIELD)
+    }
+
+    /// Add `self` as a vertex and return its vertex id.
+    fn insert(&self, txn: &mut RwTxn<'_>) -> Result<i64> {
+        txn.add_vertex(Self::LABEL, Self::FIELD_NAMES, &self.field_values())
+    }
+}
+
+/// A Rust struct mapped to an edge label.
+///
+/// # Derive
+/// `#[derive(Edge)]` implements it for a struct with named fields, the same way as
+/// `#[derive(Vertex)]` does, except that:
+/// - at most one field can be marked by `#[tugraph(temporal)]`
+/// - the edge constraints can be given by `#[tugraph(constraints = [("Src", "Dst")])]`
+///
+/// ```no_run
+/// use tugraph_plugin_util::Edge;
+///
+/// #[derive(Edge)]
+/// #[tugraph(label = "knows", constraints = [("Person", "Person")])]
+/// struct Knows {
+///     #[tugraph(temporal)]
+///     since: i64,
+/// }
+/// ```
+pub trait Edge: Sized {
+    /// The label name.
+    const LABEL: &'static str;
+
+    /// The temporal field name, empty if there is none.
+    const TEMPORAL_FIELD: &'static str;
+
+    /// The `(src label, dst label)` pairs the edge is constrained to, empty if there is none.
+    const CONSTRAINTS: &'static [(&'static str, &'static str)];
+
+    /// The field names, in the order of [`Edge::field_specs`].
+    const FIELD_NAMES: &'static [&'static str];
+
+    /// Get the field specs of the label.
+    fn field_specs() -> Vec<FieldSpec>;
+
+    /// Get the field values of `self`, in the order of [`Edge::FIELD_NAMES`].
+    fn field_values(&self) -> Vec<FieldData>;
+
+    /// Read a value from the edge the cursor points to.
+    ///
+    /// See [`Vertex::from_cursor`] for errors.
+    fn from_cursor<C: EdgeCursor>(cursor: &C) -> Result<Self>;
+
+    /// Create the edge label in `graph`.
+    ///
+    /// Returns `false` if the label already exists.
+    fn create_label(graph: &Graph<'_>) -> Result<bool> {
+        graph.add_edge_label(
+            Self::LABEL,
+            &Self::field_specs(),
+            Self::TEMPORAL_FIELD,
+            Self::CONSTRAINTS.iter().copied(),
+        )
+    }
+
+    /// Add `self` as an edge from `src` to `dst` and return its edge uid.
+    fn insert(&self, txn: &mut RwTxn<'_>, src: i64, dst: i64) -> Result<EdgeUid> {
+        txn.add_edge(
+            src,
+            dst,
+            Self::LABEL,
+            Self::FIELD_NAMES,
+            &self.field_values(),
+        )
+    }
+}
@@ -14,6 +14,7 @@
 
 use proc_macro::{TokenStream, TokenTree::Ident};
 use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, LitStr};
 
 /// A helper attribute macro brings easy life to write tugraph rust procedure
 #[proc_macro_attribute]
@@ -67,3 +68,248 @@ pub fn tugraph_plugin(_attr: TokenStream, input: TokenStream) -> TokenStream {
     };
     TokenStream::from(extern_c_process)
 }
+
+/// Derive `tugraph_plugin_util::Vertex` for a struct with named fields.
+///
+/// See the `Vertex` trait for the supported attributes.
+#[proc_macro_derive(Vertex, attributes(tugraph))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_vertex(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `tugraph_plugin_util::Edge` for a struct with named fields.
+///
+/// See the `Edge` trait for the supported attributes.
+#[proc_macro_derive(Edge, attributes(tugraph))]
+pub fn derive_edge(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_edge(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+// attributes of the derived struct
+struct LabelAttrs {
+    label: String,
+    constraints: Option<Expr>,
+}
+
+// a named field of the derived struct
+struct LabelField {
+    ident: syn::Ident,
+    ty: syn::Type,
+    name: String,
+    primary: bool,
+    temporal: bool,
+}
+
+fn parse_label_attrs(input: &DeriveInput) -> syn::Result<LabelAttrs> {
+    let mut attrs = LabelAttrs {
+        label: input.ident.to_string(),
+        constraints: None,
+    };
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("tugraph")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("label") {
+                attrs.label = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("constraints") {
+                attrs.constraints = Some(meta.value()?.parse::<Expr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported tugraph attribute"))
+            }
+        })?;
+    }
+    Ok(attrs)
+}
+
+fn parse_label_fields(input: &DeriveInput) -> syn::Result<Vec<LabelField>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "only structs with named fields are supported",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "only structs with named fields are supported",
+            ))
+        }
+    };
+    fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().expect("named field has an ident");
+            let mut label_field = LabelField {
+                name: ident.to_string(),
+                ident,
+                ty: field.ty.clone(),
+                primary: false,
+                temporal: false,
+            };
+            for attr in field.attrs.iter().filter(|a| a.path().is_ident("tugraph")) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("primary") {
+                        label_field.primary = true;
+                        Ok(())
+                    } else if meta.path.is_ident("temporal") {
+                        label_field.temporal = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported tugraph attribute"))
+                    }
+                })?;
+            }
+            Ok(label_field)
+        })
+        .collect()
+}
+
+// Generate the items shared by `Vertex` and `Edge` impls.
+fn expand_fields(fields: &[LabelField]) -> proc_macro2::TokenStream {
+    let names: Vec<_> = fields.iter().map(|f| &f.name).collect();
+    let idents: Vec<_> = fields.iter().map(|f| &f.ident).collect();
+    let tys: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    quote! {
+        const FIELD_NAMES: &'static [&'static str] = &[#(#names),*];
+
+        fn field_specs() -> ::std::vec::Vec<::tugraph_plugin_util::tugraph::field::FieldSpec> {
+            ::std::vec![#(
+                ::tugraph_plugin_util::tugraph::field::FieldSpec {
+                    name: ::std::string::String::from(#names),
+                    ty: <#tys as ::tugraph_plugin_util::tugraph::field::FieldValue>::TYPE,
+                    optional: <#tys as ::tugraph_plugin_util::tugraph::field::FieldValue>::OPTIONAL,
+                }
+            ),*]
+        }
+
+        fn field_values(&self) -> ::std::vec::Vec<::tugraph_plugin_util::tugraph::field::FieldData> {
+            ::std::vec![#(
+                ::tugraph_plugin_util::tugraph::field::FieldValue::to_field_data(&self.#idents)
+            ),*]
+        }
+    }
+}
+
+fn expand_from_cursor(
+    fields: &[LabelField],
+    cursor_trait: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let idents: Vec<_> = fields.iter().map(|f| &f.ident).collect();
+    quote! {
+        fn from_cursor<C: #cursor_trait>(cursor: &C) -> ::tugraph_plugin_util::tugraph::Result<Self> {
+            let values = cursor.fields(Self::FIELD_NAMES)?;
+            // a value for each field, otherwise a field would be read as another one
+            if values.len() != Self::FIELD_NAMES.len() {
+                return ::std::result::Result::Err(
+                    ::tugraph_plugin_util::tugraph::Error::invalid_parameter(),
+                );
+            }
+            let mut values = values.into_iter();
+            ::std::result::Result::Ok(Self {#(
+                #idents: ::tugraph_plugin_util::tugraph::field::FieldValue::from_field_data(
+                    values.next().expect("a value for each field"),
+                )?,
+            )*})
+        }
+    }
+}
+
+fn expand_vertex(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = parse_label_attrs(input)?;
+    if let Some(constraints) = &attrs.constraints {
+        return Err(syn::Error::new_spanned(
+            constraints,
+            "constraints are only supported by edges",
+        ));
+    }
+    let fields = parse_label_fields(input)?;
+    if let Some(field) = fields.iter().find(|f| f.temporal) {
+        return Err(syn::Error::new_spanned(
+            &field.ident,
+            "temporal field is only supported by edges",
+        ));
+    }
+    let primary_field = match fields.iter().filter(|f| f.primary).collect::<Vec<_>>()[..] {
+        [field] => &field.name,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "exactly one field should be marked by #[tugraph(primary)]",
+            ))
+        }
+    };
+
+    let ident = &input.ident;
+    let label = &attrs.label;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields_items = expand_fields(&fields);
+    let from_cursor = expand_from_cursor(
+        &fields,
+        quote!(::tugraph_plugin_util::tugraph::cursor::VertexCursor),
+    );
+    Ok(quote! {
+        impl #impl_generics ::tugraph_plugin_util::Vertex for #ident #ty_generics #where_clause {
+            const LABEL: &'static str = #label;
+            const PRIMARY_FIELD: &'static str = #primary_field;
+
+            #fields_items
+
+            #from_cursor
+        }
+    })
+}
+
+fn expand_edge(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = parse_label_attrs(input)?;
+    let fields = parse_label_fields(input)?;
+    if let Some(field) = fields.iter().find(|f| f.primary) {
+        return Err(syn::Error::new_spanned(
+            &field.ident,
+            "primary field is only supported by vertices",
+        ));
+    }
+    let temporal_field = match fields.iter().filter(|f| f.temporal).collect::<Vec<_>>()[..] {
+        [] => "",
+        [field] => &field.name,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "at most one field can be marked by #[tugraph(temporal)]",
+            ))
+        }
+    };
+    let constraints = match &attrs.constraints {
+        Some(constraints) => quote!(&#constraints),
+        None => quote!(&[]),
+    };
+
+    let ident = &input.ident;
+    let label = &attrs.label;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields_items = expand_fields(&fields);
+    let from_cursor = expand_from_cursor(
+        &fields,
+        quote!(::tugraph_plugin_util::tugraph::cursor::EdgeCursor),
+    );
+    Ok(quote! {
+        impl #impl_generics ::tugraph_plugin_util::Edge for #ident #ty_generics #where_clause {
+            const LABEL: &'static str = #label;
+            const TEMPORAL_FIELD: &'static str = #temporal_field;
+            const CONSTRAINTS: &'static [(&'static str, &'static str)] = #constraints;
+
+            #fields_items
+
+            #from_cursor
+        }
+    })
+}
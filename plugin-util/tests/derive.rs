@@ -0,0 +1,156 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tugraph::{
+    cursor::VertexCursor,
+    db::OpenOptions,
+    field::{FieldData, FieldSpec, FieldType},
+    txn::TxnRead,
+};
+use tugraph_plugin_util::{Edge, Vertex};
+
+#[derive(Vertex, Debug, PartialEq)]
+struct Person {
+    #[tugraph(primary)]
+    name: String,
+    age: Option<i32>,
+}
+
+#[derive(Edge, Debug, PartialEq)]
+#[tugraph(label = "knows", constraints = [("Person", "Person")])]
+struct Knows {
+    #[tugraph(temporal)]
+    since: i64,
+    weight: f64,
+}
+
+#[derive(Edge)]
+struct Follows {}
+
+#[test]
+fn test_derive_vertex() {
+    assert_eq!(Person::LABEL, "Person");
+    assert_eq!(Person::PRIMARY_FIELD, "name");
+    assert_eq!(Person::FIELD_NAMES, &["name", "age"]);
+    assert_eq!(
+        Person::field_specs(),
+        vec![
+            FieldSpec {
+                name: "name".into(),
+                ty: FieldType::String,
+                optional: false,
+            },
+            FieldSpec {
+                name: "age".into(),
+                ty: FieldType::Int32,
+                optional: true,
+            },
+        ]
+    );
+    let person = Person {
+        name: "alice".into(),
+        age: None,
+    };
+    assert_eq!(
+        person.field_values(),
+        vec![FieldData::String("alice".into()), FieldData::Null]
+    );
+}
+
+#[test]
+fn test_derive_edge() {
+    assert_eq!(Knows::LABEL, "knows");
+    assert_eq!(Knows::TEMPORAL_FIELD, "since");
+    assert_eq!(Knows::CONSTRAINTS, &[("Person", "Person")]);
+    assert_eq!(Knows::FIELD_NAMES, &["since", "weight"]);
+    assert_eq!(
+        Knows::field_specs()
+            .into_iter()
+            .map(|spec| spec.ty)
+            .collect::<Vec<_>>(),
+        vec![FieldType::Int64, FieldType::Double]
+    );
+    let knows = Knows {
+        since: 2023,
+        weight: 0.5,
+    };
+    assert_eq!(
+        knows.field_values(),
+        vec![FieldData::Int64(2023), FieldData::Double(0.5)]
+    );
+
+    assert_eq!(Follows::LABEL, "Follows");
+    assert_eq!(Follows::TEMPORAL_FIELD, "");
+    assert!(Follows::CONSTRAINTS.is_empty());
+    assert!(Follows::field_specs().is_empty());
+}
+
+#[test]
+fn test_round_trip() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let galaxy = OpenOptions::new()
+        .create(true)
+        .open(tmpdir.path(), "admin", "73@TuGraph")
+        .unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    assert!(Person::create_label(&graph).unwrap());
+    assert!(Knows::create_label(&graph).unwrap());
+    {
+        let ro_txn = graph.create_ro_txn().unwrap();
+        assert_eq!(
+            ro_txn.vertex_schema(Person::LABEL).unwrap(),
+            Person::field_specs()
+        );
+        assert_eq!(
+            ro_txn.get_vertex_primary_field(Person::LABEL).unwrap(),
+            "name"
+        );
+        assert_eq!(
+            ro_txn.edge_schema(Knows::LABEL).unwrap(),
+            Knows::field_specs()
+        );
+    }
+
+    let alice = Person {
+        name: "alice".into(),
+        age: Some(30),
+    };
+    let bob = Person {
+        name: "bob".into(),
+        age: None,
+    };
+    let knows = Knows {
+        since: 2023,
+        weight: 0.5,
+    };
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    let alice_vid = alice.insert(&mut rw_txn).unwrap();
+    let bob_vid = bob.insert(&mut rw_txn).unwrap();
+    // the primary field is unique
+    assert!(alice.insert(&mut rw_txn).is_err());
+    let euid = knows.insert(&mut rw_txn, alice_vid, bob_vid).unwrap();
+    rw_txn.commit().unwrap();
+    // the temporal field is the temporal id of the edge
+    assert_eq!(euid.tid, 2023);
+
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let mut vcur = ro_txn.vertex_cur().unwrap();
+    vcur.seek(alice_vid, false).unwrap();
+    assert_eq!(Person::from_cursor(&vcur).unwrap(), alice);
+    vcur.seek(bob_vid, false).unwrap();
+    assert_eq!(vcur.field("age").unwrap(), FieldData::Null);
+    assert_eq!(Person::from_cursor(&vcur).unwrap(), bob);
+    let ecur = ro_txn.out_edge_cur(&euid).unwrap();
+    assert_eq!(Knows::from_cursor(&ecur).unwrap(), knows);
+}
@@ -0,0 +1,744 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::VecDeque, sync::atomic::Ordering};
+
+use crate::{
+    cursor::VertexCursor,
+    parallel::{Parallel, Units, UNITS_PER_WORKER},
+    traverse::Direction,
+    txn::TxnRead,
+    Error, Result,
+};
+
+use super::{labels, Filter, Rng, TopK, Topology, VertexValues};
+
+/// Compute the degree centrality with the default [`Degree`] settings.
+pub fn degree_centrality<T: TxnRead>(txn: &T) -> Result<VertexValues<f64>> {
+    Degree::new().run(txn)
+}
+
+/// Compute the closeness centrality with the default [`Closeness`] settings.
+pub fn closeness_centrality<T: TxnRead>(txn: &T) -> Result<VertexValues<f64>> {
+    Closeness::new().run(txn)
+}
+
+/// Compute the betweenness centrality with the default [`Betweenness`] settings.
+pub fn betweenness_centrality<T: TxnRead>(txn: &T) -> Result<VertexValues<f64>> {
+    Betweenness::new().run(txn)
+}
+
+/// `Degree` computes the degree centrality, the number of edges of each vertex.
+///
+/// Degrees are read by [`VertexCursor::num_out_edges`] and [`VertexCursor::num_in_edges`]
+/// without loading the edges, so edges of all labels are counted, including those to
+/// vertices of other labels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Degree {
+    direction: Direction,
+    normalized: bool,
+    filter: Filter,
+}
+
+impl Default for Degree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Degree {
+    /// Create counting both out and in edges of all vertices without normalization.
+    pub fn new() -> Self {
+        Degree {
+            direction: Direction::Both,
+            normalized: false,
+            filter: Filter::default(),
+        }
+    }
+
+    /// Set the direction of edges to count.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Divide the degrees by the number of other vertices if `normalized`.
+    pub fn normalized(mut self, normalized: bool) -> Self {
+        self.normalized = normalized;
+        self
+    }
+
+    /// Only count vertices of the given labels.
+    pub fn vertex_labels<I, S>(mut self, vertex_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.vertex_labels = labels(vertex_labels);
+        self
+    }
+
+    // Call `f` on the degree of each vertex, and return the number of vertices.
+    fn scan<T, F>(&self, txn: &T, mut f: F) -> Result<usize>
+    where
+        T: TxnRead,
+        F: FnMut(i64, f64),
+    {
+        let lids = self.filter.lids(txn)?;
+        let mut num_vertices = 0;
+        let mut vcur = txn.vertex_cur()?;
+        vcur.seek(0, true)?;
+        while vcur.is_valid() {
+            if lids
+                .vertex
+                .as_ref()
+                .map_or(Ok(true), |lids| vcur.lid().map(|lid| lids.contains(&lid)))?
+            {
+                let mut degree = 0;
+                if self.direction.has_out() {
+                    degree += vcur.num_out_edges(usize::MAX)?.1;
+                }
+                if self.direction.has_in() {
+                    degree += vcur.num_in_edges(usize::MAX)?.1;
+                }
+                f(vcur.id()?, degree as f64);
+                num_vertices += 1;
+            }
+            vcur.seek_to_next()?;
+        }
+        Ok(num_vertices)
+    }
+
+    fn scale(&self, num_vertices: usize) -> f64 {
+        if self.normalized && num_vertices > 1 {
+            1.0 / (num_vertices - 1) as f64
+        } else {
+            1.0
+        }
+    }
+
+    /// Compute the degree of each vertex seen by `txn`.
+    ///
+    /// # Errors
+    /// If a label does not exist, an error is returned.
+    pub fn run<T: TxnRead>(&self, txn: &T) -> Result<VertexValues<f64>> {
+        let (mut vids, mut degrees) = (vec![], vec![]);
+        let num_vertices = self.scan(txn, |vid, degree| {
+            vids.push(vid);
+            degrees.push(degree);
+        })?;
+        let scale = self.scale(num_vertices);
+        degrees.iter_mut().for_each(|degree| *degree *= scale);
+        Ok(VertexValues::new(vids, degrees))
+    }
+
+    /// Get the `k` vertices of the highest degrees in descending order, keeping only
+    /// `k` degrees in memory.
+    ///
+    /// # Errors
+    /// If a label does not exist, an error is returned.
+    pub fn top_k<T: TxnRead>(&self, txn: &T, k: usize) -> Result<Vec<(i64, f64)>> {
+        let mut top = TopK::new(k);
+        let num_vertices = self.scan(txn, |vid, degree| top.push(vid, degree))?;
+        let scale = self.scale(num_vertices);
+        Ok(top
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(vid, degree)| (vid, degree * scale))
+            .collect())
+    }
+}
+
+/// `Closeness` computes the closeness centrality of each vertex, following edges in
+/// the given direction.
+///
+/// The closeness of a vertex is `r / (n - 1) * r / d`, where `n` is the number of
+/// vertices, `r` is the number of other vertices reachable from it, and `d` is the sum
+/// of their distances. The factor `r / (n - 1)` makes scores comparable in a
+/// disconnected graph. With [`Closeness::pivots`], distances are sampled from the
+/// vertices to the pivots only.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{algo::Closeness, db::OpenOptions, parallel::Parallel};
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let top = Closeness::new()
+///     .edge_labels(["knows"])
+///     .par_top_k(&Parallel::new(&graph, &ro_txn), 10)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Closeness {
+    direction: Direction,
+    pivots: Option<usize>,
+    seed: u64,
+    filter: Filter,
+}
+
+impl Default for Closeness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Closeness {
+    /// Create following out edges of all labels from all vertices exactly.
+    pub fn new() -> Self {
+        Closeness {
+            direction: Direction::Out,
+            pivots: None,
+            seed: 0,
+            filter: Filter::default(),
+        }
+    }
+
+    /// Set the direction of edges to follow.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Approximate by the distances to `pivots` vertices sampled at random.
+    pub fn pivots(mut self, pivots: usize) -> Self {
+        self.pivots = Some(pivots);
+        self
+    }
+
+    /// Set the seed to sample pivots.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Only use vertices of the given labels.
+    pub fn vertex_labels<I, S>(mut self, vertex_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.vertex_labels = labels(vertex_labels);
+        self
+    }
+
+    /// Only follow edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, edge_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.edge_labels = labels(edge_labels);
+        self
+    }
+
+    fn check(&self) -> Result<()> {
+        match self.pivots {
+            Some(0) => Err(Error::invalid_parameter()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Compute the closeness of each vertex seen by `txn`.
+    ///
+    /// # Errors
+    /// If the number of pivots is 0 or a label does not exist, an error is returned.
+    pub fn run<T: TxnRead>(&self, txn: &T) -> Result<VertexValues<f64>> {
+        self.check()?;
+        let topology = self.filter.load(txn)?;
+        let scores = self.scores(&topology, None)?;
+        Ok(VertexValues::new(topology.vids, scores))
+    }
+
+    /// Compute as [`Closeness::run`], where the graph is loaded and the searches run
+    /// by the workers of `parallel`.
+    ///
+    /// # Errors
+    /// See [`Closeness::run`].
+    pub fn par_run(&self, parallel: &Parallel<'_>) -> Result<VertexValues<f64>> {
+        self.check()?;
+        let topology = self.filter.par_load(parallel)?;
+        let scores = self.scores(&topology, Some(parallel))?;
+        Ok(VertexValues::new(topology.vids, scores))
+    }
+
+    /// Get the `k` vertices of the highest closeness in descending order. Unless
+    /// sampled by pivots, only `k` scores are kept in memory.
+    ///
+    /// # Errors
+    /// See [`Closeness::run`].
+    pub fn top_k<T: TxnRead>(&self, txn: &T, k: usize) -> Result<Vec<(i64, f64)>> {
+        self.check()?;
+        let topology = self.filter.load(txn)?;
+        self.top(&topology, None, k)
+    }
+
+    /// Get the top `k` as [`Closeness::top_k`], where the graph is loaded and the
+    /// searches run by the workers of `parallel`.
+    ///
+    /// # Errors
+    /// See [`Closeness::run`].
+    pub fn par_top_k(&self, parallel: &Parallel<'_>, k: usize) -> Result<Vec<(i64, f64)>> {
+        self.check()?;
+        let topology = self.filter.par_load(parallel)?;
+        self.top(&topology, Some(parallel), k)
+    }
+
+    fn scores(&self, topology: &Topology, parallel: Option<&Parallel<'_>>) -> Result<Vec<f64>> {
+        if let Some(pivots) = self.pivots {
+            return self.sampled(topology, parallel, pivots);
+        }
+        let n = topology.num_vertices();
+        let oriented = topology.oriented(self.direction);
+        let sources: Vec<_> = (0..n).collect();
+        let partials = for_sources(
+            parallel,
+            &sources,
+            || (vec![], Bfs::new(n)),
+            |(scores, bfs), s| scores.push((s, bfs.closeness(&oriented, s))),
+        )?;
+        let mut scores = vec![0.0; n];
+        for (pos, score) in partials.into_iter().flat_map(|(partial, _)| partial) {
+            scores[pos] = score;
+        }
+        Ok(scores)
+    }
+
+    fn top(
+        &self,
+        topology: &Topology,
+        parallel: Option<&Parallel<'_>>,
+        k: usize,
+    ) -> Result<Vec<(i64, f64)>> {
+        let mut top = TopK::new(k);
+        if let Some(pivots) = self.pivots {
+            let scores = self.sampled(topology, parallel, pivots)?;
+            for (pos, score) in scores.into_iter().enumerate() {
+                top.push(topology.vids[pos], score);
+            }
+            return Ok(top.into_sorted_vec());
+        }
+        let n = topology.num_vertices();
+        let oriented = topology.oriented(self.direction);
+        let sources: Vec<_> = (0..n).collect();
+        let partials = for_sources(
+            parallel,
+            &sources,
+            || (TopK::new(k), Bfs::new(n)),
+            |(top, bfs), s| top.push(topology.vids[s], bfs.closeness(&oriented, s)),
+        )?;
+        for (partial, _) in partials {
+            top.merge(partial);
+        }
+        Ok(top.into_sorted_vec())
+    }
+
+    // Search from the pivots against the direction, which finds the distances from each
+    // vertex to the pivots.
+    fn sampled(
+        &self,
+        topology: &Topology,
+        parallel: Option<&Parallel<'_>>,
+        pivots: usize,
+    ) -> Result<Vec<f64>> {
+        let n = topology.num_vertices();
+        let pivots = Rng::new(self.seed).sample(n, pivots);
+        let reversed = topology.oriented(self.direction.reversed());
+        let partials = for_sources(
+            parallel,
+            &pivots,
+            || (vec![0usize; n], vec![0usize; n], Bfs::new(n)),
+            |(distances, reached, bfs), p| {
+                bfs.run(&reversed, p, false);
+                for &v in &bfs.order[1..] {
+                    distances[v] += bfs.dist[v];
+                    reached[v] += 1;
+                }
+            },
+        )?;
+        let (mut distances, mut reached) = (vec![0; n], vec![0; n]);
+        for (partial_distances, partial_reached, _) in partials {
+            for v in 0..n {
+                distances[v] += partial_distances[v];
+                reached[v] += partial_reached[v];
+            }
+        }
+        let mut is_pivot = vec![false; n];
+        pivots.iter().for_each(|&p| is_pivot[p] = true);
+        Ok((0..n)
+            .map(|v| {
+                let others = pivots.len() - is_pivot[v] as usize;
+                if distances[v] == 0 || others == 0 {
+                    return 0.0;
+                }
+                let r = reached[v] as f64;
+                r / others as f64 * r / distances[v] as f64
+            })
+            .collect())
+    }
+}
+
+/// `Betweenness` computes the betweenness centrality of each vertex by Brandes'
+/// algorithm, following edges in the given direction.
+///
+/// The betweenness of a vertex is the sum over pairs of other vertices of the fraction
+/// of shortest paths between them passing through it. Pairs are unordered if the
+/// direction is [`Direction::Both`]. With [`Betweenness::pivots`], only shortest paths
+/// from the pivots are counted and scaled up.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{algo::Betweenness, db::OpenOptions, parallel::Parallel};
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let top = Betweenness::new()
+///     .pivots(1000)
+///     .normalized(true)
+///     .par_run(&Parallel::new(&graph, &ro_txn))?
+///     .top_k(10);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Betweenness {
+    direction: Direction,
+    pivots: Option<usize>,
+    seed: u64,
+    normalized: bool,
+    filter: Filter,
+}
+
+impl Default for Betweenness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Betweenness {
+    /// Create following out edges of all labels from all vertices exactly, without
+    /// normalization.
+    pub fn new() -> Self {
+        Betweenness {
+            direction: Direction::Out,
+            pivots: None,
+            seed: 0,
+            normalized: false,
+            filter: Filter::default(),
+        }
+    }
+
+    /// Set the direction of edges to follow.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Approximate by the shortest paths from `pivots` vertices sampled at random.
+    pub fn pivots(mut self, pivots: usize) -> Self {
+        self.pivots = Some(pivots);
+        self
+    }
+
+    /// Set the seed to sample pivots.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Divide the scores by the number of pairs of other vertices if `normalized`.
+    pub fn normalized(mut self, normalized: bool) -> Self {
+        self.normalized = normalized;
+        self
+    }
+
+    /// Only use vertices of the given labels.
+    pub fn vertex_labels<I, S>(mut self, vertex_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.vertex_labels = labels(vertex_labels);
+        self
+    }
+
+    /// Only follow edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, edge_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.edge_labels = labels(edge_labels);
+        self
+    }
+
+    fn check(&self) -> Result<()> {
+        match self.pivots {
+            Some(0) => Err(Error::invalid_parameter()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Compute the betweenness of each vertex seen by `txn`.
+    ///
+    /// Use [`VertexValues::top_k`] to select the vertices of the highest scores.
+    ///
+    /// # Errors
+    /// If the number of pivots is 0 or a label does not exist, an error is returned.
+    pub fn run<T: TxnRead>(&self, txn: &T) -> Result<VertexValues<f64>> {
+        self.check()?;
+        let topology = self.filter.load(txn)?;
+        let scores = self.scores(&topology, None)?;
+        Ok(VertexValues::new(topology.vids, scores))
+    }
+
+    /// Compute as [`Betweenness::run`], where the graph is loaded and the searches run
+    /// by the workers of `parallel`.
+    ///
+    /// # Errors
+    /// See [`Betweenness::run`].
+    pub fn par_run(&self, parallel: &Parallel<'_>) -> Result<VertexValues<f64>> {
+        self.check()?;
+        let topology = self.filter.par_load(parallel)?;
+        let scores = self.scores(&topology, Some(parallel))?;
+        Ok(VertexValues::new(topology.vids, scores))
+    }
+
+    fn scores(&self, topology: &Topology, parallel: Option<&Parallel<'_>>) -> Result<Vec<f64>> {
+        let n = topology.num_vertices();
+        let oriented = topology.oriented(self.direction);
+        let sources = match self.pivots {
+            Some(pivots) => Rng::new(self.seed).sample(n, pivots),
+            None => (0..n).collect(),
+        };
+        let partials = for_sources(
+            parallel,
+            &sources,
+            || (vec![0.0; n], Bfs::new(n)),
+            |(scores, bfs), s| bfs.accumulate(&oriented, s, scores),
+        )?;
+        let mut scores = vec![0.0; n];
+        for (partial, _) in partials {
+            scores.iter_mut().zip(partial).for_each(|(s, p)| *s += p);
+        }
+        let mut scale = if sources.is_empty() {
+            1.0
+        } else {
+            n as f64 / sources.len() as f64
+        };
+        if self.normalized && n > 2 {
+            scale /= ((n - 1) * (n - 2)) as f64;
+        } else if self.direction == Direction::Both {
+            // each unordered pair is counted from both ends
+            scale /= 2.0;
+        }
+        scores.iter_mut().for_each(|s| *s *= scale);
+        Ok(scores)
+    }
+}
+
+// Call `f` on each of `sources` by the workers of `parallel`, or by this thread if `None`,
+// where each worker folds into the state created by `init`.
+fn for_sources<S, I, F>(
+    parallel: Option<&Parallel<'_>>,
+    sources: &[usize],
+    init: I,
+    f: F,
+) -> Result<Vec<S>>
+where
+    S: Send,
+    I: Fn() -> S + Sync,
+    F: Fn(&mut S, usize) + Sync,
+{
+    let parallel = match parallel {
+        Some(parallel) => parallel,
+        None => {
+            let mut state = init();
+            sources.iter().for_each(|&s| f(&mut state, s));
+            return Ok(vec![state]);
+        }
+    };
+    let units = Units::new(sources.len(), parallel.num_workers() * UNITS_PER_WORKER);
+    parallel.run(|_, stop| {
+        let mut state = init();
+        while let Some(unit) = units.claim() {
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            sources[unit.start..unit.end]
+                .iter()
+                .for_each(|&s| f(&mut state, s));
+        }
+        Ok(state)
+    })
+}
+
+// The state of breadth-first searches, reused across sources.
+struct Bfs {
+    dist: Vec<usize>,
+    // the number of shortest paths from the source
+    sigma: Vec<f64>,
+    // the dependency of the source on each vertex
+    delta: Vec<f64>,
+    // the vertices visited in order
+    order: Vec<usize>,
+    queue: VecDeque<usize>,
+}
+
+impl Bfs {
+    fn new(n: usize) -> Self {
+        Bfs {
+            dist: vec![usize::MAX; n],
+            sigma: vec![0.0; n],
+            delta: vec![0.0; n],
+            order: vec![],
+            queue: VecDeque::new(),
+        }
+    }
+
+    fn run(&mut self, topology: &Topology, source: usize, count_paths: bool) {
+        for &v in &self.order {
+            self.dist[v] = usize::MAX;
+            self.sigma[v] = 0.0;
+            self.delta[v] = 0.0;
+        }
+        self.order.clear();
+        self.dist[source] = 0;
+        self.sigma[source] = 1.0;
+        self.queue.push_back(source);
+        while let Some(v) = self.queue.pop_front() {
+            self.order.push(v);
+            for &w in topology.out(v) {
+                if self.dist[w] == usize::MAX {
+                    self.dist[w] = self.dist[v] + 1;
+                    self.queue.push_back(w);
+                }
+                if count_paths && self.dist[w] == self.dist[v] + 1 {
+                    self.sigma[w] += self.sigma[v];
+                }
+            }
+        }
+    }
+
+    fn closeness(&mut self, topology: &Topology, source: usize) -> f64 {
+        self.run(topology, source, false);
+        let n = topology.num_vertices();
+        let reached = (self.order.len() - 1) as f64;
+        let total: usize = self.order.iter().map(|&v| self.dist[v]).sum();
+        if total == 0 {
+            return 0.0;
+        }
+        reached / (n - 1) as f64 * reached / total as f64
+    }
+
+    // Add the dependencies of `source` on the other vertices to `scores`.
+    fn accumulate(&mut self, topology: &Topology, source: usize, scores: &mut [f64]) {
+        self.run(topology, source, true);
+        let Bfs {
+            dist,
+            sigma,
+            delta,
+            order,
+            ..
+        } = self;
+        // the vertices farther from the source come first
+        for &v in order.iter().rev() {
+            for &w in topology.out(v) {
+                if dist[w] == dist[v] + 1 {
+                    delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+                }
+            }
+            if v != source {
+                scores[v] += delta[v];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Betweenness, Closeness};
+    use crate::{algo::Topology, traverse::Direction};
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    // 0 -> 1 -> 2 -> 3, 0 -> 4 -> 3
+    fn diamond() -> Topology {
+        Topology::from_edges(
+            5,
+            &[
+                (0, 1, 1.0),
+                (1, 2, 1.0),
+                (2, 3, 1.0),
+                (0, 4, 1.0),
+                (4, 3, 1.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_closeness() {
+        let path = Topology::from_edges(3, &[(0, 1, 1.0), (1, 2, 1.0)]);
+        let scores = Closeness::new().scores(&path, None).unwrap();
+        assert_close(&scores, &[2.0 / 3.0, 0.5, 0.0]);
+        let scores = Closeness::new()
+            .direction(Direction::Both)
+            .scores(&path, None)
+            .unwrap();
+        assert_close(&scores, &[2.0 / 3.0, 1.0, 2.0 / 3.0]);
+
+        // sampling all vertices is exact
+        let exact = Closeness::new().scores(&diamond(), None).unwrap();
+        let sampled = Closeness::new().pivots(5).scores(&diamond(), None).unwrap();
+        assert_close(&sampled, &exact);
+        let top = Closeness::new().top(&diamond(), None, 2).unwrap();
+        assert_eq!(top[0].0, 0);
+        assert_eq!(top.len(), 2);
+    }
+
+    #[test]
+    fn test_betweenness() {
+        let scores = Betweenness::new().scores(&diamond(), None).unwrap();
+        // 0 -> 2 goes through 1, 0 -> 3 through 4 and 1 -> 3 through 2
+        assert_close(&scores, &[0.0, 1.0, 1.0, 0.0, 1.0]);
+
+        let star = Topology::from_edges(4, &[(0, 1, 1.0), (0, 2, 1.0), (0, 3, 1.0)]);
+        let undirected = Betweenness::new().direction(Direction::Both);
+        assert_close(
+            &undirected.clone().scores(&star, None).unwrap(),
+            &[3.0, 0.0, 0.0, 0.0],
+        );
+        assert_close(
+            &undirected.normalized(true).scores(&star, None).unwrap(),
+            &[1.0, 0.0, 0.0, 0.0],
+        );
+
+        let exact = Betweenness::new().scores(&diamond(), None).unwrap();
+        let sampled = Betweenness::new()
+            .pivots(10)
+            .scores(&diamond(), None)
+            .unwrap();
+        assert_close(&sampled, &exact);
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{cursor::VertexCursor, txn::TxnRead, Result};
+
+use super::{labels, Filter, Topology, VertexValues};
+
+/// Count triangles of all vertices.
+pub fn triangle_count<T: TxnRead>(txn: &T) -> Result<Triangles> {
+    Cohesion::new().triangles(txn)
+}
+
+/// Compute the local clustering coefficients of all vertices.
+pub fn clustering_coefficient<T: TxnRead>(txn: &T) -> Result<VertexValues<f64>> {
+    Cohesion::new().clustering(txn)
+}
+
+/// Compute the core numbers of all vertices.
+pub fn core_numbers<T: TxnRead>(txn: &T) -> Result<VertexValues<usize>> {
+    Cohesion::new().core_numbers(txn)
+}
+
+/// The triangles counted by [`Cohesion::triangles`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Triangles {
+    /// The number of triangles in the graph.
+    pub total: u64,
+    /// The number of triangles each vertex is in.
+    pub per_vertex: VertexValues<u64>,
+}
+
+/// `Cohesion` computes how tightly the neighborhoods of vertices are knit.
+///
+/// Edges are treated as undirected, where self loops and multiple edges between two
+/// vertices are ignored. Neighbors are read by
+/// [`VertexCursor::associated_edges_dst_vids`] and
+/// [`VertexCursor::associated_edges_src_vids`], so edges of all labels are used.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{algo::Cohesion, db::OpenOptions};
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let cohesion = Cohesion::new().vertex_labels(["Account"]);
+/// let triangles = cohesion.triangles(&ro_txn)?;
+/// let dense_core = cohesion.k_core(&ro_txn, 10)?;
+/// println!("{} triangles, {} vertices in 10-core", triangles.total, dense_core.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cohesion {
+    filter: Filter,
+}
+
+impl Cohesion {
+    /// Create with all vertices.
+    pub fn new() -> Self {
+        Cohesion {
+            filter: Filter::default(),
+        }
+    }
+
+    /// Only use vertices of the given labels.
+    pub fn vertex_labels<I, S>(mut self, vertex_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.vertex_labels = labels(vertex_labels);
+        self
+    }
+
+    // Load the neighbors of each vertex, sorted and deduplicated.
+    fn load<T: TxnRead>(&self, txn: &T) -> Result<Topology> {
+        let lids = self.filter.lids(txn)?;
+        let (mut vids, mut neighbors) = (vec![], vec![]);
+        let mut vcur = txn.vertex_cur()?;
+        vcur.seek(0, true)?;
+        while vcur.is_valid() {
+            if lids
+                .vertex
+                .as_ref()
+                .map_or(Ok(true), |lids| vcur.lid().map(|lid| lids.contains(&lid)))?
+            {
+                vids.push(vcur.id()?);
+                let mut vertex_neighbors = vcur.associated_edges_dst_vids(usize::MAX)?.1;
+                vertex_neighbors.append(&mut vcur.associated_edges_src_vids(usize::MAX)?.1);
+                neighbors.push(vertex_neighbors);
+            }
+            vcur.seek_to_next()?;
+        }
+        Ok(simple(vids, neighbors))
+    }
+
+    /// Count the triangles in the graph seen by `txn`.
+    ///
+    /// # Errors
+    /// If a label does not exist, an error is returned.
+    pub fn triangles<T: TxnRead>(&self, txn: &T) -> Result<Triangles> {
+        let topology = self.load(txn)?;
+        let (total, per_vertex) = count_triangles(&topology);
+        Ok(Triangles {
+            total,
+            per_vertex: VertexValues::new(topology.vids, per_vertex),
+        })
+    }
+
+    /// Compute the local clustering coefficient of each vertex, the fraction of pairs of
+    /// its neighbors which are adjacent. It is 0 for vertices of less than 2 neighbors.
+    ///
+    /// # Errors
+    /// If a label does not exist, an error is returned.
+    pub fn clustering<T: TxnRead>(&self, txn: &T) -> Result<VertexValues<f64>> {
+        let topology = self.load(txn)?;
+        let coefficients = local_clustering(&topology);
+        Ok(VertexValues::new(topology.vids, coefficients))
+    }
+
+    /// Compute the core number of each vertex, the largest `k` such that the vertex is
+    /// in the k-core, the maximal subgraph where each vertex has at least `k` neighbors.
+    ///
+    /// # Errors
+    /// If a label does not exist, an error is returned.
+    pub fn core_numbers<T: TxnRead>(&self, txn: &T) -> Result<VertexValues<usize>> {
+        let topology = self.load(txn)?;
+        let cores = peel(&topology);
+        Ok(VertexValues::new(topology.vids, cores))
+    }
+
+    /// Get the vids of the k-core in ascending order.
+    ///
+    /// # Errors
+    /// If a label does not exist, an error is returned.
+    pub fn k_core<T: TxnRead>(&self, txn: &T, k: usize) -> Result<Vec<i64>> {
+        Ok(self
+            .core_numbers(txn)?
+            .into_iter()
+            .filter(|&(_, core)| core >= k)
+            .map(|(vid, _)| vid)
+            .collect())
+    }
+}
+
+// Build from the neighbor vids of each vertex of `vids`, dropping self loops, duplicates
+// and the neighbors not in `vids`.
+fn simple(vids: Vec<i64>, neighbors: Vec<Vec<i64>>) -> Topology {
+    let mut offsets = Vec::with_capacity(vids.len() + 1);
+    offsets.push(0);
+    let mut targets = vec![];
+    for (pos, vertex_neighbors) in neighbors.into_iter().enumerate() {
+        let mut positions: Vec<_> = vertex_neighbors
+            .into_iter()
+            .filter_map(|vid| vids.binary_search(&vid).ok())
+            .filter(|&neighbor| neighbor != pos)
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        targets.append(&mut positions);
+        offsets.push(targets.len());
+    }
+    Topology {
+        vids,
+        offsets,
+        targets,
+        weights: None,
+    }
+}
+
+// Call `f` on each item of sorted `a` and `b` in common which is greater than `min`,
+// found by merge.
+fn for_common<F: FnMut(usize)>(a: &[usize], b: &[usize], min: usize, mut f: F) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] <= min || a[i] < b[j] {
+            i += 1;
+        } else if b[j] <= min || b[j] < a[i] {
+            j += 1;
+        } else {
+            f(a[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+}
+
+// Count each triangle `u < v < w` once from its smallest vertex.
+fn count_triangles(topology: &Topology) -> (u64, Vec<u64>) {
+    let n = topology.num_vertices();
+    let mut per_vertex = vec![0u64; n];
+    let mut total = 0;
+    for u in 0..n {
+        let neighbors = topology.out(u);
+        for &v in neighbors.iter().filter(|&&v| v > u) {
+            for_common(neighbors, topology.out(v), v, |w| {
+                per_vertex[u] += 1;
+                per_vertex[v] += 1;
+                per_vertex[w] += 1;
+                total += 1;
+            });
+        }
+    }
+    (total, per_vertex)
+}
+
+fn local_clustering(topology: &Topology) -> Vec<f64> {
+    (0..topology.num_vertices())
+        .map(|u| {
+            let neighbors = topology.out(u);
+            let degree = neighbors.len();
+            if degree < 2 {
+                return 0.0;
+            }
+            // each pair of adjacent neighbors v < w is counted from v
+            let mut links = 0;
+            for &v in neighbors {
+                for_common(neighbors, topology.out(v), v, |_| links += 1);
+            }
+            2.0 * links as f64 / (degree * (degree - 1)) as f64
+        })
+        .collect()
+}
+
+// Batagelj and Zaversnik's algorithm, which peels vertices in ascending order of degree.
+fn peel(topology: &Topology) -> Vec<usize> {
+    let n = topology.num_vertices();
+    let mut degrees: Vec<usize> = (0..n).map(|u| topology.out(u).len()).collect();
+    let max_degree = degrees.iter().copied().max().unwrap_or(0);
+    // bucket sort vertices by degree
+    let mut starts = vec![0; max_degree + 2];
+    for &d in &degrees {
+        starts[d + 1] += 1;
+    }
+    for d in 0..=max_degree {
+        starts[d + 1] += starts[d];
+    }
+    let mut order = vec![0; n];
+    let mut positions = vec![0; n];
+    let mut next = starts.clone();
+    for u in 0..n {
+        positions[u] = next[degrees[u]];
+        order[positions[u]] = u;
+        next[degrees[u]] += 1;
+    }
+    for i in 0..n {
+        let u = order[i];
+        for &v in topology.out(u) {
+            if degrees[v] > degrees[u] {
+                // move v to the front of its bucket, then shrink the bucket
+                let dv = degrees[v];
+                let (pv, pw) = (positions[v], starts[dv]);
+                let w = order[pw];
+                order.swap(pv, pw);
+                positions[v] = pw;
+                positions[w] = pv;
+                starts[dv] += 1;
+                degrees[v] -= 1;
+            }
+        }
+    }
+    degrees
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_triangles, for_common, local_clustering, peel, simple};
+
+    // A 4-clique {0, 1, 2, 3}, with 4 hanging off 3 and a triangle {4, 5, 6}.
+    fn graph() -> super::Topology {
+        let edges = [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 2),
+            (1, 3),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 6),
+            (6, 4),
+            // a self loop and a duplicate, which are ignored
+            (5, 5),
+            (1, 0),
+        ];
+        let mut neighbors = vec![vec![]; 7];
+        for (u, v) in edges {
+            neighbors[u].push(v as i64);
+            neighbors[v].push(u as i64);
+        }
+        simple((0..7).collect(), neighbors)
+    }
+
+    #[test]
+    fn test_for_common() {
+        let common = |a: &[usize], b: &[usize], min| {
+            let mut found = vec![];
+            for_common(a, b, min, |x| found.push(x));
+            found
+        };
+        assert_eq!(common(&[1, 3, 5, 7], &[2, 3, 5, 8], 0), [3, 5]);
+        assert_eq!(common(&[1, 3, 5, 7], &[2, 3, 5, 8], 3), [5]);
+        assert!(common(&[], &[1], 0).is_empty());
+    }
+
+    #[test]
+    fn test_triangles() {
+        let g = graph();
+        assert_eq!(g.out(0), [1, 2, 3]);
+        assert_eq!(g.out(5), [4, 6]);
+        let (total, per_vertex) = count_triangles(&g);
+        assert_eq!(total, 5);
+        assert_eq!(per_vertex, [3, 3, 3, 3, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_clustering() {
+        let c = local_clustering(&graph());
+        assert_eq!(c[0], 1.0);
+        // 3 has neighbors 0, 1, 2, 4, where 3 of 6 pairs are adjacent
+        assert_eq!(c[3], 0.5);
+        assert_eq!(c[4], 1.0 / 3.0);
+        assert_eq!(c[5], 1.0);
+    }
+
+    #[test]
+    fn test_peel() {
+        assert_eq!(peel(&graph()), [3, 3, 3, 3, 2, 2, 2]);
+        let path = simple((0..3).collect(), vec![vec![1], vec![0, 2], vec![1]]);
+        assert_eq!(peel(&path), [1, 1, 1]);
+    }
+}
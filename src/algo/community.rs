@@ -0,0 +1,484 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{txn::TxnRead, Error, Result};
+
+use super::{check_weights, labels, Components, Filter, Topology};
+
+/// Detect communities with the default [`LabelPropagation`] settings.
+pub fn label_propagation<T: TxnRead>(txn: &T) -> Result<Communities> {
+    LabelPropagation::new().run(txn)
+}
+
+/// Detect communities with the default [`Louvain`] settings.
+pub fn louvain<T: TxnRead>(txn: &T) -> Result<Communities> {
+    Louvain::new().run(txn)
+}
+
+/// The communities detected by [`LabelPropagation`] or [`Louvain`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Communities {
+    /// The community of each vertex, numbered as [`Components`].
+    pub assignments: Components,
+    /// The modularity of the assignments, in `[-0.5, 1]`.
+    pub modularity: f64,
+}
+
+// The undirected neighbors of each vertex with edge weights.
+type Adjacency = Vec<Vec<(usize, f64)>>;
+
+fn adjacency(topology: &Topology) -> Adjacency {
+    let undirected = topology.undirected();
+    (0..undirected.num_vertices())
+        .map(|pos| {
+            let out = undirected.out(pos).iter().copied();
+            match undirected.out_weights(pos) {
+                Some(weights) => out.zip(weights.iter().copied()).collect(),
+                None => out.map(|dst| (dst, 1.0)).collect(),
+            }
+        })
+        .collect()
+}
+
+// The modularity of `communities`, each of which is less than the number of vertices.
+fn modularity(adjacency: &Adjacency, communities: &[usize], resolution: f64) -> f64 {
+    let n = adjacency.len();
+    let (mut inner, mut totals) = (vec![0.0; n], vec![0.0; n]);
+    let mut total = 0.0;
+    for (pos, neighbors) in adjacency.iter().enumerate() {
+        let c = communities[pos];
+        for &(dst, weight) in neighbors {
+            total += weight;
+            totals[c] += weight;
+            if communities[dst] == c {
+                inner[c] += weight;
+            }
+        }
+    }
+    if total <= 0.0 {
+        return 0.0;
+    }
+    inner
+        .iter()
+        .zip(&totals)
+        .map(|(inner, tot)| inner / total - resolution * (tot / total).powi(2))
+        .sum()
+}
+
+/// `LabelPropagation` detects communities by label propagation.
+///
+/// Each vertex starts with a label of its own, then repeatedly takes the label of the
+/// largest total edge weight among its neighbors, until no label changes. Edges are
+/// followed in both directions. Ties are broken towards the current label, then the
+/// smallest label, so that the result is deterministic.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{algo::LabelPropagation, db::OpenOptions};
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let communities = LabelPropagation::new()
+///     .edge_labels(["transfer"])
+///     .weight_field("amount")
+///     .run(&ro_txn)?;
+/// println!("modularity: {}", communities.modularity);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelPropagation {
+    max_iterations: usize,
+    filter: Filter,
+}
+
+impl Default for LabelPropagation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LabelPropagation {
+    /// Create with at most 20 iterations, using edges of all labels without weight.
+    pub fn new() -> Self {
+        LabelPropagation {
+            max_iterations: 20,
+            filter: Filter::default(),
+        }
+    }
+
+    /// Set the max number of iterations.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Only use vertices of the given labels.
+    pub fn vertex_labels<I, S>(mut self, vertex_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.vertex_labels = labels(vertex_labels);
+        self
+    }
+
+    /// Only use edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, edge_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.edge_labels = labels(edge_labels);
+        self
+    }
+
+    /// Weight edges by the numeric field `field`, whose values must be finite and
+    /// non-negative.
+    pub fn weight_field(mut self, field: &str) -> Self {
+        self.filter.weight_field = Some(field.to_string());
+        self
+    }
+
+    /// Run label propagation on the graph seen by `txn`.
+    ///
+    /// # Errors
+    /// If a label does not exist, the weight field is not numeric, or an edge weight is
+    /// negative, infinite or NaN, an error is returned.
+    pub fn run<T: TxnRead>(&self, txn: &T) -> Result<Communities> {
+        let topology = self.filter.load(txn)?;
+        check_weights(&topology)?;
+        let adjacency = adjacency(&topology);
+        let labels = self.propagate(&adjacency);
+        Ok(Communities {
+            modularity: modularity(&adjacency, &labels, 1.0),
+            assignments: Components::new(topology.vids, labels),
+        })
+    }
+
+    fn propagate(&self, adjacency: &Adjacency) -> Vec<usize> {
+        let n = adjacency.len();
+        let mut labels: Vec<usize> = (0..n).collect();
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        for _ in 0..self.max_iterations {
+            let mut changed = false;
+            for pos in 0..n {
+                scores.clear();
+                for &(dst, weight) in &adjacency[pos] {
+                    if dst != pos {
+                        *scores.entry(labels[dst]).or_default() += weight;
+                    }
+                }
+                let max = match scores.values().copied().reduce(f64::max) {
+                    Some(max) => max,
+                    None => continue,
+                };
+                if scores.get(&labels[pos]) == Some(&max) {
+                    continue;
+                }
+                labels[pos] = scores
+                    .iter()
+                    .filter(|&(_, &score)| score == max)
+                    .map(|(&label, _)| label)
+                    .min()
+                    .unwrap();
+                changed = true;
+            }
+            if !changed {
+                break;
+            }
+        }
+        labels
+    }
+}
+
+/// `Louvain` detects communities by greedy modularity optimization.
+///
+/// Each level moves vertices to the neighboring communities of the largest modularity
+/// gain until the gain of a pass is below the tolerance, then merges each community into
+/// a single vertex for the next level. It stops once a level moves no vertex. Edges are
+/// followed in both directions.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{algo::Louvain, db::OpenOptions};
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let communities = Louvain::new()
+///     .resolution(0.8)
+///     .weight_field("amount")
+///     .run(&ro_txn)?;
+/// for (vid, community) in communities.assignments.ids().iter() {
+///     println!("{vid}: {community}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Louvain {
+    resolution: f64,
+    tolerance: f64,
+    max_levels: usize,
+    filter: Filter,
+}
+
+impl Default for Louvain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Louvain {
+    /// Create with resolution 1, tolerance 1e-7 and at most 10 levels, using edges
+    /// of all labels without weight.
+    pub fn new() -> Self {
+        Louvain {
+            resolution: 1.0,
+            tolerance: 1e-7,
+            max_levels: 10,
+            filter: Filter::default(),
+        }
+    }
+
+    /// Set the resolution, where a larger one leads to smaller communities.
+    pub fn resolution(mut self, resolution: f64) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Stop moving vertices in a level once the modularity gain of a pass is below
+    /// `tolerance`.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the max number of levels.
+    pub fn max_levels(mut self, max_levels: usize) -> Self {
+        self.max_levels = max_levels;
+        self
+    }
+
+    /// Only use vertices of the given labels.
+    pub fn vertex_labels<I, S>(mut self, vertex_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.vertex_labels = labels(vertex_labels);
+        self
+    }
+
+    /// Only use edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, edge_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.edge_labels = labels(edge_labels);
+        self
+    }
+
+    /// Weight edges by the numeric field `field`, whose values must be finite and
+    /// non-negative.
+    pub fn weight_field(mut self, field: &str) -> Self {
+        self.filter.weight_field = Some(field.to_string());
+        self
+    }
+
+    /// Run Louvain on the graph seen by `txn`. The modularity returned is computed
+    /// with the resolution.
+    ///
+    /// # Errors
+    /// If the resolution is not positive, the tolerance is negative, a label does not
+    /// exist or the weight field is not numeric, an error is returned. So is it if an
+    /// edge weight is negative, infinite or NaN.
+    pub fn run<T: TxnRead>(&self, txn: &T) -> Result<Communities> {
+        if !(self.resolution > 0.0 && self.tolerance >= 0.0) {
+            return Err(Error::invalid_parameter());
+        }
+        let topology = self.filter.load(txn)?;
+        check_weights(&topology)?;
+        let adjacency = adjacency(&topology);
+        let communities = self.optimize(&adjacency);
+        Ok(Communities {
+            modularity: modularity(&adjacency, &communities, self.resolution),
+            assignments: Components::new(topology.vids, communities),
+        })
+    }
+
+    fn optimize(&self, adjacency: &Adjacency) -> Vec<usize> {
+        // the vertex of the current level each original vertex is merged into
+        let mut assignments: Vec<usize> = (0..adjacency.len()).collect();
+        let mut level = adjacency.clone();
+        for _ in 0..self.max_levels {
+            let communities = match self.move_vertices(&level) {
+                Some(communities) => communities,
+                None => break,
+            };
+            // renumber communities from 0
+            let mut ids = vec![usize::MAX; level.len()];
+            let mut num_communities = 0;
+            for &c in &communities {
+                if ids[c] == usize::MAX {
+                    ids[c] = num_communities;
+                    num_communities += 1;
+                }
+            }
+            let communities: Vec<_> = communities.into_iter().map(|c| ids[c]).collect();
+            for a in &mut assignments {
+                *a = communities[*a];
+            }
+            level = aggregate(&level, &communities, num_communities);
+        }
+        assignments
+    }
+
+    // Move vertices between communities, returning `None` if no vertex is moved.
+    fn move_vertices(&self, graph: &Adjacency) -> Option<Vec<usize>> {
+        let n = graph.len();
+        let degrees: Vec<f64> = graph
+            .iter()
+            .map(|neighbors| neighbors.iter().map(|&(_, w)| w).sum())
+            .collect();
+        let total: f64 = degrees.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut communities: Vec<usize> = (0..n).collect();
+        let mut totals = degrees.clone();
+        // the weights from the vertex being moved to each neighboring community
+        let mut links = vec![0.0; n];
+        let mut touched = vec![];
+        let mut moved = false;
+        loop {
+            let mut gain = 0.0;
+            for pos in 0..n {
+                let current = communities[pos];
+                for &(dst, weight) in &graph[pos] {
+                    if dst != pos {
+                        if links[communities[dst]] == 0.0 {
+                            touched.push(communities[dst]);
+                        }
+                        links[communities[dst]] += weight;
+                    }
+                }
+                totals[current] -= degrees[pos];
+                let gain_of =
+                    |c: usize| links[c] - self.resolution * totals[c] * degrees[pos] / total;
+                let stay = gain_of(current);
+                let (mut best, mut best_gain) = (current, stay);
+                for &c in &touched {
+                    if gain_of(c) > best_gain {
+                        (best, best_gain) = (c, gain_of(c));
+                    }
+                }
+                totals[best] += degrees[pos];
+                if best != current {
+                    communities[pos] = best;
+                    gain += 2.0 * (best_gain - stay) / total;
+                    moved = true;
+                }
+                for c in touched.drain(..) {
+                    links[c] = 0.0;
+                }
+            }
+            if gain <= self.tolerance {
+                break;
+            }
+        }
+        moved.then_some(communities)
+    }
+}
+
+// Merge each community into a vertex, where the edges inside become a self loop.
+fn aggregate(graph: &Adjacency, communities: &[usize], num_communities: usize) -> Adjacency {
+    let mut merged = vec![BTreeMap::new(); num_communities];
+    for (pos, neighbors) in graph.iter().enumerate() {
+        for &(dst, weight) in neighbors {
+            *merged[communities[pos]]
+                .entry(communities[dst])
+                .or_insert(0.0) += weight;
+        }
+    }
+    merged
+        .into_iter()
+        .map(|neighbors| neighbors.into_iter().collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adjacency, modularity, LabelPropagation, Louvain};
+    use crate::algo::Topology;
+
+    // Two triangles {0, 1, 2} and {3, 4, 5} bridged by 2 - 3 of weight `bridge`.
+    fn triangles(bridge: f64) -> Topology {
+        Topology::from_edges(
+            6,
+            &[
+                (0, 1, 1.0),
+                (1, 2, 1.0),
+                (2, 0, 1.0),
+                (3, 4, 1.0),
+                (4, 5, 1.0),
+                (5, 3, 1.0),
+                (2, 3, bridge),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_modularity() {
+        let graph = adjacency(&triangles(1.0));
+        let q = modularity(&graph, &[0, 0, 0, 3, 3, 3], 1.0);
+        assert!((q - 5.0 / 14.0).abs() < 1e-9);
+        assert_eq!(modularity(&graph, &[0; 6], 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_label_propagation() {
+        let graph = adjacency(&triangles(0.1));
+        let labels = LabelPropagation::new().propagate(&graph);
+        assert_eq!(labels[0], labels[2]);
+        assert_eq!(labels[3], labels[5]);
+        assert_ne!(labels[2], labels[3]);
+        // no edges, no change
+        let isolated = adjacency(&Topology::from_edges(2, &[]));
+        assert_eq!(LabelPropagation::new().propagate(&isolated), [0, 1]);
+    }
+
+    #[test]
+    fn test_louvain() {
+        let graph = adjacency(&triangles(1.0));
+        let communities = Louvain::new().optimize(&graph);
+        assert_eq!(communities[0], communities[1]);
+        assert_eq!(communities[0], communities[2]);
+        assert_eq!(communities[3], communities[4]);
+        assert_eq!(communities[3], communities[5]);
+        assert_ne!(communities[0], communities[3]);
+        assert!((modularity(&graph, &communities, 1.0) - 5.0 / 14.0).abs() < 1e-9);
+
+        // a tiny resolution merges everything
+        let communities = Louvain::new().resolution(1e-3).optimize(&graph);
+        assert!(communities.iter().all(|&c| c == communities[0]));
+    }
+}
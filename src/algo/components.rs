@@ -0,0 +1,367 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+
+use crate::{txn::TxnRead, Result};
+
+use super::{labels, Filter, Topology, VertexValues};
+
+/// Find weakly connected components of all vertices and edges.
+pub fn wcc<T: TxnRead>(txn: &T) -> Result<Components> {
+    Connectivity::new().wcc(txn)
+}
+
+/// Find strongly connected components of all vertices and edges.
+pub fn scc<T: TxnRead>(txn: &T) -> Result<Components> {
+    Connectivity::new().scc(txn)
+}
+
+/// Sort all vertices topologically along all edges.
+pub fn toposort<T: TxnRead>(txn: &T) -> Result<TopoOrder> {
+    Connectivity::new().toposort(txn)
+}
+
+/// `Connectivity` runs connectivity algorithms on the vertices and edges of given labels.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{algo::Connectivity, db::OpenOptions};
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let order = Connectivity::new()
+///     .vertex_labels(["Package"])
+///     .edge_labels(["depends_on"])
+///     .toposort(&ro_txn)?;
+/// for cycle in &order.cycles {
+///     println!("dependency cycle: {cycle:?}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Connectivity {
+    filter: Filter,
+}
+
+impl Connectivity {
+    /// Create with all vertices and edges.
+    pub fn new() -> Self {
+        Connectivity {
+            filter: Filter::default(),
+        }
+    }
+
+    /// Only use vertices of the given labels.
+    pub fn vertex_labels<I, S>(mut self, vertex_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.vertex_labels = labels(vertex_labels);
+        self
+    }
+
+    /// Only use edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, edge_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.edge_labels = labels(edge_labels);
+        self
+    }
+
+    /// Find weakly connected components, where edge directions are ignored.
+    ///
+    /// # Errors
+    /// If a label does not exist, an error is returned.
+    pub fn wcc<T: TxnRead>(&self, txn: &T) -> Result<Components> {
+        let topology = self.filter.load(txn)?;
+        let roots = weakly_connected(&topology);
+        Ok(Components::new(topology.vids, roots))
+    }
+
+    /// Find strongly connected components.
+    ///
+    /// # Errors
+    /// If a label does not exist, an error is returned.
+    pub fn scc<T: TxnRead>(&self, txn: &T) -> Result<Components> {
+        let topology = self.filter.load(txn)?;
+        let roots = strongly_connected(&topology);
+        Ok(Components::new(topology.vids, roots))
+    }
+
+    /// Sort vertices topologically, so that the src of each edge comes before its dst.
+    ///
+    /// # Errors
+    /// If a label does not exist, an error is returned.
+    pub fn toposort<T: TxnRead>(&self, txn: &T) -> Result<TopoOrder> {
+        let topology = self.filter.load(txn)?;
+        Ok(topological(&topology))
+    }
+}
+
+/// The components found by [`Connectivity::wcc`] or [`Connectivity::scc`].
+///
+/// Components are numbered from 0 in ascending order of their smallest vids.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Components {
+    ids: VertexValues<usize>,
+    sizes: Vec<usize>,
+}
+
+impl Components {
+    // `roots[pos]`, less than the number of vertices, identifies the component of `pos`.
+    pub(crate) fn new(vids: Vec<i64>, roots: Vec<usize>) -> Self {
+        let mut id_of_root = vec![usize::MAX; roots.len()];
+        let mut sizes = vec![];
+        let ids = roots
+            .into_iter()
+            .map(|root| {
+                if id_of_root[root] == usize::MAX {
+                    id_of_root[root] = sizes.len();
+                    sizes.push(0);
+                }
+                sizes[id_of_root[root]] += 1;
+                id_of_root[root]
+            })
+            .collect();
+        Components {
+            ids: VertexValues::new(vids, ids),
+            sizes,
+        }
+    }
+
+    /// Get the component id of each vertex.
+    pub fn ids(&self) -> &VertexValues<usize> {
+        &self.ids
+    }
+
+    /// Get the component id of vertex `vid`.
+    pub fn component(&self, vid: i64) -> Option<usize> {
+        self.ids.get(vid).copied()
+    }
+
+    /// Get the number of components.
+    pub fn num_components(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Get the size of each component, indexed by component id.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// Get the id and size of the largest component, the one with the smallest id
+    /// if there are many.
+    pub fn largest(&self) -> Option<(usize, usize)> {
+        self.sizes
+            .iter()
+            .copied()
+            .enumerate()
+            .fold(None, |largest, (id, size)| match largest {
+                Some((_, max)) if max >= size => largest,
+                _ => Some((id, size)),
+            })
+    }
+
+    /// Get the vids of component `id` in ascending order.
+    pub fn members(&self, id: usize) -> Vec<i64> {
+        self.ids
+            .iter()
+            .filter(|&(_, &c)| c == id)
+            .map(|(vid, _)| vid)
+            .collect()
+    }
+}
+
+/// The result of [`Connectivity::toposort`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopoOrder {
+    /// The vertices in topological order. Vertices in a cycle, or reachable from a cycle,
+    /// can not be sorted and are left out.
+    pub order: Vec<i64>,
+    /// The cycles, each of which is a strongly connected component of more than one vertex
+    /// or a vertex with a self loop, in ascending order of vid.
+    pub cycles: Vec<Vec<i64>>,
+}
+
+impl TopoOrder {
+    /// Query if the graph is acyclic, i.e. all vertices are sorted.
+    pub fn is_dag(&self) -> bool {
+        self.cycles.is_empty()
+    }
+}
+
+fn find(parents: &mut [usize], mut pos: usize) -> usize {
+    while parents[pos] != pos {
+        parents[pos] = parents[parents[pos]];
+        pos = parents[pos];
+    }
+    pos
+}
+
+// Union-find, with the smallest position as the root of each set.
+pub(crate) fn weakly_connected(topology: &Topology) -> Vec<usize> {
+    let n = topology.num_vertices();
+    let mut parents: Vec<usize> = (0..n).collect();
+    for src in 0..n {
+        for &dst in topology.out(src) {
+            let (a, b) = (find(&mut parents, src), find(&mut parents, dst));
+            if a != b {
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+    }
+    (0..n).map(|pos| find(&mut parents, pos)).collect()
+}
+
+// Iterative Tarjan's algorithm, with the smallest position as the root of each component.
+pub(crate) fn strongly_connected(topology: &Topology) -> Vec<usize> {
+    const UNVISITED: usize = usize::MAX;
+    let n = topology.num_vertices();
+    let mut index = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = vec![];
+    let mut roots = vec![UNVISITED; n];
+    let mut next_index = 0;
+    // (vertex, position of the next out neighbor to visit)
+    let mut frames: Vec<(usize, usize)> = vec![];
+    for start in 0..n {
+        if index[start] != UNVISITED {
+            continue;
+        }
+        frames.push((start, 0));
+        while let Some(&mut (pos, ref mut next)) = frames.last_mut() {
+            if *next == 0 && index[pos] == UNVISITED {
+                index[pos] = next_index;
+                low[pos] = next_index;
+                next_index += 1;
+                stack.push(pos);
+                on_stack[pos] = true;
+            }
+            if let Some(&dst) = topology.out(pos).get(*next) {
+                *next += 1;
+                if index[dst] == UNVISITED {
+                    frames.push((dst, 0));
+                } else if on_stack[dst] {
+                    low[pos] = low[pos].min(index[dst]);
+                }
+                continue;
+            }
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low[parent] = low[parent].min(low[pos]);
+            }
+            if low[pos] == index[pos] {
+                let split = stack.iter().rposition(|&p| p == pos).unwrap();
+                let members = stack.split_off(split);
+                let root = *members.iter().min().unwrap();
+                for member in members {
+                    on_stack[member] = false;
+                    roots[member] = root;
+                }
+            }
+        }
+    }
+    roots
+}
+
+// Kahn's algorithm, where the vertices left are in or after cycles.
+pub(crate) fn topological(topology: &Topology) -> TopoOrder {
+    let n = topology.num_vertices();
+    let mut in_degrees = vec![0; n];
+    for src in 0..n {
+        for &dst in topology.out(src) {
+            in_degrees[dst] += 1;
+        }
+    }
+    let mut ready: VecDeque<_> = (0..n).filter(|&pos| in_degrees[pos] == 0).collect();
+    let mut order = Vec::with_capacity(n);
+    while let Some(pos) = ready.pop_front() {
+        order.push(topology.vids[pos]);
+        for &dst in topology.out(pos) {
+            in_degrees[dst] -= 1;
+            if in_degrees[dst] == 0 {
+                ready.push_back(dst);
+            }
+        }
+    }
+
+    let mut cycles = vec![];
+    if order.len() < n {
+        let roots = strongly_connected(topology);
+        let mut members = vec![vec![]; n];
+        for (pos, &root) in roots.iter().enumerate() {
+            members[root].push(pos);
+        }
+        for (root, members) in members.into_iter().enumerate() {
+            if members.len() > 1 || topology.out(root).contains(&root) {
+                cycles.push(members.iter().map(|&pos| topology.vids[pos]).collect());
+            }
+        }
+    }
+    TopoOrder { order, cycles }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{strongly_connected, topological, weakly_connected, Components};
+    use crate::algo::Topology;
+
+    fn topology(num_vertices: usize, edges: &[(usize, usize)]) -> Topology {
+        let edges: Vec<_> = edges.iter().map(|&(src, dst)| (src, dst, 1.0)).collect();
+        Topology::from_edges(num_vertices, &edges)
+    }
+
+    #[test]
+    fn test_weakly_connected() {
+        // {0, 1, 2}, {3, 4}, {5}
+        let t = topology(6, &[(1, 0), (2, 1), (4, 3)]);
+        let components = Components::new(t.vids.clone(), weakly_connected(&t));
+        assert_eq!(components.ids().values(), [0, 0, 0, 1, 1, 2]);
+        assert_eq!(components.sizes(), [3, 2, 1]);
+        assert_eq!(components.largest(), Some((0, 3)));
+        assert_eq!(components.members(1), [3, 4]);
+    }
+
+    #[test]
+    fn test_strongly_connected() {
+        // {0, 1, 2} -> {3} -> {4, 5}
+        let t = topology(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 4)]);
+        assert_eq!(strongly_connected(&t), [0, 0, 0, 3, 4, 4]);
+        let components = Components::new(t.vids.clone(), strongly_connected(&t));
+        assert_eq!(components.sizes(), [3, 1, 2]);
+    }
+
+    #[test]
+    fn test_topological() {
+        let dag = topology(4, &[(3, 1), (1, 0), (3, 2), (2, 0)]);
+        let order = topological(&dag);
+        assert!(order.is_dag());
+        assert_eq!(order.order, [3, 1, 2, 0]);
+
+        // 0 -> 1 <-> 2 -> 3, 4 -> 4
+        let cyclic = topology(5, &[(0, 1), (1, 2), (2, 1), (2, 3), (4, 4)]);
+        let order = topological(&cyclic);
+        assert!(!order.is_dag());
+        assert_eq!(order.order, [0]);
+        assert_eq!(order.cycles, [vec![1, 2], vec![4]]);
+    }
+}
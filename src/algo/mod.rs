@@ -0,0 +1,517 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Graph algorithms running against a transaction.
+//!
+//! The algorithms read the vertices and edges they need through cursors of the given
+//! transaction, then compute in memory. Results are returned as [`VertexValues`],
+//! which can be turned into a `HashMap` or a dense vector indexed by vid.
+
+mod centrality;
+mod cohesion;
+mod community;
+mod components;
+mod pagerank;
+mod paths;
+mod projection;
+mod sampling;
+pub mod similarity;
+mod walk;
+mod write_back;
+
+#[doc(inline)]
+pub use centrality::{
+    betweenness_centrality, closeness_centrality, degree_centrality, Betweenness, Closeness, Degree,
+};
+#[doc(inline)]
+pub use cohesion::{clustering_coefficient, core_numbers, triangle_count, Cohesion, Triangles};
+#[doc(inline)]
+pub use community::{label_propagation, louvain, Communities, LabelPropagation, Louvain};
+#[doc(inline)]
+pub use components::{scc, toposort, wcc, Components, Connectivity, TopoOrder};
+#[doc(inline)]
+pub use pagerank::{pagerank, PageRank};
+#[doc(inline)]
+pub use paths::{shortest_path, Path, ShortestPath};
+#[doc(inline)]
+pub use projection::{Projection, ProjectionOptions};
+#[doc(inline)]
+pub use sampling::{NeighborSampler, Neighborhood};
+#[doc(inline)]
+pub use walk::{RandomWalk, Walks};
+#[doc(inline)]
+pub use write_back::{write_back, WriteBack, WriteProgress};
+
+use std::{
+    borrow::Cow,
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{
+    field::{type_mismatch, FieldData, FieldType},
+    parallel::Parallel,
+    traverse::Direction,
+    txn::TxnRead,
+    Error, Result,
+};
+
+/// The values computed for each vertex by an algorithm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VertexValues<T> {
+    // sorted ascending
+    vids: Vec<i64>,
+    values: Vec<T>,
+}
+
+impl<T> VertexValues<T> {
+    pub(crate) fn new(vids: Vec<i64>, values: Vec<T>) -> Self {
+        debug_assert_eq!(vids.len(), values.len());
+        VertexValues { vids, values }
+    }
+
+    /// Get the number of vertices.
+    pub fn len(&self) -> usize {
+        self.vids.len()
+    }
+
+    /// Query if there is no vertex.
+    pub fn is_empty(&self) -> bool {
+        self.vids.is_empty()
+    }
+
+    /// Get the value of vertex `vid`.
+    pub fn get(&self, vid: i64) -> Option<&T> {
+        self.vids
+            .binary_search(&vid)
+            .ok()
+            .map(|pos| &self.values[pos])
+    }
+
+    /// Get the vids in ascending order.
+    pub fn vids(&self) -> &[i64] {
+        &self.vids
+    }
+
+    /// Get the values in the order of [`VertexValues::vids`].
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Iterate over `(vid, value)` in ascending order of vid.
+    pub fn iter(&self) -> impl Iterator<Item = (i64, &T)> + '_ {
+        self.vids.iter().copied().zip(self.values.iter())
+    }
+
+    /// Convert into a `HashMap` from vid to value.
+    pub fn into_hash_map(self) -> HashMap<i64, T> {
+        self.vids.into_iter().zip(self.values).collect()
+    }
+
+    /// Convert into a dense vector indexed by vid, where vids without value are
+    /// filled with `default`.
+    pub fn into_dense(self, default: T) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let len = self.vids.last().map_or(0, |&vid| vid as usize + 1);
+        let mut dense = vec![default; len];
+        for (vid, value) in self.vids.into_iter().zip(self.values) {
+            dense[vid as usize] = value;
+        }
+        dense
+    }
+}
+
+impl VertexValues<f64> {
+    /// Get the `k` vertices of the highest values in descending order, where ties are
+    /// in ascending order of vid.
+    pub fn top_k(&self, k: usize) -> Vec<(i64, f64)> {
+        let mut top = TopK::new(k);
+        for (vid, &value) in self.iter() {
+            top.push(vid, value);
+        }
+        top.into_sorted_vec()
+    }
+}
+
+impl<T> IntoIterator for VertexValues<T> {
+    type Item = (i64, T);
+    type IntoIter = std::iter::Zip<std::vec::IntoIter<i64>, std::vec::IntoIter<T>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.vids.into_iter().zip(self.values)
+    }
+}
+
+// A score ordered by value, then by vid reversed, so that the smaller vid wins a tie.
+struct Scored {
+    vid: i64,
+    score: f64,
+}
+
+impl PartialEq for Scored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.vid.cmp(&self.vid))
+    }
+}
+
+/// Keeps the `k` highest scores pushed, without keeping the others.
+pub(crate) struct TopK {
+    k: usize,
+    heap: BinaryHeap<Reverse<Scored>>,
+}
+
+impl TopK {
+    pub(crate) fn new(k: usize) -> Self {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k.saturating_add(1).min(1 << 16)),
+        }
+    }
+
+    pub(crate) fn push(&mut self, vid: i64, score: f64) {
+        self.heap.push(Reverse(Scored { vid, score }));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    pub(crate) fn merge(&mut self, other: TopK) {
+        for Reverse(Scored { vid, score }) in other.heap {
+            self.push(vid, score);
+        }
+    }
+
+    /// The scores in descending order.
+    pub(crate) fn into_sorted_vec(self) -> Vec<(i64, f64)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(Scored { vid, score })| (vid, score))
+            .collect()
+    }
+}
+
+/// A SplitMix64 generator for sampling, which is fast and deterministic by seed.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform number in `[0, 1)`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A uniform number in `0..n`, where `n` is positive.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_f64() * n as f64) as usize % n
+    }
+
+    /// Sample `k` distinct numbers of `0..n`, or all of them if `k >= n`.
+    pub(crate) fn sample(&mut self, n: usize, k: usize) -> Vec<usize> {
+        let mut all: Vec<usize> = (0..n).collect();
+        let k = k.min(n);
+        for i in 0..k {
+            let j = i + self.below(n - i);
+            all.swap(i, j);
+        }
+        all.truncate(k);
+        all
+    }
+}
+
+// Read a numeric field as an edge weight.
+pub(crate) fn weight_of(data: FieldData) -> Result<f64> {
+    match data {
+        FieldData::Int8(v) => Ok(v as f64),
+        FieldData::Int16(v) => Ok(v as f64),
+        FieldData::Int32(v) => Ok(v as f64),
+        FieldData::Int64(v) => Ok(v as f64),
+        FieldData::Float(v) => Ok(v as f64),
+        FieldData::Double(v) => Ok(v),
+        data => Err(type_mismatch(FieldType::Double, data.ty())),
+    }
+}
+
+// Check the edge weights are finite and non-negative, for the algorithms splitting or
+// summing up them, where other weights are meaningless.
+pub(crate) fn check_weights(topology: &Topology) -> Result<()> {
+    let valid = |w: &f64| w.is_finite() && *w >= 0.0;
+    if topology.weights.iter().flatten().all(valid) {
+        Ok(())
+    } else {
+        Err(Error::invalid_parameter())
+    }
+}
+
+/// The out adjacency of the vertices, in compressed sparse row form.
+///
+/// Vertices are referred by their positions in `vids`.
+#[derive(Clone)]
+pub(crate) struct Topology {
+    // sorted ascending
+    pub(crate) vids: Vec<i64>,
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    weights: Option<Vec<f64>>,
+}
+
+/// The vertices and edges an algorithm runs on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Filter {
+    pub(crate) vertex_labels: Option<Vec<String>>,
+    pub(crate) edge_labels: Option<Vec<String>>,
+    pub(crate) weight_field: Option<String>,
+}
+
+pub(crate) fn labels<I, S>(labels: I) -> Option<Vec<String>>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    Some(labels.into_iter().map(Into::into).collect())
+}
+
+// The label ids of a `Filter`.
+struct Lids {
+    vertex: Option<Vec<u16>>,
+    edge: Option<Vec<u16>>,
+}
+
+impl Filter {
+    fn lids<T: TxnRead>(&self, txn: &T) -> Result<Lids> {
+        let label_ids = |labels: &Option<Vec<String>>, label_id: &dyn Fn(&str) -> Result<usize>| {
+            labels
+                .as_ref()
+                .map(|labels| {
+                    labels
+                        .iter()
+                        .map(|label| label_id(label).map(|lid| lid as u16))
+                        .collect::<Result<Vec<_>>>()
+                })
+                .transpose()
+        };
+        Ok(Lids {
+            vertex: label_ids(&self.vertex_labels, &|label| txn.vertex_label_id(label))?,
+            edge: label_ids(&self.edge_labels, &|label| txn.edge_label_id(label))?,
+        })
+    }
+
+    /// Load the vertices of `vertex_labels` and the out edges of `edge_labels` between them,
+    /// where `None` means all labels. Edges are weighted by the numeric field `weight_field`
+    /// if given.
+    pub(crate) fn load<T: TxnRead>(&self, txn: &T) -> Result<Topology> {
+        Projection::load(txn, self).map(Topology::from)
+    }
+
+    /// Load as [`Filter::load`], where the vertices are read by the workers of `parallel`.
+    pub(crate) fn par_load(&self, parallel: &Parallel<'_>) -> Result<Topology> {
+        Projection::par_load(parallel, self).map(Topology::from)
+    }
+}
+
+impl Topology {
+    /// Build from `(src, dst, weight)` positions of `num_vertices` vertices whose vids
+    /// are their positions.
+    #[cfg(test)]
+    pub(crate) fn from_edges(num_vertices: usize, edges: &[(usize, usize, f64)]) -> Self {
+        let mut sorted = edges.to_vec();
+        sorted.sort_by_key(|&(src, dst, _)| (src, dst));
+        let mut offsets = vec![0; num_vertices + 1];
+        for &(src, _, _) in &sorted {
+            offsets[src + 1] += 1;
+        }
+        for i in 0..num_vertices {
+            offsets[i + 1] += offsets[i];
+        }
+        Topology {
+            vids: (0..num_vertices as i64).collect(),
+            offsets,
+            targets: sorted.iter().map(|&(_, dst, _)| dst).collect(),
+            weights: Some(sorted.iter().map(|&(_, _, w)| w).collect()),
+        }
+    }
+
+    pub(crate) fn num_vertices(&self) -> usize {
+        self.vids.len()
+    }
+
+    pub(crate) fn position(&self, vid: i64) -> Option<usize> {
+        self.vids.binary_search(&vid).ok()
+    }
+
+    /// The positions of the out neighbors of the vertex at `pos`.
+    pub(crate) fn out(&self, pos: usize) -> &[usize] {
+        &self.targets[self.offsets[pos]..self.offsets[pos + 1]]
+    }
+
+    /// The weights of the out edges of the vertex at `pos`, in the order of
+    /// [`Topology::out`].
+    pub(crate) fn out_weights(&self, pos: usize) -> Option<&[f64]> {
+        self.weights
+            .as_ref()
+            .map(|weights| &weights[self.offsets[pos]..self.offsets[pos + 1]])
+    }
+
+    /// The topology where each edge goes both ways, with the neighbors of each vertex
+    /// sorted. A self loop appears twice among the neighbors of its vertex.
+    pub(crate) fn undirected(&self) -> Topology {
+        self.rebuild(true, true)
+    }
+
+    /// The topology where each edge is reversed, with the neighbors of each vertex sorted.
+    pub(crate) fn reversed(&self) -> Topology {
+        self.rebuild(false, true)
+    }
+
+    // Rebuild with edges in their directions if `forward`, and reversed if `backward`.
+    fn rebuild(&self, forward: bool, backward: bool) -> Topology {
+        let n = self.num_vertices();
+        let mut offsets = vec![0; n + 1];
+        for src in 0..n {
+            for &dst in self.out(src) {
+                if forward {
+                    offsets[src + 1] += 1;
+                }
+                if backward {
+                    offsets[dst + 1] += 1;
+                }
+            }
+        }
+        for i in 0..n {
+            offsets[i + 1] += offsets[i];
+        }
+        let mut edges = vec![(0, 0.0); offsets[n]];
+        let mut next = offsets.clone();
+        for src in 0..n {
+            for (i, &dst) in self.out(src).iter().enumerate() {
+                let weight = self.out_weights(src).map_or(1.0, |weights| weights[i]);
+                let hops = [(src, dst, forward), (dst, src, backward)];
+                for (from, to, _) in hops.into_iter().filter(|hop| hop.2) {
+                    edges[next[from]] = (to, weight);
+                    next[from] += 1;
+                }
+            }
+        }
+        for pos in 0..n {
+            edges[offsets[pos]..offsets[pos + 1]].sort_by_key(|&(to, _)| to);
+        }
+        Topology {
+            vids: self.vids.clone(),
+            offsets,
+            targets: edges.iter().map(|&(to, _)| to).collect(),
+            weights: self
+                .weights
+                .as_ref()
+                .map(|_| edges.iter().map(|&(_, w)| w).collect()),
+        }
+    }
+
+    /// The topology following edges in `direction`.
+    pub(crate) fn oriented(&self, direction: Direction) -> Cow<'_, Topology> {
+        match direction {
+            Direction::Out => Cow::Borrowed(self),
+            Direction::In => Cow::Owned(self.reversed()),
+            Direction::Both => Cow::Owned(self.undirected()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_weights, Rng, Topology, VertexValues};
+
+    #[test]
+    fn test_vertex_values() {
+        let values = VertexValues::new(vec![1, 3, 4], vec!['a', 'b', 'c']);
+        assert_eq!(values.len(), 3);
+        assert_eq!(values.get(3), Some(&'b'));
+        assert_eq!(values.get(2), None);
+        assert_eq!(
+            values.iter().collect::<Vec<_>>(),
+            [(1, &'a'), (3, &'b'), (4, &'c')]
+        );
+        assert_eq!(values.clone().into_dense('-'), ['-', 'a', '-', 'b', 'c']);
+        assert_eq!(values.into_hash_map()[&4], 'c');
+    }
+
+    #[test]
+    fn test_undirected() {
+        let t = Topology::from_edges(3, &[(0, 1, 1.0), (2, 0, 2.0), (2, 2, 3.0)]).undirected();
+        assert_eq!(t.out(0), [1, 2]);
+        assert_eq!(t.out_weights(0), Some(&[1.0, 2.0][..]));
+        assert_eq!(t.out(1), [0]);
+        assert_eq!(t.out(2), [0, 2, 2]);
+    }
+
+    #[test]
+    fn test_check_weights() {
+        let weighted = Topology::from_edges(2, &[(0, 1, 0.0), (1, 0, 2.5)]);
+        assert!(check_weights(&weighted).is_ok());
+        for weight in [-1.0, f64::INFINITY, f64::NAN] {
+            let invalid = Topology::from_edges(2, &[(0, 1, 1.0), (1, 0, weight)]);
+            assert!(check_weights(&invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn test_top_k() {
+        let values = VertexValues::new(vec![1, 2, 3, 4], vec![0.5, 2.0, 0.5, 1.0]);
+        assert_eq!(values.top_k(3), [(2, 2.0), (4, 1.0), (1, 0.5)]);
+        assert_eq!(values.top_k(10).len(), 4);
+        assert!(values.top_k(0).is_empty());
+    }
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        let mut sample = rng.sample(10, 4);
+        assert_eq!(sample.len(), 4);
+        sample.sort_unstable();
+        sample.dedup();
+        assert_eq!(sample.len(), 4);
+        assert!(sample.iter().all(|&i| i < 10));
+        assert_eq!(rng.sample(3, 5).len(), 3);
+        assert!((0..100).all(|_| rng.below(3) < 3));
+        let (mut a, mut b) = (Rng::new(1), Rng::new(1));
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+}
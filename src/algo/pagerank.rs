@@ -0,0 +1,287 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{txn::TxnRead, Error, Result};
+
+use super::{check_weights, labels, Filter, Projection, Topology, VertexValues};
+
+/// Run PageRank with the default [`PageRank`] settings.
+pub fn pagerank<T: TxnRead>(txn: &T) -> Result<VertexValues<f64>> {
+    PageRank::new().run(txn)
+}
+
+/// `PageRank` computes the PageRank score of each vertex, following out edges.
+///
+/// The scores sum to 1. The rank of dangling vertices, which have no out edges, is
+/// spread over the vertices the random surfer teleports to: all vertices, or the seeds
+/// of [`PageRank::run_personalized`].
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{algo::PageRank, db::OpenOptions};
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let scores = PageRank::new()
+///     .damping(0.9)
+///     .edge_labels(["knows"])
+///     .weight_field("weight")
+///     .run(&ro_txn)?
+///     .into_hash_map();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageRank {
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+    filter: Filter,
+}
+
+impl Default for PageRank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PageRank {
+    /// Create with damping 0.85, tolerance 1e-6 and at most 100 iterations,
+    /// following edges of all labels without weight.
+    pub fn new() -> Self {
+        PageRank {
+            damping: 0.85,
+            tolerance: 1e-6,
+            max_iterations: 100,
+            filter: Filter::default(),
+        }
+    }
+
+    /// Set the probability to follow an edge rather than to teleport.
+    pub fn damping(mut self, damping: f64) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Stop once the L1 norm of score changes in an iteration is below `tolerance`.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the max number of iterations.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Only rank vertices of the given labels.
+    pub fn vertex_labels<I, S>(mut self, vertex_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.vertex_labels = labels(vertex_labels);
+        self
+    }
+
+    /// Only follow edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, edge_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.edge_labels = labels(edge_labels);
+        self
+    }
+
+    /// Weight edges by the numeric field `field`, so that a vertex passes its score
+    /// to its out neighbors in proportion to the edge weights, which must be finite
+    /// and non-negative.
+    pub fn weight_field(mut self, field: &str) -> Self {
+        self.filter.weight_field = Some(field.to_string());
+        self
+    }
+
+    fn check(&self) -> Result<()> {
+        if (0.0..=1.0).contains(&self.damping) && self.tolerance >= 0.0 {
+            Ok(())
+        } else {
+            Err(Error::invalid_parameter())
+        }
+    }
+
+    fn load<T: TxnRead>(&self, txn: &T) -> Result<Topology> {
+        self.check()?;
+        let topology = self.filter.load(txn)?;
+        check_weights(&topology)?;
+        Ok(topology)
+    }
+
+    /// Run PageRank on the graph seen by `txn`.
+    ///
+    /// # Errors
+    /// If the damping is not in `[0, 1]`, the tolerance is negative, a label does not
+    /// exist or the weight field is not numeric, an error is returned. So is it if an
+    /// edge weight is negative, infinite or NaN.
+    pub fn run<T: TxnRead>(&self, txn: &T) -> Result<VertexValues<f64>> {
+        let topology = self.load(txn)?;
+        let n = topology.num_vertices();
+        let teleport = vec![1.0 / n as f64; n];
+        let scores = self.compute(&topology, &teleport);
+        Ok(VertexValues::new(topology.vids, scores))
+    }
+
+    /// Run PageRank on `projection`, weighting edges by its weights if any.
+    ///
+    /// The labels and the weight field of `self` are ignored, since the projection has
+    /// been built with its own.
+    ///
+    /// # Errors
+    /// If the damping is not in `[0, 1]`, the tolerance is negative, or an edge weight is
+    /// negative, infinite or NaN, an error is returned.
+    pub fn run_on(&self, projection: &Projection) -> Result<VertexValues<f64>> {
+        self.check()?;
+        let topology = Topology::from(projection);
+        check_weights(&topology)?;
+        let n = topology.num_vertices();
+        let teleport = vec![1.0 / n as f64; n];
+        let scores = self.compute(&topology, &teleport);
+        Ok(VertexValues::new(topology.vids, scores))
+    }
+
+    /// Run personalized PageRank on the graph seen by `txn`, where the random surfer
+    /// teleports to `seeds` only.
+    ///
+    /// # Errors
+    /// Besides the errors of [`PageRank::run`], if none of `seeds` exists, an error
+    /// is returned.
+    pub fn run_personalized<T, I>(&self, txn: &T, seeds: I) -> Result<VertexValues<f64>>
+    where
+        T: TxnRead,
+        I: IntoIterator<Item = i64>,
+    {
+        let topology = self.load(txn)?;
+        let mut teleport = vec![0.0; topology.num_vertices()];
+        let positions: Vec<_> = seeds
+            .into_iter()
+            .filter_map(|vid| topology.position(vid))
+            .collect();
+        if positions.is_empty() {
+            return Err(Error::invalid_parameter());
+        }
+        for &pos in &positions {
+            teleport[pos] = 1.0;
+        }
+        let num_seeds = teleport.iter().filter(|&&t| t > 0.0).count() as f64;
+        teleport.iter_mut().for_each(|t| *t /= num_seeds);
+        let scores = self.compute(&topology, &teleport);
+        Ok(VertexValues::new(topology.vids, scores))
+    }
+
+    // Power iteration, where `teleport` is the teleport distribution summing to 1.
+    fn compute(&self, topology: &Topology, teleport: &[f64]) -> Vec<f64> {
+        let n = topology.num_vertices();
+        // the sum of out weights of each vertex, 0 for dangling
+        let out_weights: Vec<f64> = (0..n)
+            .map(|pos| match topology.out_weights(pos) {
+                Some(weights) => weights.iter().sum(),
+                None => topology.out(pos).len() as f64,
+            })
+            .collect();
+        let mut scores = teleport.to_vec();
+        let mut next = vec![0.0; n];
+        for _ in 0..self.max_iterations {
+            next.iter_mut().for_each(|s| *s = 0.0);
+            let mut dangling = 0.0;
+            for pos in 0..n {
+                if out_weights[pos] <= 0.0 {
+                    dangling += scores[pos];
+                    continue;
+                }
+                let share = scores[pos] / out_weights[pos];
+                match topology.out_weights(pos) {
+                    Some(weights) => {
+                        for (&dst, &w) in topology.out(pos).iter().zip(weights) {
+                            next[dst] += share * w;
+                        }
+                    }
+                    None => {
+                        for &dst in topology.out(pos) {
+                            next[dst] += share;
+                        }
+                    }
+                }
+            }
+            let mut diff = 0.0;
+            for pos in 0..n {
+                let score = self.damping * (next[pos] + dangling * teleport[pos])
+                    + (1.0 - self.damping) * teleport[pos];
+                diff += (score - scores[pos]).abs();
+                next[pos] = score;
+            }
+            std::mem::swap(&mut scores, &mut next);
+            if diff < self.tolerance {
+                break;
+            }
+        }
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PageRank;
+    use crate::algo::Topology;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_pagerank() {
+        let pagerank = PageRank::new().tolerance(1e-10);
+
+        // a cycle is uniform
+        let cycle = Topology::from_edges(3, &[(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0)]);
+        let scores = pagerank.compute(&cycle, &[1.0 / 3.0; 3]);
+        assert_close(&scores, &[1.0 / 3.0; 3]);
+
+        // 0 -> 1 <- 2, where 1 is dangling
+        let star = Topology::from_edges(3, &[(0, 1, 1.0), (2, 1, 1.0)]);
+        let scores = pagerank.compute(&star, &[1.0 / 3.0; 3]);
+        assert!((scores.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(scores[1] > scores[0]);
+        assert_close(&[scores[0]], &[scores[2]]);
+
+        // weights split the score of 0 as 1:3
+        let weighted = Topology::from_edges(3, &[(0, 1, 1.0), (0, 2, 3.0)]);
+        let scores = PageRank::new()
+            .damping(1.0)
+            .max_iterations(1)
+            .compute(&weighted, &[1.0, 0.0, 0.0]);
+        assert_close(&scores, &[0.0, 0.25, 0.75]);
+
+        // personalized scores concentrate around the seed
+        let path = Topology::from_edges(3, &[(0, 1, 1.0), (1, 2, 1.0), (2, 1, 1.0)]);
+        let scores = pagerank.compute(&path, &[1.0, 0.0, 0.0]);
+        assert_close(&[scores[0]], &[0.15]);
+        assert!((scores.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+}
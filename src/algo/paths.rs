@@ -0,0 +1,792 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{
+    cursor::{EdgeCursor, VertexCur, VertexCursor},
+    traverse::Direction,
+    txn::TxnRead,
+    types::EdgeUid,
+    Error, Result,
+};
+
+use super::{labels, weight_of};
+
+/// Find a shortest path from `src` to `dst` following out edges of all labels.
+///
+/// See [`ShortestPath::bidirectional_bfs`].
+pub fn shortest_path<T: TxnRead>(txn: &T, src: i64, dst: i64) -> Result<Option<Path>> {
+    ShortestPath::new().bidirectional_bfs(txn, src, dst)
+}
+
+/// A path between two vertices.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Path {
+    /// The vids along the path, from the source to the target.
+    pub vids: Vec<i64>,
+    /// The edges along the path, where `edges[i]` connects `vids[i]` and `vids[i + 1]`.
+    /// An edge goes from `vids[i + 1]` to `vids[i]` if it is followed as an in edge.
+    pub edges: Vec<EdgeUid>,
+    /// The total weight of the edges, which is the number of edges if unweighted.
+    pub weight: f64,
+}
+
+impl Path {
+    /// Get the number of edges.
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Query if the path has no edge, i.e. it is a single vertex.
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Get the vid of the source, or `None` if there is no vid, which only happens to a
+    /// `Path` not found by [`ShortestPath`], e.g. a deserialized one.
+    pub fn src(&self) -> Option<i64> {
+        self.vids.first().copied()
+    }
+
+    /// Get the vid of the target, or `None` if there is no vid.
+    pub fn dst(&self) -> Option<i64> {
+        self.vids.last().copied()
+    }
+}
+
+/// Shows as `(0)-[1]->(2)<-[1]-(3)`, where edges are shown by their label ids, or as
+/// nothing if there is no vid.
+impl Display for Path {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (src, vids) = match self.vids.split_first() {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+        write!(f, "({})", src)?;
+        for (edge, vid) in self.edges.iter().zip(vids) {
+            if edge.dst == *vid {
+                write!(f, "-[{}]->({})", edge.lid, vid)?;
+            } else {
+                write!(f, "<-[{}]-({})", edge.lid, vid)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `ShortestPath` finds shortest paths between two vertices.
+///
+/// Unlike other algorithms, it reads vertices and edges through cursors as the
+/// search goes, rather than loading the whole graph up front.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{algo::ShortestPath, db::OpenOptions, traverse::Direction};
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let shortest = ShortestPath::new()
+///     .direction(Direction::Both)
+///     .edge_labels(["knows"])
+///     .max_depth(6);
+/// if let Some(path) = shortest.bidirectional_bfs(&ro_txn, 0, 42)? {
+///     println!("{path}");
+/// }
+/// for path in shortest.weight_field("distance").k_shortest(&ro_txn, 0, 42, 3)? {
+///     println!("{} {path}", path.weight);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortestPath {
+    direction: Direction,
+    edge_labels: Option<Vec<String>>,
+    max_depth: usize,
+    weight_field: Option<String>,
+}
+
+impl Default for ShortestPath {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShortestPath {
+    /// Create following out edges of all labels without weight and depth limit.
+    pub fn new() -> Self {
+        ShortestPath {
+            direction: Direction::Out,
+            edge_labels: None,
+            max_depth: usize::MAX,
+            weight_field: None,
+        }
+    }
+
+    /// Set the direction of edges to follow.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Only follow edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, edge_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.edge_labels = labels(edge_labels);
+        self
+    }
+
+    /// Only find paths of at most `max_depth` edges.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Weight edges by the numeric field `field` in [`ShortestPath::dijkstra`] and
+    /// [`ShortestPath::k_shortest`]. Without it, each edge weighs 1.
+    pub fn weight_field(mut self, field: &str) -> Self {
+        self.weight_field = Some(field.to_string());
+        self
+    }
+
+    fn expander<'t, T: TxnRead>(&self, txn: &'t T, weighted: bool) -> Result<Expander<'t>> {
+        let edge_lids = self
+            .edge_labels
+            .as_ref()
+            .map(|labels| {
+                labels
+                    .iter()
+                    .map(|label| txn.edge_label_id(label).map(|lid| lid as u16))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+        Ok(Expander {
+            vcur: txn.vertex_cur()?,
+            direction: self.direction,
+            edge_lids,
+            weight_field: self.weight_field.clone().filter(|_| weighted),
+        })
+    }
+
+    /// Find a path of the fewest edges from `src` to `dst` by breadth-first search.
+    ///
+    /// Returns `None` if `dst` can not be reached, or either vertex does not exist.
+    ///
+    /// # Errors
+    /// If an edge label does not exist, an error is returned.
+    pub fn bfs<T: TxnRead>(&self, txn: &T, src: i64, dst: i64) -> Result<Option<Path>> {
+        let mut expander = self.expander(txn, false)?;
+        if !expander.exists(src)? || !expander.exists(dst)? {
+            return Ok(None);
+        }
+        bfs(
+            &mut |vid, _| expander.hops(vid, false),
+            src,
+            dst,
+            self.max_depth,
+        )
+    }
+
+    /// Find a path of the fewest edges from `src` to `dst` by breadth-first search
+    /// from both ends, which expands the smaller frontier each time. It visits far fewer
+    /// vertices than [`ShortestPath::bfs`] on large graphs.
+    ///
+    /// Returns `None` if `dst` can not be reached, or either vertex does not exist.
+    ///
+    /// # Errors
+    /// If an edge label does not exist, an error is returned.
+    pub fn bidirectional_bfs<T: TxnRead>(
+        &self,
+        txn: &T,
+        src: i64,
+        dst: i64,
+    ) -> Result<Option<Path>> {
+        let mut expander = self.expander(txn, false)?;
+        if !expander.exists(src)? || !expander.exists(dst)? {
+            return Ok(None);
+        }
+        bidirectional_bfs(
+            &mut |vid, backward| expander.hops(vid, backward),
+            src,
+            dst,
+            self.max_depth,
+        )
+    }
+
+    /// Find a path of the least total weight from `src` to `dst` by Dijkstra's algorithm.
+    ///
+    /// Returns `None` if `dst` can not be reached, or either vertex does not exist.
+    ///
+    /// # Errors
+    /// If an edge label does not exist, the weight field is not numeric or a weight is
+    /// negative, an error is returned.
+    pub fn dijkstra<T: TxnRead>(&self, txn: &T, src: i64, dst: i64) -> Result<Option<Path>> {
+        let mut expander = self.expander(txn, true)?;
+        if !expander.exists(src)? || !expander.exists(dst)? {
+            return Ok(None);
+        }
+        let found = dijkstra(
+            &mut |vid, _| expander.hops(vid, false),
+            src,
+            dst,
+            self.max_depth,
+            &HashSet::new(),
+            &HashSet::new(),
+        )?;
+        Ok(found.map(|(path, _)| path))
+    }
+
+    /// Find the `k` loopless paths of the least total weights from `src` to `dst` by
+    /// Yen's algorithm, in ascending order of weight.
+    ///
+    /// Returns fewer paths if there are not as many.
+    ///
+    /// # Errors
+    /// See [`ShortestPath::dijkstra`].
+    pub fn k_shortest<T: TxnRead>(
+        &self,
+        txn: &T,
+        src: i64,
+        dst: i64,
+        k: usize,
+    ) -> Result<Vec<Path>> {
+        let mut expander = self.expander(txn, true)?;
+        if k == 0 || !expander.exists(src)? || !expander.exists(dst)? {
+            return Ok(vec![]);
+        }
+        k_shortest(
+            &mut |vid, _| expander.hops(vid, false),
+            src,
+            dst,
+            k,
+            self.max_depth,
+        )
+    }
+}
+
+// An edge followed from a vertex to `vid`.
+struct Hop {
+    vid: i64,
+    uid: EdgeUid,
+    weight: f64,
+}
+
+// Reads the hops from vertices through cursors.
+struct Expander<'t> {
+    vcur: VertexCur<'t>,
+    direction: Direction,
+    edge_lids: Option<Vec<u16>>,
+    weight_field: Option<String>,
+}
+
+impl<'t> Expander<'t> {
+    fn exists(&mut self, vid: i64) -> Result<bool> {
+        Ok(self.vcur.seek(vid, false)?.is_valid())
+    }
+
+    fn accept<E: EdgeCursor>(edge_lids: &Option<Vec<u16>>, ecur: &E) -> Result<bool> {
+        match edge_lids {
+            Some(lids) => Ok(lids.contains(&ecur.lid()?)),
+            None => Ok(true),
+        }
+    }
+
+    fn weight<E: EdgeCursor>(weight_field: &Option<String>, ecur: &E) -> Result<f64> {
+        match weight_field {
+            Some(field) => weight_of(ecur.field(field)?),
+            None => Ok(1.0),
+        }
+    }
+
+    // The hops from `vid`, or to `vid` if `backward`.
+    fn hops(&mut self, vid: i64, backward: bool) -> Result<Vec<Hop>> {
+        let mut hops = vec![];
+        if !self.vcur.seek(vid, false)?.is_valid() {
+            return Ok(hops);
+        }
+        let direction = if backward {
+            self.direction.reversed()
+        } else {
+            self.direction
+        };
+        if direction.has_out() {
+            let mut ecur = self.vcur.out_edge_cursor()?;
+            while ecur.is_valid() {
+                if Self::accept(&self.edge_lids, &ecur)? {
+                    hops.push(Hop {
+                        vid: ecur.dst()?,
+                        uid: ecur.uid()?,
+                        weight: Self::weight(&self.weight_field, &ecur)?,
+                    });
+                }
+                ecur.seek_to_next()?;
+            }
+        }
+        if direction.has_in() {
+            let mut ecur = self.vcur.in_edge_cursor()?;
+            while ecur.is_valid() {
+                if Self::accept(&self.edge_lids, &ecur)? {
+                    hops.push(Hop {
+                        vid: ecur.src()?,
+                        uid: ecur.uid()?,
+                        weight: Self::weight(&self.weight_field, &ecur)?,
+                    });
+                }
+                ecur.seek_to_next()?;
+            }
+        }
+        Ok(hops)
+    }
+}
+
+// For each vertex reached, its depth and the vertex and edge it is reached from.
+type Parents = HashMap<i64, (usize, Option<(i64, EdgeUid)>)>;
+
+// The path from the root of `parents` to `vid`.
+fn trace(parents: &Parents, mut vid: i64) -> (Vec<i64>, Vec<EdgeUid>) {
+    let (mut vids, mut edges) = (vec![vid], vec![]);
+    while let Some((_, Some((prev, uid)))) = parents.get(&vid) {
+        vids.push(*prev);
+        edges.push(*uid);
+        vid = *prev;
+    }
+    vids.reverse();
+    edges.reverse();
+    (vids, edges)
+}
+
+fn unweighted(vids: Vec<i64>, edges: Vec<EdgeUid>) -> Path {
+    let weight = edges.len() as f64;
+    Path {
+        vids,
+        edges,
+        weight,
+    }
+}
+
+fn bfs<H>(hops: &mut H, src: i64, dst: i64, max_depth: usize) -> Result<Option<Path>>
+where
+    H: FnMut(i64, bool) -> Result<Vec<Hop>>,
+{
+    let mut parents = Parents::new();
+    parents.insert(src, (0, None));
+    let mut frontier = vec![src];
+    let mut depth = 0;
+    while !parents.contains_key(&dst) && !frontier.is_empty() && depth < max_depth {
+        let mut next = vec![];
+        for vid in frontier {
+            for hop in hops(vid, false)? {
+                if let Entry::Vacant(entry) = parents.entry(hop.vid) {
+                    entry.insert((depth + 1, Some((vid, hop.uid))));
+                    next.push(hop.vid);
+                }
+            }
+        }
+        frontier = next;
+        depth += 1;
+    }
+    Ok(parents.contains_key(&dst).then(|| {
+        let (vids, edges) = trace(&parents, dst);
+        unweighted(vids, edges)
+    }))
+}
+
+fn bidirectional_bfs<H>(hops: &mut H, src: i64, dst: i64, max_depth: usize) -> Result<Option<Path>>
+where
+    H: FnMut(i64, bool) -> Result<Vec<Hop>>,
+{
+    if src == dst {
+        return Ok(Some(unweighted(vec![src], vec![])));
+    }
+    let (mut forward, mut backward) = (Parents::new(), Parents::new());
+    forward.insert(src, (0, None));
+    backward.insert(dst, (0, None));
+    let (mut forward_frontier, mut backward_frontier) = (vec![src], vec![dst]);
+    let mut depth = 0;
+    while depth < max_depth && !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+        let is_backward = backward_frontier.len() < forward_frontier.len();
+        let (parents, frontier, others) = if is_backward {
+            (&mut backward, &mut backward_frontier, &forward)
+        } else {
+            (&mut forward, &mut forward_frontier, &backward)
+        };
+        // the vertex where both searches meet, with the least total depth
+        let mut meet: Option<(usize, i64)> = None;
+        let mut next = vec![];
+        for &vid in frontier.iter() {
+            let level = parents[&vid].0 + 1;
+            for hop in hops(vid, is_backward)? {
+                if let Entry::Vacant(entry) = parents.entry(hop.vid) {
+                    entry.insert((level, Some((vid, hop.uid))));
+                    next.push(hop.vid);
+                    if let Some((other_level, _)) = others.get(&hop.vid) {
+                        let total = level + other_level;
+                        if meet.map_or(true, |(best, _)| total < best) {
+                            meet = Some((total, hop.vid));
+                        }
+                    }
+                }
+            }
+        }
+        if let Some((_, vid)) = meet {
+            let (mut vids, mut edges) = trace(&forward, vid);
+            let (mut rest_vids, mut rest_edges) = trace(&backward, vid);
+            rest_vids.reverse();
+            rest_edges.reverse();
+            vids.extend_from_slice(&rest_vids[1..]);
+            edges.append(&mut rest_edges);
+            return Ok(Some(unweighted(vids, edges)));
+        }
+        *frontier = next;
+        depth += 1;
+    }
+    Ok(None)
+}
+
+// A state of the search, which is a vertex and its depth if the depth is limited.
+type State = (i64, usize);
+
+// For each state reached, its weight and the state, edge and edge weight it is reached from.
+type WeightedParents = HashMap<State, (f64, Option<(State, EdgeUid, f64)>)>;
+
+struct Candidate {
+    weight: f64,
+    state: State,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Reversed for the max heap to pop the lightest first.
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .weight
+            .total_cmp(&self.weight)
+            .then_with(|| other.state.cmp(&self.state))
+    }
+}
+
+// Returns the path with the weight of each edge.
+fn dijkstra<H>(
+    hops: &mut H,
+    src: i64,
+    dst: i64,
+    max_depth: usize,
+    banned_vertices: &HashSet<i64>,
+    banned_edges: &HashSet<EdgeUid>,
+) -> Result<Option<(Path, Vec<f64>)>>
+where
+    H: FnMut(i64, bool) -> Result<Vec<Hop>>,
+{
+    let limited = max_depth != usize::MAX;
+    let mut parents = WeightedParents::new();
+    let mut settled = HashSet::new();
+    let mut heap = BinaryHeap::new();
+    parents.insert((src, 0), (0.0, None));
+    heap.push(Candidate {
+        weight: 0.0,
+        state: (src, 0),
+    });
+    while let Some(Candidate { weight, state }) = heap.pop() {
+        if !settled.insert(state) {
+            continue;
+        }
+        if state.0 == dst {
+            let (mut vids, mut edges, mut weights) = (vec![dst], vec![], vec![]);
+            let mut current = state;
+            while let Some((_, Some((prev, uid, w)))) = parents.get(&current) {
+                vids.push(prev.0);
+                edges.push(*uid);
+                weights.push(*w);
+                current = *prev;
+            }
+            vids.reverse();
+            edges.reverse();
+            weights.reverse();
+            let path = Path {
+                vids,
+                edges,
+                weight,
+            };
+            return Ok(Some((path, weights)));
+        }
+        if limited && state.1 >= max_depth {
+            continue;
+        }
+        for hop in hops(state.0, false)? {
+            if hop.weight < 0.0 || hop.weight.is_nan() {
+                return Err(Error::invalid_parameter());
+            }
+            if banned_vertices.contains(&hop.vid) || banned_edges.contains(&hop.uid) {
+                continue;
+            }
+            let next = (hop.vid, if limited { state.1 + 1 } else { 0 });
+            let next_weight = weight + hop.weight;
+            if parents.get(&next).map_or(true, |&(w, _)| next_weight < w) {
+                parents.insert(next, (next_weight, Some((state, hop.uid, hop.weight))));
+                heap.push(Candidate {
+                    weight: next_weight,
+                    state: next,
+                });
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn k_shortest<H>(hops: &mut H, src: i64, dst: i64, k: usize, max_depth: usize) -> Result<Vec<Path>>
+where
+    H: FnMut(i64, bool) -> Result<Vec<Hop>>,
+{
+    let mut paths = vec![];
+    match dijkstra(hops, src, dst, max_depth, &HashSet::new(), &HashSet::new())? {
+        Some(first) => paths.push(first),
+        None => return Ok(vec![]),
+    }
+    let mut candidates: Vec<(Path, Vec<f64>)> = vec![];
+    while paths.len() < k {
+        let (last, last_weights) = paths[paths.len() - 1].clone();
+        // deviate from the last path at each of its vertices but the target
+        for i in 0..last.edges.len() {
+            let (root_vids, root_edges) = (&last.vids[..=i], &last.edges[..i]);
+            let banned_edges = paths
+                .iter()
+                .filter(|(p, _)| {
+                    p.edges.len() > i && p.vids[..=i] == *root_vids && p.edges[..i] == *root_edges
+                })
+                .map(|(p, _)| p.edges[i])
+                .collect();
+            let banned_vertices = root_vids[..i].iter().copied().collect();
+            let spur_depth = if max_depth == usize::MAX {
+                max_depth
+            } else {
+                max_depth - i
+            };
+            let spur = dijkstra(
+                hops,
+                last.vids[i],
+                dst,
+                spur_depth,
+                &banned_vertices,
+                &banned_edges,
+            )?;
+            if let Some((spur, spur_weights)) = spur {
+                let mut weights = last_weights[..i].to_vec();
+                weights.extend(spur_weights);
+                let mut vids = root_vids.to_vec();
+                vids.extend_from_slice(&spur.vids[1..]);
+                let mut edges = root_edges.to_vec();
+                edges.extend(spur.edges);
+                let path = Path {
+                    vids,
+                    edges,
+                    weight: weights.iter().sum(),
+                };
+                if !paths
+                    .iter()
+                    .chain(&candidates)
+                    .any(|(p, _)| p.edges == path.edges)
+                {
+                    candidates.push((path, weights));
+                }
+            }
+        }
+        // the lightest candidate, the first found if there are many
+        let best = candidates
+            .iter()
+            .enumerate()
+            .min_by(|(i, (a, _)), (j, (b, _))| a.weight.total_cmp(&b.weight).then(i.cmp(j)))
+            .map(|(i, _)| i);
+        match best {
+            Some(i) => paths.push(candidates.remove(i)),
+            None => break,
+        }
+    }
+    Ok(paths.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bfs, bidirectional_bfs, dijkstra, k_shortest, Hop, Path};
+    use crate::{types::EdgeUid, Result};
+    use std::collections::HashSet;
+
+    // The hops of edges `(src, dst, weight)` of label 0, where the eid is the index.
+    fn hops(edges: &[(i64, i64, f64)]) -> impl FnMut(i64, bool) -> Result<Vec<Hop>> + '_ {
+        move |vid, backward| {
+            Ok(edges
+                .iter()
+                .enumerate()
+                .filter(|(_, &(src, dst, _))| if backward { dst == vid } else { src == vid })
+                .map(|(eid, &(src, dst, weight))| Hop {
+                    vid: if backward { src } else { dst },
+                    uid: EdgeUid {
+                        src,
+                        dst,
+                        eid: eid as i64,
+                        ..Default::default()
+                    },
+                    weight,
+                })
+                .collect())
+        }
+    }
+
+    // 0 -> 1 -> 2 -> 3 -> 4, 0 -> 5 -> 4 with weight 10, 1 -> 3 with weight 3
+    const EDGES: [(i64, i64, f64); 7] = [
+        (0, 1, 1.0),
+        (1, 2, 1.0),
+        (2, 3, 1.0),
+        (3, 4, 1.0),
+        (0, 5, 5.0),
+        (5, 4, 5.0),
+        (1, 3, 3.0),
+    ];
+
+    #[test]
+    fn test_bfs() {
+        for search in [bfs, bidirectional_bfs] {
+            let path = search(&mut hops(&EDGES), 0, 4, usize::MAX)
+                .unwrap()
+                .unwrap();
+            assert_eq!(path.vids, [0, 5, 4]);
+            assert_eq!(path.weight, 2.0);
+            assert_eq!(path.edges[0].dst, 5);
+            assert!(search(&mut hops(&EDGES), 0, 4, 1).unwrap().is_none());
+            assert!(search(&mut hops(&EDGES), 4, 0, usize::MAX)
+                .unwrap()
+                .is_none());
+            let path = search(&mut hops(&EDGES), 1, 4, 2).unwrap().unwrap();
+            assert_eq!(path.vids, [1, 3, 4]);
+            let path = search(&mut hops(&EDGES), 2, 2, 0).unwrap().unwrap();
+            assert!(path.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let (path, weights) = dijkstra(
+            &mut hops(&EDGES),
+            0,
+            4,
+            usize::MAX,
+            &HashSet::new(),
+            &HashSet::new(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(path.vids, [0, 1, 2, 3, 4]);
+        assert_eq!(path.weight, 4.0);
+        assert_eq!(weights, [1.0; 4]);
+        // the lightest path of at most 3 edges
+        let (path, _) = dijkstra(&mut hops(&EDGES), 0, 4, 3, &HashSet::new(), &HashSet::new())
+            .unwrap()
+            .unwrap();
+        assert_eq!(path.vids, [0, 1, 3, 4]);
+        assert_eq!(path.weight, 5.0);
+        let negative = [(0, 1, -1.0)];
+        assert!(dijkstra(
+            &mut hops(&negative),
+            0,
+            1,
+            usize::MAX,
+            &HashSet::new(),
+            &HashSet::new()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_k_shortest() {
+        let paths = k_shortest(&mut hops(&EDGES), 0, 4, 5, usize::MAX).unwrap();
+        let found: Vec<_> = paths.iter().map(|p| (p.vids.clone(), p.weight)).collect();
+        assert_eq!(
+            found,
+            [
+                (vec![0, 1, 2, 3, 4], 4.0),
+                (vec![0, 1, 3, 4], 5.0),
+                (vec![0, 5, 4], 10.0),
+            ]
+        );
+        let paths = k_shortest(&mut hops(&EDGES), 0, 4, 2, 3).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[1].vids, [0, 5, 4]);
+    }
+
+    #[test]
+    fn test_display() {
+        let edge = |src, dst| EdgeUid {
+            src,
+            dst,
+            lid: 1,
+            ..Default::default()
+        };
+        let path = Path {
+            vids: vec![0, 2, 3],
+            edges: vec![edge(0, 2), edge(3, 2)],
+            weight: 2.0,
+        };
+        assert_eq!(path.to_string(), "(0)-[1]->(2)<-[1]-(3)");
+        assert_eq!((path.src(), path.dst(), path.len()), (Some(0), Some(3), 2));
+
+        let empty = Path {
+            vids: vec![],
+            edges: vec![],
+            weight: 0.0,
+        };
+        assert_eq!(empty.to_string(), "");
+        assert_eq!((empty.src(), empty.dst()), (None, None));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let path = Path {
+            vids: vec![0, 1],
+            edges: vec![EdgeUid {
+                src: 0,
+                dst: 1,
+                ..Default::default()
+            }],
+            weight: 1.5,
+        };
+        let json = serde_json::to_value(&path).unwrap();
+        assert_eq!(json["vids"], serde_json::json!([0, 1]));
+        assert_eq!(json["edges"][0]["dst"], 1);
+        assert_eq!(serde_json::from_value::<Path>(json).unwrap(), path);
+    }
+}
@@ -0,0 +1,521 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use crate::{
+    cursor::{EdgeCursor, VertexCur, VertexCursor},
+    parallel::Parallel,
+    txn::TxnRead,
+    types::EdgeUid,
+    Error, Result,
+};
+
+use super::{labels, weight_of, Filter, Lids, Topology};
+
+/// The options of building a [`Projection`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectionOptions {
+    filter: Filter,
+    reverse: bool,
+}
+
+impl ProjectionOptions {
+    /// Create with all vertices and edges, no weight and no reverse adjacency.
+    pub fn new() -> Self {
+        ProjectionOptions {
+            filter: Filter::default(),
+            reverse: false,
+        }
+    }
+
+    /// Only project vertices of the given labels.
+    pub fn vertex_labels<I, S>(mut self, vertex_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.vertex_labels = labels(vertex_labels);
+        self
+    }
+
+    /// Only project edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, edge_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.edge_labels = labels(edge_labels);
+        self
+    }
+
+    /// Read the numeric edge field `weight_field` as the weight column.
+    pub fn weight_field(mut self, weight_field: &str) -> Self {
+        self.filter.weight_field = Some(weight_field.to_string());
+        self
+    }
+
+    /// Set whether to build the reverse adjacency of in edges as well.
+    pub fn reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+}
+
+// The in adjacency, where each in edge refers to its index in the out adjacency.
+#[derive(Debug, Clone, PartialEq)]
+struct InAdjacency {
+    offsets: Vec<usize>,
+    srcs: Vec<u32>,
+    edges: Vec<usize>,
+}
+
+/// `Projection` is a compact in-memory snapshot of the vertices and edges seen by a
+/// transaction, in compressed sparse row (CSR) form.
+///
+/// Vertices are mapped to dense `u32` ids in ascending order of vid. The out edges of the
+/// vertex `id` are the edges `out_offsets[id]..out_offsets[id + 1]`, whose destinations
+/// are in `out_targets`. Each edge keeps its label id and [`EdgeUid`], and its weight if
+/// a weight field is given. Edges to the vertices not projected are dropped.
+///
+/// Building scans the vertices and their out edges once, which can be done in parallel
+/// over forked transactions by [`Projection::par_build`]. Algorithms over the projection,
+/// e.g. [`PageRank::run_on`](super::PageRank::run_on), then run without going through
+/// cursors, and the projection can be reused by several of them.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{
+///     algo::{Projection, ProjectionOptions},
+///     db::OpenOptions,
+///     parallel::Parallel,
+/// };
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let options = ProjectionOptions::new()
+///     .edge_labels(["transfer"])
+///     .weight_field("amount")
+///     .reverse(true);
+/// let projection = Projection::par_build(&Parallel::new(&graph, &ro_txn), &options)?;
+/// for id in 0..projection.num_vertices() as u32 {
+///     let in_degree = projection.in_neighbors(id).map_or(0, |srcs| srcs.len());
+///     println!("{}: {}", projection.vid(id), in_degree);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Projection {
+    // sorted ascending
+    vids: Vec<i64>,
+    out_offsets: Vec<usize>,
+    out_targets: Vec<u32>,
+    lids: Vec<u16>,
+    tids: Vec<i64>,
+    eids: Vec<i64>,
+    weights: Option<Vec<f64>>,
+    in_adjacency: Option<InAdjacency>,
+}
+
+// The vertices and out edges read by a worker, before the edges to the vertices not
+// projected are dropped.
+#[derive(Default)]
+struct Part {
+    vids: Vec<i64>,
+    // the end of the out edges of each vertex
+    ends: Vec<usize>,
+    dsts: Vec<i64>,
+    lids: Vec<u16>,
+    tids: Vec<i64>,
+    eids: Vec<i64>,
+    weights: Vec<f64>,
+}
+
+impl Projection {
+    /// Build the projection of the graph seen by `txn`.
+    ///
+    /// # Errors
+    /// If a label does not exist, the weight field is not numeric, or there are more than
+    /// `u32::MAX` vertices, an error is returned.
+    pub fn build<T: TxnRead>(txn: &T, options: &ProjectionOptions) -> Result<Projection> {
+        Self::load(txn, &options.filter).map(|projection| projection.with_reverse(options))
+    }
+
+    /// Build as [`Projection::build`], where the vertices are read by the workers of
+    /// `parallel`.
+    ///
+    /// # Errors
+    /// See [`Projection::build`].
+    pub fn par_build(parallel: &Parallel<'_>, options: &ProjectionOptions) -> Result<Projection> {
+        Self::par_load(parallel, &options.filter).map(|projection| projection.with_reverse(options))
+    }
+
+    // Load the vertices and edges accepted by `filter`, without the reverse adjacency.
+    pub(super) fn load<T: TxnRead>(txn: &T, filter: &Filter) -> Result<Projection> {
+        let lids = filter.lids(txn)?;
+        let mut part = Part::default();
+        let mut vcur = txn.vertex_cur()?;
+        vcur.seek(0, true)?;
+        while vcur.is_valid() {
+            load_vertex(filter, &lids, &mut vcur, &mut part)?;
+            vcur.seek_to_next()?;
+        }
+        Self::merge(vec![part], filter)
+    }
+
+    // Load as `Projection::load`, where the vertices are read by the workers of `parallel`.
+    pub(super) fn par_load(parallel: &Parallel<'_>, filter: &Filter) -> Result<Projection> {
+        let lids = filter.lids(parallel.txn())?;
+        let ranges = parallel.vid_ranges()?;
+        let parts = parallel.run(|txn, stop| {
+            Parallel::fold_vertices(txn, &ranges, stop, Part::default(), |mut part, vcur| {
+                load_vertex(filter, &lids, vcur, &mut part)?;
+                Ok(part)
+            })
+        })?;
+        Self::merge(parts, filter)
+    }
+
+    // Build the reverse adjacency if asked to.
+    fn with_reverse(mut self, options: &ProjectionOptions) -> Projection {
+        if options.reverse {
+            self.in_adjacency = Some(self.reversed());
+        }
+        self
+    }
+
+    // Merge the parts in the order of vid, and drop edges to the vertices not projected.
+    fn merge(parts: Vec<Part>, filter: &Filter) -> Result<Projection> {
+        // (vid, part, index in part)
+        let mut order: Vec<(i64, usize, usize)> = parts
+            .iter()
+            .enumerate()
+            .flat_map(|(p, part)| {
+                part.vids
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, &vid)| (vid, p, i))
+            })
+            .collect();
+        if order.len() > u32::MAX as usize {
+            return Err(Error::invalid_parameter());
+        }
+        order.sort_unstable();
+        let vids: Vec<i64> = order.iter().map(|&(vid, _, _)| vid).collect();
+        let mut projection = Projection {
+            out_offsets: Vec::with_capacity(vids.len() + 1),
+            vids,
+            out_targets: vec![],
+            lids: vec![],
+            tids: vec![],
+            eids: vec![],
+            weights: filter.weight_field.as_ref().map(|_| vec![]),
+            in_adjacency: None,
+        };
+        projection.out_offsets.push(0);
+        for (_, p, i) in order {
+            let part = &parts[p];
+            let begin = if i == 0 { 0 } else { part.ends[i - 1] };
+            for e in begin..part.ends[i] {
+                if let Some(dst) = projection.id(part.dsts[e]) {
+                    projection.out_targets.push(dst);
+                    projection.lids.push(part.lids[e]);
+                    projection.tids.push(part.tids[e]);
+                    projection.eids.push(part.eids[e]);
+                    if let Some(weights) = &mut projection.weights {
+                        weights.push(part.weights[e]);
+                    }
+                }
+            }
+            projection.out_offsets.push(projection.out_targets.len());
+        }
+        Ok(projection)
+    }
+
+    // Bucket the out edges by destination.
+    fn reversed(&self) -> InAdjacency {
+        let n = self.num_vertices();
+        let mut offsets = vec![0; n + 1];
+        for &dst in &self.out_targets {
+            offsets[dst as usize + 1] += 1;
+        }
+        for i in 0..n {
+            offsets[i + 1] += offsets[i];
+        }
+        let mut next = offsets.clone();
+        let mut srcs = vec![0; self.num_edges()];
+        let mut edges = vec![0; self.num_edges()];
+        for src in 0..n {
+            for e in self.out_edges(src as u32) {
+                let dst = self.out_targets[e] as usize;
+                srcs[next[dst]] = src as u32;
+                edges[next[dst]] = e;
+                next[dst] += 1;
+            }
+        }
+        InAdjacency {
+            offsets,
+            srcs,
+            edges,
+        }
+    }
+
+    /// Get the number of vertices.
+    pub fn num_vertices(&self) -> usize {
+        self.vids.len()
+    }
+
+    /// Get the number of edges.
+    pub fn num_edges(&self) -> usize {
+        self.out_targets.len()
+    }
+
+    /// Get the vids in the order of ids.
+    pub fn vids(&self) -> &[i64] {
+        &self.vids
+    }
+
+    /// Get the vid of vertex `id`.
+    ///
+    /// # Panics
+    /// Panics if `id` is out of range.
+    pub fn vid(&self, id: u32) -> i64 {
+        self.vids[id as usize]
+    }
+
+    /// Get the id of vertex `vid`, or `None` if it is not projected.
+    pub fn id(&self, vid: i64) -> Option<u32> {
+        self.vids.binary_search(&vid).ok().map(|id| id as u32)
+    }
+
+    /// Get the offsets of the out edges of each vertex, which has `num_vertices() + 1`
+    /// items.
+    pub fn out_offsets(&self) -> &[usize] {
+        &self.out_offsets
+    }
+
+    /// Get the destination ids of all edges.
+    pub fn out_targets(&self) -> &[u32] {
+        &self.out_targets
+    }
+
+    /// Get the label ids of all edges.
+    pub fn edge_label_ids(&self) -> &[u16] {
+        &self.lids
+    }
+
+    /// Get the weights of all edges, if a weight field is given.
+    pub fn weights(&self) -> Option<&[f64]> {
+        self.weights.as_deref()
+    }
+
+    /// Get the indices of the out edges of vertex `id`.
+    ///
+    /// # Panics
+    /// Panics if `id` is out of range.
+    pub fn out_edges(&self, id: u32) -> Range<usize> {
+        self.out_offsets[id as usize]..self.out_offsets[id as usize + 1]
+    }
+
+    /// Get the destination ids of the out edges of vertex `id`.
+    ///
+    /// # Panics
+    /// Panics if `id` is out of range.
+    pub fn out_neighbors(&self, id: u32) -> &[u32] {
+        &self.out_targets[self.out_edges(id)]
+    }
+
+    /// Get the source ids of the in edges of vertex `id`, or `None` without the reverse
+    /// adjacency.
+    ///
+    /// # Panics
+    /// Panics if `id` is out of range.
+    pub fn in_neighbors(&self, id: u32) -> Option<&[u32]> {
+        self.in_adjacency.as_ref().map(|adjacency| {
+            &adjacency.srcs[adjacency.offsets[id as usize]..adjacency.offsets[id as usize + 1]]
+        })
+    }
+
+    /// Get the indices of the in edges of vertex `id` in the order of
+    /// [`Projection::in_neighbors`], or `None` without the reverse adjacency.
+    ///
+    /// # Panics
+    /// Panics if `id` is out of range.
+    pub fn in_edges(&self, id: u32) -> Option<&[usize]> {
+        self.in_adjacency.as_ref().map(|adjacency| {
+            &adjacency.edges[adjacency.offsets[id as usize]..adjacency.offsets[id as usize + 1]]
+        })
+    }
+
+    /// Get the source id of edge `edge`.
+    ///
+    /// # Panics
+    /// Panics if `edge` is out of range.
+    pub fn edge_src(&self, edge: usize) -> u32 {
+        assert!(edge < self.num_edges());
+        (self.out_offsets.partition_point(|&offset| offset <= edge) - 1) as u32
+    }
+
+    /// Get the [`EdgeUid`] of edge `edge`.
+    ///
+    /// # Panics
+    /// Panics if `edge` is out of range.
+    pub fn edge_uid(&self, edge: usize) -> EdgeUid {
+        EdgeUid {
+            src: self.vid(self.edge_src(edge)),
+            lid: self.lids[edge],
+            tid: self.tids[edge],
+            dst: self.vid(self.out_targets[edge]),
+            eid: self.eids[edge],
+        }
+    }
+}
+
+impl From<Projection> for Topology {
+    fn from(projection: Projection) -> Self {
+        Topology {
+            vids: projection.vids,
+            offsets: projection.out_offsets,
+            targets: projection
+                .out_targets
+                .iter()
+                .map(|&dst| dst as usize)
+                .collect(),
+            weights: projection.weights,
+        }
+    }
+}
+
+impl From<&Projection> for Topology {
+    fn from(projection: &Projection) -> Self {
+        Topology {
+            vids: projection.vids.clone(),
+            offsets: projection.out_offsets.clone(),
+            targets: projection
+                .out_targets
+                .iter()
+                .map(|&dst| dst as usize)
+                .collect(),
+            weights: projection.weights.clone(),
+        }
+    }
+}
+
+// Append the vertex `vcur` points to and its out edges to `part`, if it is accepted.
+fn load_vertex(
+    filter: &Filter,
+    lids: &Lids,
+    vcur: &mut VertexCur<'_>,
+    part: &mut Part,
+) -> Result<()> {
+    if let Some(lids) = &lids.vertex {
+        if !lids.contains(&vcur.lid()?) {
+            return Ok(());
+        }
+    }
+    part.vids.push(vcur.id()?);
+    let mut ecur = vcur.out_edge_cursor()?;
+    while ecur.is_valid() {
+        let uid = ecur.uid()?;
+        if lids
+            .edge
+            .as_ref()
+            .map_or(true, |lids| lids.contains(&uid.lid))
+        {
+            part.dsts.push(uid.dst);
+            part.lids.push(uid.lid);
+            part.tids.push(uid.tid);
+            part.eids.push(uid.eid);
+            if let Some(field) = &filter.weight_field {
+                part.weights.push(weight_of(ecur.field(field)?)?);
+            }
+        }
+        ecur.seek_to_next()?;
+    }
+    part.ends.push(part.dsts.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Part, Projection, ProjectionOptions, Topology};
+
+    // Vertices 10, 20, 30 of two parts, with edges 10 -> 20, 10 -> 30, 30 -> 10, and
+    // 20 -> 40 to a vertex not projected.
+    fn parts() -> Vec<Part> {
+        vec![
+            Part {
+                vids: vec![30],
+                ends: vec![1],
+                dsts: vec![10],
+                lids: vec![1],
+                tids: vec![0],
+                eids: vec![2],
+                weights: vec![3.0],
+            },
+            Part {
+                vids: vec![10, 20],
+                ends: vec![2, 3],
+                dsts: vec![20, 30, 40],
+                lids: vec![0, 1, 0],
+                tids: vec![5, 0, 0],
+                eids: vec![0, 1, 3],
+                weights: vec![1.0, 2.0, 4.0],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_merge() {
+        let options = ProjectionOptions::new().weight_field("w").reverse(true);
+        let p = Projection::merge(parts(), &options.filter)
+            .unwrap()
+            .with_reverse(&options);
+        assert_eq!(p.vids(), [10, 20, 30]);
+        assert_eq!(p.num_edges(), 3);
+        assert_eq!(p.out_offsets(), [0, 2, 2, 3]);
+        assert_eq!(p.out_neighbors(0), [1, 2]);
+        assert_eq!(p.edge_label_ids(), [0, 1, 1]);
+        assert_eq!(p.weights(), Some(&[1.0, 2.0, 3.0][..]));
+        assert_eq!(p.id(30), Some(2));
+        assert_eq!(p.id(40), None);
+
+        assert_eq!(p.edge_src(1), 0);
+        assert_eq!(p.edge_src(2), 2);
+        let uid = p.edge_uid(0);
+        assert_eq!((uid.src, uid.dst, uid.lid, uid.tid), (10, 20, 0, 5));
+
+        assert_eq!(p.in_neighbors(0), Some(&[2][..]));
+        assert_eq!(p.in_edges(0), Some(&[2][..]));
+        assert_eq!(p.in_neighbors(2), Some(&[0][..]));
+        assert_eq!(p.in_edges(2), Some(&[1][..]));
+
+        let options = ProjectionOptions::new();
+        let p = Projection::merge(parts(), &options.filter)
+            .unwrap()
+            .with_reverse(&options);
+        assert_eq!(p.weights(), None);
+        assert_eq!(p.in_neighbors(0), None);
+
+        // the same topology as loaded by the algorithms
+        let t = Topology::from(p);
+        assert_eq!(t.vids, [10, 20, 30]);
+        assert_eq!(t.out(0), [1, 2]);
+        assert_eq!(t.out(2), [0]);
+        assert_eq!(t.out_weights(0), None);
+    }
+}
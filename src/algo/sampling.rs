@@ -0,0 +1,372 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::{
+    cursor::{EdgeCursor, VertexCur, VertexCursor},
+    field::FieldData,
+    traverse::Direction,
+    txn::TxnRead,
+    Error, Result,
+};
+
+use super::{labels, weight_of, Filter, Rng};
+
+/// The multi-hop neighborhood of seed vertices sampled by [`NeighborSampler`].
+///
+/// Vertices are referred by local ids, their positions in `vids`. The seeds come first,
+/// followed by the vertices first reached at each hop. Sampled edges point from the
+/// sampled neighbor to the vertex it is sampled for, the direction messages flow in a
+/// GNN, whatever the direction of the edge in the graph.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Neighborhood {
+    /// The vid of each local id.
+    pub vids: Vec<i64>,
+    /// The local ids of the sources of the sampled edges.
+    pub src: Vec<usize>,
+    /// The local ids of the destinations of the sampled edges.
+    pub dst: Vec<usize>,
+    /// The number of vertices added by the seeds and then each hop.
+    pub num_vertices_per_hop: Vec<usize>,
+    /// The number of edges sampled at each hop, which are ordered by hop.
+    pub num_edges_per_hop: Vec<usize>,
+    /// A column of each feature field, holding the value of each local id. Null is
+    /// read as NaN.
+    pub features: Vec<Vec<f64>>,
+}
+
+impl Neighborhood {
+    /// Get the number of vertices.
+    pub fn num_vertices(&self) -> usize {
+        self.vids.len()
+    }
+
+    /// Get the number of edges.
+    pub fn num_edges(&self) -> usize {
+        self.src.len()
+    }
+}
+
+/// `NeighborSampler` samples the multi-hop neighborhoods of seed vertices, as GraphSAGE
+/// does for mini-batch training of GNNs.
+///
+/// At hop `i`, up to `fanouts[i]` edges are sampled uniformly without replacement from
+/// each vertex first reached at the previous hop, starting from the seeds. Whether a
+/// vertex has more edges than the fanout is checked by
+/// [`VertexCursor::num_out_edges`] and [`VertexCursor::num_in_edges`]: if not, all its
+/// edges are taken, otherwise they are sampled by reservoir sampling over the edge
+/// cursors.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{algo::NeighborSampler, db::OpenOptions, traverse::Direction};
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let sampler = NeighborSampler::new([25, 10])
+///     .direction(Direction::In)
+///     .edge_labels(["follows"])
+///     .feature_fields(["age", "score"])
+///     .seed(42);
+/// let batch = sampler.sample(&ro_txn, &[0, 1, 2])?;
+/// println!("{} vertices, {} edges", batch.num_vertices(), batch.num_edges());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeighborSampler {
+    fanouts: Vec<usize>,
+    direction: Direction,
+    feature_fields: Vec<String>,
+    seed: u64,
+    filter: Filter,
+}
+
+impl NeighborSampler {
+    /// Create sampling `fanouts[i]` edges at hop `i`, following out edges of all labels
+    /// with seed 0 and no feature.
+    pub fn new<I: IntoIterator<Item = usize>>(fanouts: I) -> Self {
+        NeighborSampler {
+            fanouts: fanouts.into_iter().collect(),
+            direction: Direction::Out,
+            feature_fields: vec![],
+            seed: 0,
+            filter: Filter::default(),
+        }
+    }
+
+    /// Set the direction of edges to neighbors.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Only sample neighbors of the given labels.
+    pub fn vertex_labels<I, S>(mut self, vertex_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.vertex_labels = labels(vertex_labels);
+        self
+    }
+
+    /// Only sample edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, edge_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.edge_labels = labels(edge_labels);
+        self
+    }
+
+    /// Read the given numeric vertex fields as feature columns.
+    pub fn feature_fields<I, S>(mut self, feature_fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.feature_fields = feature_fields.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the seed of the random sampling.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sample the neighborhood of `seeds`, where repeated seeds are taken once.
+    ///
+    /// # Errors
+    /// If a seed does not exist, a label does not exist, or a feature field is missing or
+    /// not numeric on a sampled vertex, an error is returned.
+    pub fn sample<T: TxnRead>(&self, txn: &T, seeds: &[i64]) -> Result<Neighborhood> {
+        let lids = self.filter.lids(txn)?;
+        let mut sampler = Sampler {
+            vcur: txn.vertex_cur()?,
+            probe: txn.vertex_cur()?,
+            direction: self.direction,
+            vertex_lids: lids.vertex,
+            edge_lids: lids.edge,
+            rng: Rng::new(self.seed),
+        };
+        let mut locals = HashMap::new();
+        let mut vids = vec![];
+        for &seed in seeds {
+            if !sampler.vcur.seek(seed, false)?.is_valid() {
+                return Err(Error::invalid_parameter());
+            }
+            locals.entry(seed).or_insert_with(|| {
+                vids.push(seed);
+                vids.len() - 1
+            });
+        }
+        let (mut src, mut dst) = (vec![], vec![]);
+        let mut num_vertices_per_hop = vec![vids.len()];
+        let mut num_edges_per_hop = vec![];
+        let mut frontier = 0..vids.len();
+        for &fanout in &self.fanouts {
+            let num_edges = src.len();
+            for local in frontier.clone() {
+                for neighbor in sampler.neighbors(vids[local], fanout)? {
+                    let neighbor_local = *locals.entry(neighbor).or_insert_with(|| {
+                        vids.push(neighbor);
+                        vids.len() - 1
+                    });
+                    src.push(neighbor_local);
+                    dst.push(local);
+                }
+            }
+            num_edges_per_hop.push(src.len() - num_edges);
+            num_vertices_per_hop.push(vids.len() - frontier.end);
+            frontier = frontier.end..vids.len();
+        }
+        let features = self
+            .feature_fields
+            .iter()
+            .map(|field| {
+                vids.iter()
+                    .map(|&vid| feature_of(sampler.vcur.seek(vid, false)?.field(field)?))
+                    .collect()
+            })
+            .collect::<Result<_>>()?;
+        Ok(Neighborhood {
+            vids,
+            src,
+            dst,
+            num_vertices_per_hop,
+            num_edges_per_hop,
+            features,
+        })
+    }
+}
+
+fn feature_of(data: FieldData) -> Result<f64> {
+    match data {
+        FieldData::Null => Ok(f64::NAN),
+        data => weight_of(data),
+    }
+}
+
+// Keeps a uniform sample of at most `k` of the items offered.
+struct Reservoir {
+    k: usize,
+    seen: usize,
+    items: Vec<i64>,
+}
+
+impl Reservoir {
+    fn new(k: usize) -> Self {
+        Reservoir {
+            k,
+            seen: 0,
+            items: vec![],
+        }
+    }
+
+    fn offer(&mut self, rng: &mut Rng, item: i64) {
+        if self.items.len() < self.k {
+            self.items.push(item);
+        } else {
+            let i = rng.below(self.seen + 1);
+            if i < self.k {
+                self.items[i] = item;
+            }
+        }
+        self.seen += 1;
+    }
+}
+
+// Samples neighbors through an expanding cursor, and a probing cursor to check the labels
+// of neighbors.
+struct Sampler<'t> {
+    vcur: VertexCur<'t>,
+    probe: VertexCur<'t>,
+    direction: Direction,
+    vertex_lids: Option<Vec<u16>>,
+    edge_lids: Option<Vec<u16>>,
+    rng: Rng,
+}
+
+impl<'t> Sampler<'t> {
+    fn accept<E: EdgeCursor>(
+        probe: &mut VertexCur<'_>,
+        vertex_lids: &Option<Vec<u16>>,
+        edge_lids: &Option<Vec<u16>>,
+        ecur: &E,
+        neighbor: i64,
+    ) -> Result<bool> {
+        if let Some(lids) = edge_lids {
+            if !lids.contains(&ecur.lid()?) {
+                return Ok(false);
+            }
+        }
+        match vertex_lids {
+            Some(lids) => {
+                Ok(probe.seek(neighbor, false)?.is_valid() && lids.contains(&probe.lid()?))
+            }
+            None => Ok(true),
+        }
+    }
+
+    // Sample up to `fanout` neighbors of `vid`.
+    fn neighbors(&mut self, vid: i64, fanout: usize) -> Result<Vec<i64>> {
+        if fanout == 0 || !self.vcur.seek(vid, false)?.is_valid() {
+            return Ok(vec![]);
+        }
+        let mut num_edges = 0;
+        let mut more = false;
+        if self.direction.has_out() {
+            let (out_more, n) = self.vcur.num_out_edges(fanout)?;
+            more |= out_more;
+            num_edges += n;
+        }
+        if self.direction.has_in() {
+            let (in_more, n) = self.vcur.num_in_edges(fanout)?;
+            more |= in_more;
+            num_edges += n;
+        }
+        // take all edges if there are not more than `fanout`
+        let mut reservoir = Reservoir::new(if more || num_edges > fanout {
+            fanout
+        } else {
+            usize::MAX
+        });
+        if self.direction.has_out() {
+            let mut ecur = self.vcur.out_edge_cursor()?;
+            while ecur.is_valid() {
+                let dst = ecur.dst()?;
+                if Self::accept(
+                    &mut self.probe,
+                    &self.vertex_lids,
+                    &self.edge_lids,
+                    &ecur,
+                    dst,
+                )? {
+                    reservoir.offer(&mut self.rng, dst);
+                }
+                ecur.seek_to_next()?;
+            }
+        }
+        if self.direction.has_in() {
+            let mut ecur = self.vcur.in_edge_cursor()?;
+            while ecur.is_valid() {
+                let src = ecur.src()?;
+                if Self::accept(
+                    &mut self.probe,
+                    &self.vertex_lids,
+                    &self.edge_lids,
+                    &ecur,
+                    src,
+                )? {
+                    reservoir.offer(&mut self.rng, src);
+                }
+                ecur.seek_to_next()?;
+            }
+        }
+        Ok(reservoir.items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reservoir, Rng};
+
+    #[test]
+    fn test_reservoir() {
+        let mut rng = Rng::new(3);
+        let mut all = Reservoir::new(usize::MAX);
+        (0..5).for_each(|i| all.offer(&mut rng, i));
+        assert_eq!(all.items, [0, 1, 2, 3, 4]);
+
+        // each of 10 items is kept with probability 3 / 10
+        let mut counts = [0; 10];
+        for _ in 0..10000 {
+            let mut reservoir = Reservoir::new(3);
+            (0..10).for_each(|i| reservoir.offer(&mut rng, i));
+            assert_eq!(reservoir.items.len(), 3);
+            reservoir
+                .items
+                .iter()
+                .for_each(|&i| counts[i as usize] += 1);
+        }
+        assert!(counts.iter().all(|&c| (2700..3300).contains(&c)));
+    }
+}
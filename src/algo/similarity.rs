@@ -0,0 +1,275 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Neighborhood-based similarity between vertices, for link prediction.
+//!
+//! [`Similarity`] scores a pair of vertices by their neighbors, or finds the vertices
+//! most similar to a given one among its 2-hop neighborhood. Neighbors are read on demand
+//! by [`VertexCursor::associated_edges_dst_vids`] and
+//! [`VertexCursor::associated_edges_src_vids`] with a limit, so that a supernode does
+//! not blow up the work.
+//!
+//! ```no_run
+//! use tugraph::{
+//!     algo::similarity::{Measure, Similarity},
+//!     db::OpenOptions,
+//! };
+//!
+//! # fn main() -> Result<(), tugraph::Error> {
+//! let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+//! let graph = galaxy.open_graph("default", true)?;
+//! let ro_txn = graph.create_ro_txn()?;
+//! let similarity = Similarity::new().max_neighbors(1000);
+//! let jaccard = similarity.pair(&ro_txn, 0, 1)?.get(Measure::Jaccard);
+//! let candidates = similarity.top_k(&ro_txn, 0, Measure::AdamicAdar, 10)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    cursor::{VertexCur, VertexCursor},
+    traverse::Direction,
+    txn::TxnRead,
+    Result,
+};
+
+use super::TopK;
+
+/// A measure of similarity between vertices `a` and `b`, where `N(v)` denotes the
+/// neighbors of `v`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Measure {
+    /// `|N(a) ∩ N(b)|`.
+    CommonNeighbors,
+    /// `|N(a) ∩ N(b)| / |N(a) ∪ N(b)|`, or 0 if both have no neighbor.
+    Jaccard,
+    /// The sum of `1 / ln |N(z)|` over the common neighbors `z`.
+    AdamicAdar,
+    /// The sum of `1 / |N(z)|` over the common neighbors `z`.
+    ResourceAllocation,
+    /// `|N(a)| * |N(b)|`.
+    PreferentialAttachment,
+}
+
+/// The scores of a pair of vertices by each [`Measure`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PairScores {
+    /// See [`Measure::CommonNeighbors`].
+    pub common_neighbors: f64,
+    /// See [`Measure::Jaccard`].
+    pub jaccard: f64,
+    /// See [`Measure::AdamicAdar`].
+    pub adamic_adar: f64,
+    /// See [`Measure::ResourceAllocation`].
+    pub resource_allocation: f64,
+    /// See [`Measure::PreferentialAttachment`].
+    pub preferential_attachment: f64,
+}
+
+impl PairScores {
+    /// Get the score by `measure`.
+    pub fn get(&self, measure: Measure) -> f64 {
+        match measure {
+            Measure::CommonNeighbors => self.common_neighbors,
+            Measure::Jaccard => self.jaccard,
+            Measure::AdamicAdar => self.adamic_adar,
+            Measure::ResourceAllocation => self.resource_allocation,
+            Measure::PreferentialAttachment => self.preferential_attachment,
+        }
+    }
+}
+
+// The scores accumulated for a pair from the degrees of their common neighbors.
+#[derive(Default)]
+struct Common {
+    count: usize,
+    adamic_adar: f64,
+    resource_allocation: f64,
+}
+
+impl Common {
+    fn add(&mut self, degree: usize) {
+        self.count += 1;
+        if degree > 1 {
+            self.adamic_adar += 1.0 / (degree as f64).ln();
+        }
+        if degree > 0 {
+            self.resource_allocation += 1.0 / degree as f64;
+        }
+    }
+
+    fn scores(&self, degree_a: usize, degree_b: usize) -> PairScores {
+        let union = degree_a + degree_b - self.count;
+        PairScores {
+            common_neighbors: self.count as f64,
+            jaccard: if union == 0 {
+                0.0
+            } else {
+                self.count as f64 / union as f64
+            },
+            adamic_adar: self.adamic_adar,
+            resource_allocation: self.resource_allocation,
+            preferential_attachment: (degree_a * degree_b) as f64,
+        }
+    }
+}
+
+/// `Similarity` scores vertices by their neighbors.
+///
+/// Neighbors are the distinct vertices connected by edges of all labels in the given
+/// direction, other than the vertex itself. At most `max_neighbors` edges are read in
+/// each direction of a vertex, so a supernode only counts its first neighbors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Similarity {
+    direction: Direction,
+    max_neighbors: usize,
+}
+
+impl Default for Similarity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Similarity {
+    /// Create following edges in both directions, reading at most 10000 edges
+    /// in each direction of a vertex.
+    pub fn new() -> Self {
+        Similarity {
+            direction: Direction::Both,
+            max_neighbors: 10000,
+        }
+    }
+
+    /// Set the direction of edges to neighbors.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Set the max number of edges to read in each direction of a vertex.
+    pub fn max_neighbors(mut self, max_neighbors: usize) -> Self {
+        self.max_neighbors = max_neighbors;
+        self
+    }
+
+    // The sorted neighbors of `vid`, empty if it does not exist.
+    fn neighbors(&self, vcur: &mut VertexCur<'_>, vid: i64) -> Result<Vec<i64>> {
+        self.neighbors_toward(vcur, vid, self.direction)
+    }
+
+    // The sorted neighbors of `vid` in `direction`, empty if it does not exist.
+    fn neighbors_toward(
+        &self,
+        vcur: &mut VertexCur<'_>,
+        vid: i64,
+        direction: Direction,
+    ) -> Result<Vec<i64>> {
+        if !vcur.seek(vid, false)?.is_valid() {
+            return Ok(vec![]);
+        }
+        let mut neighbors = vec![];
+        if direction.has_out() {
+            neighbors = vcur.associated_edges_dst_vids(self.max_neighbors)?.1;
+        }
+        if direction.has_in() {
+            neighbors.append(&mut vcur.associated_edges_src_vids(self.max_neighbors)?.1);
+        }
+        neighbors.retain(|&neighbor| neighbor != vid);
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        Ok(neighbors)
+    }
+
+    /// Score vertices `a` and `b` by all measures. A vertex which does not exist has
+    /// no neighbor.
+    pub fn pair<T: TxnRead>(&self, txn: &T, a: i64, b: i64) -> Result<PairScores> {
+        let mut vcur = txn.vertex_cur()?;
+        let neighbors_a = self.neighbors(&mut vcur, a)?;
+        let neighbors_b = self.neighbors(&mut vcur, b)?;
+        let mut common = Common::default();
+        let (mut i, mut j) = (0, 0);
+        while i < neighbors_a.len() && j < neighbors_b.len() {
+            match neighbors_a[i].cmp(&neighbors_b[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    common.add(self.neighbors(&mut vcur, neighbors_a[i])?.len());
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        Ok(common.scores(neighbors_a.len(), neighbors_b.len()))
+    }
+
+    /// Get the `k` vertices most similar to `vid` by `measure` in descending order of
+    /// score, among the vertices sharing a neighbor with `vid` which are not neighbors of
+    /// `vid` yet. The scores are the same as of [`Similarity::pair`].
+    pub fn top_k<T: TxnRead>(
+        &self,
+        txn: &T,
+        vid: i64,
+        measure: Measure,
+        k: usize,
+    ) -> Result<Vec<(i64, f64)>> {
+        let mut vcur = txn.vertex_cur()?;
+        let neighbors = self.neighbors(&mut vcur, vid)?;
+        let mut candidates: HashMap<i64, Common> = HashMap::new();
+        for &z in &neighbors {
+            let degree = self.neighbors(&mut vcur, z)?.len();
+            // the vertices having `z` as a neighbor, by the edges in the reversed direction
+            let second = self.neighbors_toward(&mut vcur, z, self.direction.reversed())?;
+            for &c in &second {
+                if c != vid && neighbors.binary_search(&c).is_err() {
+                    candidates.entry(c).or_default().add(degree);
+                }
+            }
+        }
+        let needs_degree = matches!(measure, Measure::Jaccard | Measure::PreferentialAttachment);
+        let mut top = TopK::new(k);
+        for (c, common) in candidates {
+            let degree = if needs_degree {
+                self.neighbors(&mut vcur, c)?.len()
+            } else {
+                0
+            };
+            top.push(c, common.scores(neighbors.len(), degree).get(measure));
+        }
+        Ok(top.into_sorted_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Common, Measure};
+
+    #[test]
+    fn test_common() {
+        // a and b of 3 and 2 neighbors share neighbors of degrees 2 and 4
+        let mut common = Common::default();
+        common.add(2);
+        common.add(4);
+        let scores = common.scores(3, 2);
+        assert_eq!(scores.get(Measure::CommonNeighbors), 2.0);
+        assert_eq!(scores.get(Measure::Jaccard), 2.0 / 3.0);
+        let adamic_adar = 1.0 / 2f64.ln() + 1.0 / 4f64.ln();
+        assert!((scores.get(Measure::AdamicAdar) - adamic_adar).abs() < 1e-12);
+        assert_eq!(scores.get(Measure::ResourceAllocation), 0.75);
+        assert_eq!(scores.get(Measure::PreferentialAttachment), 6.0);
+        assert_eq!(Common::default().scores(0, 0).jaccard, 0.0);
+    }
+}
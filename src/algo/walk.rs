@@ -0,0 +1,402 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{io::Write, mem, sync::Mutex};
+
+use crate::{
+    cursor::{EdgeCursor, VertexCur, VertexCursor},
+    parallel::Parallel,
+    txn::TxnRead,
+    Error, Result,
+};
+
+use super::{labels, Filter, Rng};
+
+// The size of the walks a worker buffers before writing them out.
+const FLUSH_LEN: usize = 1 << 16;
+
+/// `RandomWalk` generates random walks from every vertex, following out edges, to train
+/// graph embeddings such as DeepWalk and node2vec.
+///
+/// Each walk starts at a vertex and moves to an out neighbor chosen at random, until it
+/// has `walk_length` vertices or reaches a vertex without out edges. With the default
+/// `p` and `q` of 1, neighbors are chosen uniformly. Otherwise the walk is the 2nd order
+/// walk of node2vec: moving from `v` which was reached from `t`, a neighbor `x` is chosen
+/// with weight `1 / p` if it is `t`, 1 if it is a neighbor of `t`, or `1 / q` otherwise.
+///
+/// The walks are reproducible: the `i`-th walk from a vertex only depends on the seed,
+/// the vertex, `i` and the graph, whether walks are generated in parallel or not.
+///
+/// # Examples
+/// ```no_run
+/// use std::{fs::File, io::BufWriter};
+/// use tugraph::{algo::RandomWalk, db::OpenOptions, parallel::Parallel};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let node2vec = RandomWalk::new()
+///     .walk_length(40)
+///     .walks_per_node(5)
+///     .p(0.5)
+///     .q(2.0)
+///     .edge_labels(["knows"])
+///     .seed(42);
+/// let out = BufWriter::new(File::create("walks.txt")?);
+/// let num_walks = node2vec.par_write(&Parallel::new(&graph, &ro_txn), out)?;
+/// for walk in node2vec.walks(&ro_txn)?.take(10) {
+///     println!("{:?}", walk?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomWalk {
+    walk_length: usize,
+    walks_per_node: usize,
+    p: f64,
+    q: f64,
+    seed: u64,
+    filter: Filter,
+}
+
+impl Default for RandomWalk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RandomWalk {
+    /// Create with walks of 80 vertices, 10 walks per vertex, uniform walks of `p` and
+    /// `q` 1, seed 0 and all edges.
+    pub fn new() -> Self {
+        RandomWalk {
+            walk_length: 80,
+            walks_per_node: 10,
+            p: 1.0,
+            q: 1.0,
+            seed: 0,
+            filter: Filter::default(),
+        }
+    }
+
+    /// Set the max number of vertices in a walk, which must be positive.
+    pub fn walk_length(mut self, walk_length: usize) -> Self {
+        self.walk_length = walk_length;
+        self
+    }
+
+    /// Set the number of walks from each vertex.
+    pub fn walks_per_node(mut self, walks_per_node: usize) -> Self {
+        self.walks_per_node = walks_per_node;
+        self
+    }
+
+    /// Set the return parameter of node2vec, which must be positive. A small `p` keeps
+    /// the walk close to where it starts.
+    pub fn p(mut self, p: f64) -> Self {
+        self.p = p;
+        self
+    }
+
+    /// Set the in-out parameter of node2vec, which must be positive. A small `q` pushes
+    /// the walk outwards.
+    pub fn q(mut self, q: f64) -> Self {
+        self.q = q;
+        self
+    }
+
+    /// Set the seed of the random choices.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Only follow edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, edge_labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.filter.edge_labels = labels(edge_labels);
+        self
+    }
+
+    fn walker<'t, T: TxnRead>(&self, txn: &'t T) -> Result<Walker<'t>> {
+        let valid = |x: f64| x.is_finite() && x > 0.0;
+        if self.walk_length == 0 || !valid(self.p) || !valid(self.q) {
+            return Err(Error::invalid_parameter());
+        }
+        Ok(Walker {
+            vcur: txn.vertex_cur()?,
+            edge_lids: self.filter.lids(txn)?.edge,
+            walk_length: self.walk_length,
+            p: self.p,
+            q: self.q,
+            seed: self.seed,
+        })
+    }
+
+    /// Get an iterator over the walks, which makes `walks_per_node` passes over the
+    /// vertices in ascending order of vid, and walks once from each vertex in each pass.
+    ///
+    /// # Errors
+    /// If a parameter is invalid or a label does not exist, an error is returned.
+    pub fn walks<'t, T: TxnRead>(&self, txn: &'t T) -> Result<Walks<'t>> {
+        let walker = self.walker(txn)?;
+        let mut starts = txn.vertex_cur()?;
+        starts.seek(0, true)?;
+        Ok(Walks {
+            walker,
+            starts,
+            round: 0,
+            walks_per_node: self.walks_per_node,
+        })
+    }
+
+    /// Write the walks to `out` in the order of [`RandomWalk::walks`], one walk per line
+    /// of space-separated vids. Returns the number of walks.
+    ///
+    /// `out` is written a walk at a time, so it should be buffered, e.g. by
+    /// [`std::io::BufWriter`].
+    ///
+    /// # Errors
+    /// If a parameter is invalid, a label does not exist or `out` fails, an error is
+    /// returned.
+    pub fn write<T: TxnRead, W: Write>(&self, txn: &T, mut out: W) -> Result<usize> {
+        let mut num_walks = 0;
+        let mut line = String::new();
+        for walk in self.walks(txn)? {
+            line.clear();
+            push_line(&mut line, &walk?);
+            out.write_all(line.as_bytes()).map_err(io_error)?;
+            num_walks += 1;
+        }
+        out.flush().map_err(io_error)?;
+        Ok(num_walks)
+    }
+
+    /// Write the walks to `out` like [`RandomWalk::write`] by the workers of `parallel`.
+    ///
+    /// Each worker walks `walks_per_node` times from each vertex it claims and writes
+    /// its walks in chunks, so the walks are not in any particular order.
+    ///
+    /// # Errors
+    /// If a parameter is invalid, a label does not exist or `out` fails, an error is
+    /// returned.
+    pub fn par_write<W: Write + Send>(&self, parallel: &Parallel<'_>, out: W) -> Result<usize> {
+        self.walker(parallel.txn())?;
+        let ranges = parallel.vid_ranges()?;
+        let out = Mutex::new(out);
+        let flush = |buf: &mut String| {
+            let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+            let res = out.write_all(buf.as_bytes()).map_err(io_error);
+            buf.clear();
+            res
+        };
+        let counts = parallel.run(|txn, stop| {
+            let mut walker = self.walker(txn)?;
+            let mut buf = String::new();
+            let num_walks = Parallel::fold_vertices(txn, &ranges, stop, 0, |num_walks, vcur| {
+                let start = vcur.id()?;
+                for round in 0..self.walks_per_node {
+                    push_line(&mut buf, &walker.walk(start, round)?);
+                    if buf.len() >= FLUSH_LEN {
+                        flush(&mut buf)?;
+                    }
+                }
+                Ok(num_walks + self.walks_per_node)
+            })?;
+            flush(&mut buf)?;
+            Ok(num_walks)
+        })?;
+        let mut out = out.into_inner().unwrap_or_else(|e| e.into_inner());
+        out.flush().map_err(io_error)?;
+        Ok(counts.into_iter().sum())
+    }
+}
+
+fn io_error(_: std::io::Error) -> Error {
+    Error::new("IO Error.".to_string())
+}
+
+fn push_line(buf: &mut String, walk: &[i64]) {
+    for (i, vid) in walk.iter().enumerate() {
+        if i > 0 {
+            buf.push(' ');
+        }
+        buf.push_str(&vid.to_string());
+    }
+    buf.push('\n');
+}
+
+/// An iterator over the walks generated by [`RandomWalk::walks`].
+pub struct Walks<'t> {
+    walker: Walker<'t>,
+    starts: VertexCur<'t>,
+    round: usize,
+    walks_per_node: usize,
+}
+
+impl<'t> Walks<'t> {
+    fn next_walk(&mut self) -> Result<Option<Vec<i64>>> {
+        while self.round < self.walks_per_node {
+            if self.starts.is_valid() {
+                let walk = self.walker.walk(self.starts.id()?, self.round)?;
+                self.starts.seek_to_next()?;
+                return Ok(Some(walk));
+            }
+            self.round += 1;
+            if self.round < self.walks_per_node {
+                self.starts.seek(0, true)?;
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'t> Iterator for Walks<'t> {
+    type Item = Result<Vec<i64>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let walk = self.next_walk();
+        if walk.is_err() {
+            // stop after an error
+            self.round = self.walks_per_node;
+        }
+        walk.transpose()
+    }
+}
+
+// Walks from vertices by reading their out neighbors with a cursor.
+struct Walker<'t> {
+    vcur: VertexCur<'t>,
+    edge_lids: Option<Vec<u16>>,
+    walk_length: usize,
+    p: f64,
+    q: f64,
+    seed: u64,
+}
+
+impl<'t> Walker<'t> {
+    // The sorted out neighbors of `vid`, once for each edge.
+    fn neighbors(&mut self, vid: i64) -> Result<Vec<i64>> {
+        let mut neighbors = vec![];
+        if !self.vcur.seek(vid, false)?.is_valid() {
+            return Ok(neighbors);
+        }
+        let mut ecur = self.vcur.out_edge_cursor()?;
+        while ecur.is_valid() {
+            let accepted = match &self.edge_lids {
+                Some(lids) => lids.contains(&ecur.lid()?),
+                None => true,
+            };
+            if accepted {
+                neighbors.push(ecur.dst()?);
+            }
+            ecur.seek_to_next()?;
+        }
+        neighbors.sort_unstable();
+        Ok(neighbors)
+    }
+
+    // The `round`-th walk from `start`.
+    fn walk(&mut self, start: i64, round: usize) -> Result<Vec<i64>> {
+        let mut rng = Rng::new(Rng::new(self.seed ^ start as u64).next_u64() ^ round as u64);
+        let uniform = self.p == 1.0 && self.q == 1.0;
+        let mut walk = vec![start];
+        let mut neighbors = self.neighbors(start)?;
+        let mut prev_neighbors = vec![];
+        while walk.len() < self.walk_length && !neighbors.is_empty() {
+            let next = if uniform || walk.len() == 1 {
+                neighbors[rng.below(neighbors.len())]
+            } else {
+                let prev = walk[walk.len() - 2];
+                biased(&mut rng, self.p, self.q, prev, &prev_neighbors, &neighbors)
+            };
+            walk.push(next);
+            if walk.len() < self.walk_length {
+                prev_neighbors = mem::replace(&mut neighbors, self.neighbors(next)?);
+            }
+        }
+        Ok(walk)
+    }
+}
+
+// Choose the next of `neighbors` by the node2vec weights, where the walk came from `prev`
+// whose neighbors are `prev_neighbors`.
+fn biased(
+    rng: &mut Rng,
+    p: f64,
+    q: f64,
+    prev: i64,
+    prev_neighbors: &[i64],
+    neighbors: &[i64],
+) -> i64 {
+    let weight = |x: i64| {
+        if x == prev {
+            1.0 / p
+        } else if prev_neighbors.binary_search(&x).is_ok() {
+            1.0
+        } else {
+            1.0 / q
+        }
+    };
+    let total: f64 = neighbors.iter().map(|&x| weight(x)).sum();
+    let mut r = rng.next_f64() * total;
+    for &x in neighbors {
+        r -= weight(x);
+        if r < 0.0 {
+            return x;
+        }
+    }
+    neighbors[neighbors.len() - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{biased, push_line, Rng};
+
+    #[test]
+    fn test_push_line() {
+        let mut buf = String::new();
+        push_line(&mut buf, &[1, 20, 3]);
+        push_line(&mut buf, &[4]);
+        assert_eq!(buf, "1 20 3\n4\n");
+    }
+
+    #[test]
+    fn test_biased() {
+        // from 0 to 1, whose neighbors are 0, 2 adjacent to 0, and 3
+        let (prev_neighbors, neighbors) = ([1, 2], [0, 2, 3]);
+        let count = |p, q| {
+            let mut rng = Rng::new(7);
+            let mut counts = [0; 4];
+            for _ in 0..10000 {
+                counts[biased(&mut rng, p, q, 0, &prev_neighbors, &neighbors) as usize] += 1;
+            }
+            counts
+        };
+        // weights 1, 1, 1
+        let counts = count(1.0, 1.0);
+        assert!(counts[1] == 0 && counts.iter().all(|&c| c == 0 || (3000..3700).contains(&c)));
+        // weights 10, 1, 0.1
+        let counts = count(0.1, 10.0);
+        assert!(counts[0] > 8500 && counts[3] < 200);
+        // weights 0.1, 1, 10
+        let counts = count(10.0, 0.1);
+        assert!(counts[3] > 8500 && counts[0] < 200);
+    }
+}
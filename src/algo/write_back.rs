@@ -0,0 +1,198 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    cursor::{VertexCurMut, VertexCursor, VertexCursorMut},
+    db::Graph,
+    field::{type_mismatch, FieldData, FieldSpec, FieldType},
+    txn::{BatchReport, TxnRead, TxnWrite},
+    Error, Result,
+};
+
+/// Write `results` into a field of vertices with the default [`WriteBack`] settings.
+pub fn write_back<I, V>(
+    graph: &Graph<'_>,
+    label: &str,
+    field_name: &str,
+    field_type: FieldType,
+    results: I,
+) -> Result<BatchReport<i64>>
+where
+    I: IntoIterator<Item = (i64, V)>,
+    V: Into<FieldData>,
+{
+    WriteBack::new(label, field_name, field_type).run(graph, results)
+}
+
+/// The progress of [`WriteBack::run_with_progress`], reported once a batch is done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteProgress {
+    /// The number of batches done.
+    pub batches: usize,
+    /// The number of vertices written so far.
+    pub written: usize,
+    /// The number of vertices failed to be written so far.
+    pub failed: usize,
+}
+
+/// `WriteBack` persists algorithm results into a field of vertices of a label.
+///
+/// The field is added to the label as an optional field defaulting to
+/// [`FieldData::Null`] if it is missing. Values are then written by
+/// [`VertexCursorMut::set_field_by_id`] in batches, each of which is committed in its own
+/// read-write transaction, so writing all vertices does not hold a single huge
+/// transaction. Like [`Graph::load_vertices`], rows are reported by their positions in
+/// `results`, and rows of a batch failed to commit are all reported as failed.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{
+///     algo::{self, WriteBack},
+///     db::OpenOptions,
+///     field::FieldType,
+/// };
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", false)?;
+/// let scores = graph.read(|txn| algo::pagerank(txn))?;
+/// let report = WriteBack::new("Account", "pagerank", FieldType::Double)
+///     .batch_size(1000)
+///     .run_with_progress(&graph, scores, |progress| {
+///         println!("{} vertices written", progress.written)
+///     })?;
+/// assert!(report.is_all_succeeded());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteBack {
+    label: String,
+    field_name: String,
+    field_type: FieldType,
+    batch_size: usize,
+}
+
+impl WriteBack {
+    /// Create writing into field `field_name` of type `field_type` of vertices of `label`,
+    /// committing every 10000 vertices.
+    pub fn new(label: &str, field_name: &str, field_type: FieldType) -> Self {
+        WriteBack {
+            label: label.to_string(),
+            field_name: field_name.to_string(),
+            field_type,
+            batch_size: 10000,
+        }
+    }
+
+    /// Set the number of vertices written in each transaction. 0 is treated as 1.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Write `results` of `(vid, value)` into the vertices.
+    ///
+    /// # Errors
+    /// See [`WriteBack::run_with_progress`].
+    pub fn run<I, V>(&self, graph: &Graph<'_>, results: I) -> Result<BatchReport<i64>>
+    where
+        I: IntoIterator<Item = (i64, V)>,
+        V: Into<FieldData>,
+    {
+        self.run_with_progress(graph, results, |_| {})
+    }
+
+    /// Write `results` of `(vid, value)` into the vertices, calling `progress` once each
+    /// batch is done.
+    ///
+    /// A vertex which does not exist or is not of the label fails to be written, as well
+    /// as a value which does not fit the field.
+    ///
+    /// # Errors
+    /// If the label does not exist, the field exists with another type, or a transaction
+    /// cannot be created, an error is returned. Batches committed before the error are
+    /// kept.
+    pub fn run_with_progress<I, V, F>(
+        &self,
+        graph: &Graph<'_>,
+        results: I,
+        mut progress: F,
+    ) -> Result<BatchReport<i64>>
+    where
+        I: IntoIterator<Item = (i64, V)>,
+        V: Into<FieldData>,
+        F: FnMut(WriteProgress),
+    {
+        self.ensure_field(graph)?;
+        let mut batches = 0;
+        graph.load_in_batches(
+            results.into_iter().map(|(vid, value)| (vid, value.into())),
+            self.batch_size,
+            |txn, batch| {
+                let lid = txn.vertex_label_id(&self.label)?;
+                let field_id = txn.vertex_field_id(lid, &self.field_name)?;
+                let mut vcur = txn.vertex_cur_mut()?;
+                let mut report = BatchReport::default();
+                for (pos, (vid, value)) in batch.into_iter().enumerate() {
+                    report.push(pos, set_field(&mut vcur, lid, field_id, vid, &value));
+                }
+                Ok(report)
+            },
+            |report| {
+                batches += 1;
+                progress(WriteProgress {
+                    batches,
+                    written: report.succeeded.len(),
+                    failed: report.failed.len(),
+                });
+            },
+        )
+    }
+
+    // Add the field as optional if it is missing.
+    fn ensure_field(&self, graph: &Graph<'_>) -> Result<()> {
+        let schema = graph.read(|txn| txn.vertex_schema(&self.label))?;
+        match schema.iter().find(|spec| spec.name == self.field_name) {
+            Some(spec) if spec.ty != self.field_type => {
+                Err(type_mismatch(self.field_type, spec.ty))
+            }
+            Some(_) => Ok(()),
+            None => graph
+                .alter_vertex_label_add_fields(
+                    &self.label,
+                    &[FieldSpec {
+                        name: self.field_name.clone(),
+                        ty: self.field_type,
+                        optional: true,
+                    }],
+                    &[FieldData::Null],
+                )
+                .map(|_| ()),
+        }
+    }
+}
+
+fn set_field(
+    vcur: &mut VertexCurMut<'_>,
+    lid: usize,
+    field_id: usize,
+    vid: i64,
+    value: &FieldData,
+) -> Result<i64> {
+    if !vcur.seek(vid, false)?.is_valid() || usize::from(vcur.lid()?) != lid {
+        return Err(Error::invalid_parameter());
+    }
+    vcur.set_field_by_id(field_id, value).map(|_| vid)
+}
@@ -15,7 +15,7 @@
 use std::marker::PhantomData;
 
 use crate::{
-    field::FieldData,
+    field::{FieldData, FieldValue},
     raw::{RawEdgeCursor, RawInEdgeCursor, RawOutEdgeCursor},
     types::EdgeUid,
     Result,
@@ -167,6 +167,27 @@ pub trait EdgeCursor {
     /// Get fields values with given field ids of the current edge.
     fn fields_by_ids(&self, ids: &[usize]) -> Result<Vec<FieldData>>;
 
+    /// Get field value with given name of the current edge, converted to `T`.
+    ///
+    /// Integer and float fields can be read as a wider Rust type, e.g. an `Int8` field as `i64`,
+    /// and optional fields can be read as `Option<T>`.
+    fn get<T: FieldValue>(&self, name: &str) -> Result<T>
+    where
+        Self: Sized,
+    {
+        T::from_field_data(self.field(name)?)
+    }
+
+    /// Get field value with given field id of the current edge, converted to `T`.
+    ///
+    /// See [`get`](Self::get) for the conversion rules.
+    fn get_by_id<T: FieldValue>(&self, id: usize) -> Result<T>
+    where
+        Self: Sized,
+    {
+        T::from_field_data(self.field_by_id(id)?)
+    }
+
     /// Get all fields return name and its value.
     fn all_fields(&self) -> Result<Vec<(String, FieldData)>>;
 }
@@ -347,7 +368,7 @@ pub struct InEdgeCur<'v> {
 }
 
 impl<'v> InEdgeCur<'v> {
-    pub(super) fn new(raw_cursor: RawInEdgeCursor) -> InEdgeCur<'v> {
+    pub(crate) fn new(raw_cursor: RawInEdgeCursor) -> InEdgeCur<'v> {
         InEdgeCur {
             inner: raw_cursor,
             _marker: PhantomData,
@@ -467,7 +488,7 @@ pub struct InEdgeCurMut<'v> {
 }
 
 impl<'v> InEdgeCurMut<'v> {
-    pub(super) fn new(raw_cursor: RawInEdgeCursor) -> InEdgeCurMut<'v> {
+    pub(crate) fn new(raw_cursor: RawInEdgeCursor) -> InEdgeCurMut<'v> {
         InEdgeCurMut {
             inner: raw_cursor,
             _marker: PhantomData,
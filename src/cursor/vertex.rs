@@ -14,7 +14,11 @@
 
 use std::marker::PhantomData;
 
-use crate::{field::FieldData, raw::RawVertexCursor, Result};
+use crate::{
+    field::{FieldData, FieldValue},
+    raw::RawVertexCursor,
+    Result,
+};
 
 use super::{
     iter::{IntoVertexFields, IntoVertexIds, IntoVertexIter, IntoVertexLabelIds, IntoVertexLabels},
@@ -123,6 +127,27 @@ pub trait VertexCursor {
     /// Get fields values with given field ids of the current vertex.
     fn fields_by_ids(&self, ids: &[usize]) -> Result<Vec<FieldData>>;
 
+    /// Get field value with given name of the current vertex, converted to `T`.
+    ///
+    /// Integer and float fields can be read as a wider Rust type, e.g. an `Int8` field as `i64`,
+    /// and optional fields can be read as `Option<T>`.
+    fn get<T: FieldValue>(&self, name: &str) -> Result<T>
+    where
+        Self: Sized,
+    {
+        T::from_field_data(self.field(name)?)
+    }
+
+    /// Get field value with given field id of the current vertex, converted to `T`.
+    ///
+    /// See [`get`](Self::get) for the conversion rules.
+    fn get_by_id<T: FieldValue>(&self, id: usize) -> Result<T>
+    where
+        Self: Sized,
+    {
+        T::from_field_data(self.field_by_id(id)?)
+    }
+
     /// Get all fields return name and its value.
     fn all_fields(&self) -> Result<Vec<(String, FieldData)>>;
 
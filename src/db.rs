@@ -15,7 +15,7 @@
 //! Manages each `Graph` instance with access controlled manager `Galaxy`.
 
 use std::path::Path;
-use std::{cmp, marker::PhantomData};
+use std::{cmp, marker::PhantomData, mem, thread};
 
 use libtugraph_sys::lgraph_api_graph_db_t;
 
@@ -23,10 +23,10 @@ use crate::{
     field::{FieldData, FieldSpec},
     raw::{RawGalaxy, RawGraphDB},
     role_info::RoleInfo,
-    txn::{RoTxn, RwTxn},
+    txn::{BatchReport, Committed, RetryPolicy, RoTxn, RwTxn, TxnWrite},
     types::{AccessLevel, EdgeUid},
     user_info::UserInfo,
-    Result,
+    ErrorKind, Result,
 };
 
 /// A standalone graph opened by [`Galaxy`].
@@ -108,6 +108,246 @@ impl<'gl> Graph<'gl> {
         self.inner.create_write_txn(optimistic).map(RwTxn::from_raw)
     }
 
+    /// Run `f` in a read-only transaction and return what it returns.
+    ///
+    /// The transaction is dropped once `f` returns.
+    ///
+    /// # Errors
+    /// Returns the error if the transaction cannot be created or `f` fails.
+    ///
+    /// # Examples
+    /// ```
+    /// use tugraph::{db::OpenOptions, txn::TxnRead, Error};
+    ///
+    /// let galaxy = OpenOptions::new()
+    ///     .create(true)
+    ///     .open("/tmp/rust_tugraph/doc/read", "admin", "73@TuGraph")?;
+    /// let graph = galaxy.open_graph("default", true)?;
+    /// let num_vertex_labels = graph.read(|txn| txn.num_vertex_labels())?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn read<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&RoTxn<'_>) -> Result<T>,
+    {
+        let txn = self.create_ro_txn()?;
+        f(&txn)
+    }
+
+    /// Run `f` in an optimistic read-write transaction with the default [`RetryPolicy`].
+    ///
+    /// See [`Graph::write_with`] for more details.
+    ///
+    /// # Errors
+    /// See [`Graph::write_with`].
+    ///
+    /// # Examples
+    /// ```
+    /// use tugraph::{db::OpenOptions, txn::TxnRead, Error};
+    ///
+    /// let galaxy = OpenOptions::new()
+    ///     .create(true)
+    ///     .open("/tmp/rust_tugraph/doc/write", "admin", "73@TuGraph")?;
+    /// let graph = galaxy.open_graph("default", false)?;
+    /// let committed = graph.write(|txn| {
+    ///     // do some update work in txn
+    ///     txn.num_vertex_labels()
+    /// })?;
+    /// assert!(committed.attempts >= 1);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn write<T, F>(&self, f: F) -> Result<Committed<T>>
+    where
+        F: FnMut(&mut RwTxn<'_>) -> Result<T>,
+    {
+        self.write_with(&RetryPolicy::default(), f)
+    }
+
+    /// Run `f` in an optimistic read-write transaction, retrying on conflicts.
+    ///
+    /// If `f` returns `Ok`, the transaction is committed. If `f` returns `Err`, the
+    /// transaction is aborted and the error is returned. Whenever `f` or the commit fails
+    /// with [`ErrorKind::TxnConflict`], the transaction is aborted and `f` is run again in
+    /// a new transaction after a backoff, until the attempts configured by `policy` are
+    /// exhausted.
+    ///
+    /// Since `f` may be called more than once, it should not have side effects outside
+    /// the transaction.
+    ///
+    /// # Errors
+    /// Returns the first error that is not a [`ErrorKind::TxnConflict`], or the last
+    /// conflict error if all attempts fail. The error of `f` is returned even if aborting
+    /// the transaction fails afterwards.
+    ///
+    /// # Examples
+    /// ```
+    /// use tugraph::{db::OpenOptions, txn::{RetryPolicy, TxnRead}, Error};
+    ///
+    /// let galaxy = OpenOptions::new()
+    ///     .create(true)
+    ///     .open("/tmp/rust_tugraph/doc/write_with", "admin", "73@TuGraph")?;
+    /// let graph = galaxy.open_graph("default", false)?;
+    /// let policy = RetryPolicy::new().max_attempts(10);
+    /// let committed = graph.write_with(&policy, |txn| txn.num_vertex_labels())?;
+    /// assert!(committed.attempts <= 10);
+    /// # Ok::<(), Error>(())
+    /// ```
+    ///
+    /// [`ErrorKind::TxnConflict`]: crate::ErrorKind::TxnConflict
+    pub fn write_with<T, F>(&self, policy: &RetryPolicy, mut f: F) -> Result<Committed<T>>
+    where
+        F: FnMut(&mut RwTxn<'_>) -> Result<T>,
+    {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let mut txn = self.create_rw_txn(true)?;
+            let res = match f(&mut txn) {
+                Ok(value) => txn.commit().map(|_| value),
+                Err(e) => {
+                    // the error of `f` matters, while a failed abort only means the
+                    // transaction is already invalid, whose changes are discarded anyway
+                    let _ = txn.abort();
+                    Err(e)
+                }
+            };
+            match res {
+                Ok(value) => return Ok(Committed { value, attempts }),
+                Err(e)
+                    if e.kind() == ErrorKind::TxnConflict
+                        && attempts < policy.get_max_attempts() =>
+                {
+                    thread::sleep(policy.delay(attempts));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Add vertices of the same label in batches, committing every `batch_size` rows.
+    ///
+    /// Each batch is added by [`TxnWrite::add_vertices`] in a new non-optimistic read-write
+    /// transaction, which is committed before the next batch starts. So a bulk load does
+    /// not hold a single huge transaction. If a batch fails to commit, all its rows are
+    /// reported as failed with the commit error, while rows of earlier batches are kept.
+    ///
+    /// A `batch_size` of 0 is treated as 1.
+    ///
+    /// # Errors
+    /// If the label or any field does not exist, or a transaction cannot be created, an error
+    /// is returned. Batches committed before the error are kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use tugraph::{
+    ///     db::OpenOptions,
+    ///     field::{FieldData, FieldSpec, FieldType},
+    ///     Error,
+    /// };
+    ///
+    /// let galaxy = OpenOptions::new()
+    ///     .create(true)
+    ///     .open("/tmp/rust_tugraph/doc/load_vertices", "admin", "73@TuGraph")?;
+    /// let graph = galaxy.open_graph("default", false)?;
+    /// graph.add_vertex_label(
+    ///     "Person",
+    ///     &[FieldSpec { name: "id".into(), ty: FieldType::Int64, optional: false }],
+    ///     "id",
+    /// )?;
+    /// let rows: Vec<_> = (0..10_000).map(|id| [FieldData::Int64(id)]).collect();
+    /// let report = graph.load_vertices("Person", &["id"], &rows, 1000)?;
+    /// assert!(report.is_all_succeeded());
+    /// # Ok::<(), Error>(())
+    /// ```
+    ///
+    /// [`TxnWrite::add_vertices`]: crate::txn::TxnWrite::add_vertices
+    pub fn load_vertices<'a, R, V>(
+        &self,
+        label: &str,
+        field_names: &[&str],
+        rows: R,
+        batch_size: usize,
+    ) -> Result<BatchReport<i64>>
+    where
+        R: IntoIterator<Item = V>,
+        V: IntoIterator<Item = &'a FieldData>,
+    {
+        self.load_in_batches(
+            rows,
+            batch_size,
+            |txn, batch| txn.add_vertices(label, field_names, batch),
+            |_| {},
+        )
+    }
+
+    /// Add edges of the same label in batches, committing every `batch_size` rows.
+    ///
+    /// Each row is a tuple of `(src, dst, field_values)`. See [`Graph::load_vertices`] for
+    /// how batches are committed and reported.
+    ///
+    /// # Errors
+    /// See [`Graph::load_vertices`].
+    pub fn load_edges<'a, R, V>(
+        &self,
+        label: &str,
+        field_names: &[&str],
+        rows: R,
+        batch_size: usize,
+    ) -> Result<BatchReport<EdgeUid>>
+    where
+        R: IntoIterator<Item = (i64, i64, V)>,
+        V: IntoIterator<Item = &'a FieldData>,
+    {
+        self.load_in_batches(
+            rows,
+            batch_size,
+            |txn, batch| txn.add_edges(label, field_names, batch),
+            |_| {},
+        )
+    }
+
+    // Run `add_batch` on every `batch_size` rows in a new transaction, then call
+    // `on_batch` with the report of all rows so far once the batch is committed or failed.
+    pub(crate) fn load_in_batches<R, T, F, P>(
+        &self,
+        rows: R,
+        batch_size: usize,
+        mut add_batch: F,
+        mut on_batch: P,
+    ) -> Result<BatchReport<T>>
+    where
+        R: IntoIterator,
+        F: FnMut(&mut RwTxn<'_>, Vec<R::Item>) -> Result<BatchReport<T>>,
+        P: FnMut(&BatchReport<T>),
+    {
+        let batch_size = cmp::max(batch_size, 1);
+        let mut rows = rows.into_iter();
+        let (mut report, mut offset) = (BatchReport::default(), 0);
+        loop {
+            let batch: Vec<_> = rows.by_ref().take(batch_size).collect();
+            if batch.is_empty() {
+                return Ok(report);
+            }
+            let num_rows = batch.len();
+            let mut txn = self.create_rw_txn(false)?;
+            let mut batch_report = match add_batch(&mut txn, batch) {
+                Ok(batch_report) => batch_report,
+                Err(e) => return txn.abort().and(Err(e)),
+            };
+            if let Err(e) = txn.commit() {
+                // none of the rows in this batch is added
+                let succeeded = mem::take(&mut batch_report.succeeded);
+                batch_report
+                    .failed
+                    .extend(succeeded.into_iter().map(|(pos, _)| (pos, e.clone())));
+                batch_report.failed.sort_by_key(|(pos, _)| *pos);
+            }
+            report.append(offset, batch_report);
+            offset += num_rows;
+            on_batch(&report);
+        }
+    }
+
     /// Fork a read-only transaction
     ///
     /// The resulting read-only transaction will share the same view
@@ -26,6 +26,7 @@ use crate::{
 ///
 /// See the [`FieldData`] for details.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FieldType {
     Null = ffi::lgraph_api_field_type_null as isize,
     Bool = ffi::lgraph_api_field_type_bool as isize,
@@ -57,7 +58,7 @@ impl TryFrom<u32> for FieldType {
             ffi::lgraph_api_field_type_datetime => Ok(FieldType::DateTime),
             ffi::lgraph_api_field_type_string => Ok(FieldType::String),
             ffi::lgraph_api_field_type_blob => Ok(FieldType::Blob),
-            _ => Err(crate::Error::new("Invalid parameter.".to_string())),
+            _ => Err(crate::Error::invalid_parameter()),
         }
     }
 }
@@ -75,7 +76,16 @@ impl Display for FieldType {
 }
 
 /// `FieldData` is a type that represents all possible values(and associated types) of field of graph element
+///
+/// With the `serde` feature, it is serialized in a tagged form which round-trips exactly,
+/// e.g. `{"type": "Int32", "value": 1}` and `{"type": "Null"}`, where dates and datetimes
+/// are ISO 8601 strings and blobs are base64 strings. See [`PlainFieldData`] for a plain form.
 #[derive(Clone, Debug, PartialEq, PartialOrd, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", content = "value")
+)]
 pub enum FieldData {
     #[default]
     Null,
@@ -89,10 +99,28 @@ pub enum FieldData {
     Date(Date),
     DateTime(DateTime),
     String(String),
-    Blob(Vec<u8>),
+    Blob(#[cfg_attr(feature = "serde", serde(with = "base64_blob"))] Vec<u8>),
 }
 
 impl FieldData {
+    /// Get the [`FieldType`] of this data.
+    pub fn ty(&self) -> FieldType {
+        match self {
+            FieldData::Null => FieldType::Null,
+            FieldData::Bool(_) => FieldType::Bool,
+            FieldData::Int8(_) => FieldType::Int8,
+            FieldData::Int16(_) => FieldType::Int16,
+            FieldData::Int32(_) => FieldType::Int32,
+            FieldData::Int64(_) => FieldType::Int64,
+            FieldData::Float(_) => FieldType::Float,
+            FieldData::Double(_) => FieldType::Double,
+            FieldData::Date(_) => FieldType::Date,
+            FieldData::DateTime(_) => FieldType::DateTime,
+            FieldData::String(_) => FieldType::String,
+            FieldData::Blob(_) => FieldType::Blob,
+        }
+    }
+
     pub(crate) fn from_raw_field_data(raw: &RawFieldData) -> Self {
         unsafe {
             match raw.ty() {
@@ -134,8 +162,293 @@ impl FieldData {
     }
 }
 
+pub(crate) fn type_mismatch(expected: FieldType, found: FieldType) -> crate::Error {
+    crate::Error::new(format!(
+        "Field type mismatch: expected {}, found {}.",
+        expected, found
+    ))
+}
+
+macro_rules! field_data_from_impl {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for FieldData {
+                fn from(value: $ty) -> Self {
+                    FieldData::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+field_data_from_impl!(
+    bool => Bool,
+    i8 => Int8,
+    i16 => Int16,
+    i32 => Int32,
+    i64 => Int64,
+    f32 => Float,
+    f64 => Double,
+    Date => Date,
+    DateTime => DateTime,
+    String => String,
+    &str => String,
+    Vec<u8> => Blob,
+    &[u8] => Blob,
+);
+
+impl<T: Into<FieldData>> From<Option<T>> for FieldData {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(FieldData::Null, Into::into)
+    }
+}
+
+// Each Rust type accepts the variant of its own field type, and the variants
+// that widen into it without loss, e.g. `i64` accepts `Int8`.
+macro_rules! field_data_try_into_impl {
+    ($($ty:ty => $expected:ident $(| $variant:ident)*),* $(,)?) => {
+        $(
+            impl TryFrom<FieldData> for $ty {
+                type Error = crate::Error;
+
+                fn try_from(data: FieldData) -> crate::Result<Self> {
+                    match data {
+                        FieldData::$expected(v) => Ok(v),
+                        $(FieldData::$variant(v) => Ok(v.into()),)*
+                        data => Err(type_mismatch(FieldType::$expected, data.ty())),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+field_data_try_into_impl!(
+    bool => Bool,
+    i8 => Int8,
+    i16 => Int16 | Int8,
+    i32 => Int32 | Int8 | Int16,
+    i64 => Int64 | Int8 | Int16 | Int32,
+    f32 => Float,
+    f64 => Double | Float,
+    Date => Date,
+    DateTime => DateTime,
+    String => String,
+    Vec<u8> => Blob,
+);
+
+/// `FieldValue` maps a Rust type to a [`FieldType`], and converts its values from and
+/// into [`FieldData`].
+///
+/// It is implemented for every Rust type that converts from and into [`FieldData`]
+/// by [`From`] and [`TryFrom`]. `Option<T>` maps to an optional field of `T`,
+/// with `None` being [`FieldData::Null`].
+pub trait FieldValue: Sized {
+    /// The field type this Rust type maps to.
+    const TYPE: FieldType;
+
+    /// Whether the field this Rust type maps to is optional.
+    const OPTIONAL: bool = false;
+
+    /// Converts the value into [`FieldData`].
+    fn to_field_data(&self) -> FieldData;
+
+    /// Converts [`FieldData`] into the value.
+    ///
+    /// # Errors
+    /// If the data can not be converted without loss, an [`ErrorKind::TypeMismatch`]
+    /// is returned.
+    ///
+    /// [`ErrorKind::TypeMismatch`]: crate::ErrorKind::TypeMismatch
+    fn from_field_data(data: FieldData) -> crate::Result<Self>;
+}
+
+macro_rules! field_value_impl {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl FieldValue for $ty {
+                const TYPE: FieldType = FieldType::$variant;
+
+                fn to_field_data(&self) -> FieldData {
+                    FieldData::from(self.clone())
+                }
+
+                fn from_field_data(data: FieldData) -> crate::Result<Self> {
+                    Self::try_from(data)
+                }
+            }
+        )*
+    };
+}
+
+field_value_impl!(
+    bool => Bool,
+    i8 => Int8,
+    i16 => Int16,
+    i32 => Int32,
+    i64 => Int64,
+    f32 => Float,
+    f64 => Double,
+    Date => Date,
+    DateTime => DateTime,
+    String => String,
+    Vec<u8> => Blob,
+);
+
+impl<T: FieldValue> FieldValue for Option<T> {
+    const TYPE: FieldType = T::TYPE;
+    const OPTIONAL: bool = true;
+
+    fn to_field_data(&self) -> FieldData {
+        match self {
+            Some(v) => v.to_field_data(),
+            None => FieldData::Null,
+        }
+    }
+
+    fn from_field_data(data: FieldData) -> crate::Result<Self> {
+        match data {
+            FieldData::Null => Ok(None),
+            data => T::from_field_data(data).map(Some),
+        }
+    }
+}
+
+/// `PlainFieldData` wraps a [`FieldData`] to serialize it in a plain form, for APIs.
+///
+/// The data is serialized as a bare value: `Null` as `null`, dates and datetimes as ISO 8601
+/// strings and blobs as base64 strings. The form is lossy, so deserializing gives the widest
+/// type: integers become `Int64`, floats become `Double` and strings become `String`.
+/// Use [`FieldValue`] or [`TryFrom`] to convert them to the expected Rust types.
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct PlainFieldData(pub FieldData);
+
+#[cfg(feature = "serde")]
+impl From<FieldData> for PlainFieldData {
+    fn from(data: FieldData) -> Self {
+        PlainFieldData(data)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<PlainFieldData> for FieldData {
+    fn from(plain: PlainFieldData) -> Self {
+        plain.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PlainFieldData {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.0 {
+            FieldData::Null => serializer.serialize_none(),
+            FieldData::Bool(b) => serializer.serialize_bool(*b),
+            FieldData::Int8(i) => serializer.serialize_i8(*i),
+            FieldData::Int16(i) => serializer.serialize_i16(*i),
+            FieldData::Int32(i) => serializer.serialize_i32(*i),
+            FieldData::Int64(i) => serializer.serialize_i64(*i),
+            FieldData::Float(f) => serializer.serialize_f32(*f),
+            FieldData::Double(d) => serializer.serialize_f64(*d),
+            FieldData::Date(date) => serde::Serialize::serialize(date, serializer),
+            FieldData::DateTime(datetime) => serde::Serialize::serialize(datetime, serializer),
+            FieldData::String(str) => serializer.serialize_str(str),
+            FieldData::Blob(b) => base64_blob::serialize(b, serializer),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PlainFieldData {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PlainVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for PlainVisitor {
+            type Value = FieldData;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a null, bool, number, string or bytes")
+            }
+
+            fn visit_unit<E: serde::de::Error>(self) -> Result<FieldData, E> {
+                Ok(FieldData::Null)
+            }
+
+            fn visit_none<E: serde::de::Error>(self) -> Result<FieldData, E> {
+                Ok(FieldData::Null)
+            }
+
+            fn visit_some<D: serde::Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<FieldData, D::Error> {
+                deserializer.deserialize_any(self)
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<FieldData, E> {
+                Ok(FieldData::Bool(v))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<FieldData, E> {
+                Ok(FieldData::Int64(v))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<FieldData, E> {
+                i64::try_from(v)
+                    .map(FieldData::Int64)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<FieldData, E> {
+                Ok(FieldData::Double(v))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<FieldData, E> {
+                Ok(FieldData::String(v.to_string()))
+            }
+
+            fn visit_string<E: serde::de::Error>(self, v: String) -> Result<FieldData, E> {
+                Ok(FieldData::String(v))
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<FieldData, E> {
+                Ok(FieldData::Blob(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<FieldData, E> {
+                Ok(FieldData::Blob(v))
+            }
+        }
+
+        deserializer
+            .deserialize_any(PlainVisitor)
+            .map(PlainFieldData)
+    }
+}
+
+// Serialize blobs as base64 strings.
+#[cfg(feature = "serde")]
+mod base64_blob {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    pub(super) fn serialize<S: serde::Serializer>(
+        blob: &[u8],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(blob))
+    }
+
+    pub(super) fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let encoded: String = serde::Deserialize::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
 /// `FieldSpec` describes the characteristics of a particular field.
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FieldSpec {
     /// The name of field. e.g. the age of a Person element
     pub name: String,
@@ -174,7 +487,8 @@ mod tests {
         types::{Date, DateTime},
     };
 
-    use super::{FieldData, FieldSpec, FieldType};
+    use super::{FieldData, FieldSpec, FieldType, FieldValue};
+    use crate::ErrorKind;
     #[test]
     fn test_field_type() {
         let t_null: FieldType = ffi::lgraph_api_field_type_null.try_into().unwrap();
@@ -291,4 +605,128 @@ mod tests {
         assert_eq!(raw_fs.ty(), FieldType::String);
         assert!(raw_fs.optional())
     }
+
+    #[test]
+    fn test_field_value() {
+        assert_eq!(<i32 as FieldValue>::TYPE, FieldType::Int32);
+        const _: () = assert!(!<i32 as FieldValue>::OPTIONAL);
+        assert_eq!(1_i32.to_field_data(), FieldData::Int32(1));
+        assert_eq!(i32::from_field_data(FieldData::Int32(1)), Ok(1));
+        assert!(i32::from_field_data(FieldData::Int64(1)).is_err());
+        assert!(i32::from_field_data(FieldData::Null).is_err());
+
+        assert_eq!(<Option<String> as FieldValue>::TYPE, FieldType::String);
+        const _: () = assert!(<Option<String> as FieldValue>::OPTIONAL);
+        assert_eq!(None::<String>.to_field_data(), FieldData::Null);
+        assert_eq!(
+            Some("a".to_string()).to_field_data(),
+            FieldData::String("a".to_string())
+        );
+        assert_eq!(Option::<String>::from_field_data(FieldData::Null), Ok(None));
+        assert_eq!(
+            Option::<Vec<u8>>::from_field_data(FieldData::Blob(b"a".to_vec())),
+            Ok(Some(b"a".to_vec()))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_field_data_serde() {
+        use super::PlainFieldData;
+        use crate::types::{Date, DateTime};
+        use chrono::NaiveDate;
+        use serde_json::json;
+
+        let date = Date::from_native(NaiveDate::from_ymd_opt(2016, 7, 8).unwrap());
+        let datetime = DateTime::from_native(date.and_hms_opt(9, 10, 11).unwrap());
+        let cases = [
+            (FieldData::Null, json!({"type": "Null"}), json!(null)),
+            (
+                FieldData::Bool(true),
+                json!({"type": "Bool", "value": true}),
+                json!(true),
+            ),
+            (
+                FieldData::Int8(-8),
+                json!({"type": "Int8", "value": -8}),
+                json!(-8),
+            ),
+            (
+                FieldData::Float(0.5),
+                json!({"type": "Float", "value": 0.5}),
+                json!(0.5),
+            ),
+            (
+                FieldData::Date(date),
+                json!({"type": "Date", "value": "2016-07-08"}),
+                json!("2016-07-08"),
+            ),
+            (
+                FieldData::DateTime(datetime),
+                json!({"type": "DateTime", "value": "2016-07-08T09:10:11"}),
+                json!("2016-07-08T09:10:11"),
+            ),
+            (
+                FieldData::String("a".into()),
+                json!({"type": "String", "value": "a"}),
+                json!("a"),
+            ),
+            (
+                FieldData::Blob(b"rawdata".to_vec()),
+                json!({"type": "Blob", "value": "cmF3ZGF0YQ=="}),
+                json!("cmF3ZGF0YQ=="),
+            ),
+        ];
+        for (data, tagged, plain) in cases {
+            assert_eq!(serde_json::to_value(&data).unwrap(), tagged);
+            assert_eq!(serde_json::from_value::<FieldData>(tagged).unwrap(), data);
+            assert_eq!(
+                serde_json::to_value(PlainFieldData(data.clone())).unwrap(),
+                plain
+            );
+        }
+
+        // plain form gives the widest type
+        let plain: PlainFieldData = serde_json::from_value(json!(-8)).unwrap();
+        assert_eq!(plain.0, FieldData::Int64(-8));
+        let plain: PlainFieldData = serde_json::from_value(json!(0.5)).unwrap();
+        assert_eq!(plain.0, FieldData::Double(0.5));
+        let plain: PlainFieldData = serde_json::from_value(json!(null)).unwrap();
+        assert_eq!(plain.0, FieldData::Null);
+
+        let spec = FieldSpec {
+            name: "age".into(),
+            ty: FieldType::Int32,
+            optional: true,
+        };
+        let value = json!({"name": "age", "ty": "Int32", "optional": true});
+        assert_eq!(serde_json::to_value(&spec).unwrap(), value);
+        assert_eq!(serde_json::from_value::<FieldSpec>(value).unwrap(), spec);
+    }
+
+    #[test]
+    fn test_field_data_conversion() {
+        assert_eq!(FieldData::from(true), FieldData::Bool(true));
+        assert_eq!(FieldData::from(1_i8), FieldData::Int8(1));
+        assert_eq!(FieldData::from(1.5_f64), FieldData::Double(1.5));
+        assert_eq!(FieldData::from("a"), FieldData::String("a".to_string()));
+        assert_eq!(FieldData::from(&b"a"[..]), FieldData::Blob(b"a".to_vec()));
+        assert_eq!(FieldData::from(Some(1_i64)), FieldData::Int64(1));
+        assert_eq!(FieldData::from(None::<i64>), FieldData::Null);
+
+        // widening
+        assert_eq!(i64::try_from(FieldData::Int8(-1)), Ok(-1));
+        assert_eq!(i64::try_from(FieldData::Int32(1 << 20)), Ok(1 << 20));
+        assert_eq!(i32::try_from(FieldData::Int16(-2)), Ok(-2));
+        assert_eq!(f64::try_from(FieldData::Float(0.5)), Ok(0.5));
+
+        // lossy or mismatched
+        let e = i32::try_from(FieldData::Int64(1)).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::TypeMismatch);
+        assert_eq!(e.msg(), "Field type mismatch: expected Int32, found Int64.");
+        assert!(f32::try_from(FieldData::Double(0.5)).is_err());
+        assert!(f64::try_from(FieldData::Int32(1)).is_err());
+        assert!(String::try_from(FieldData::Blob(vec![])).is_err());
+        assert!(bool::try_from(FieldData::Null).is_err());
+    }
 }
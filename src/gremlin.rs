@@ -0,0 +1,1502 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A fluent, Gremlin-style traversal builder.
+//!
+//! A [`GraphTraversalSource`] starts a [`GraphTraversal`] from some vertices, which is
+//! then chained with steps. Steps are lazy: nothing is read until the traversal is
+//! iterated by [`GraphTraversal::iter`] or collected by [`GraphTraversal::to_list`],
+//! which evaluate it streaming through the cursors of the transaction. Only `order`
+//! and `count` are barriers that gather all the traversers reaching them.
+//!
+//! Steps taking labels accept a `&str`, an array or a `Vec` of labels, or `()` for all
+//! labels, as [`Labels`] describes.
+//!
+//! ```no_run
+//! use tugraph::{
+//!     db::OpenOptions,
+//!     gremlin::{gt, GraphTraversalSource},
+//! };
+//!
+//! # fn main() -> Result<(), tugraph::Error> {
+//! let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+//! let graph = galaxy.open_graph("default", true)?;
+//! let ro_txn = graph.create_ro_txn()?;
+//! let g = GraphTraversalSource::new(&ro_txn);
+//! let names = g
+//!     .v(0)
+//!     .out("knows")
+//!     .has("age", gt(30))
+//!     .dedup()
+//!     .limit(10)
+//!     .values("name")
+//!     .to_list()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    iter, vec,
+};
+
+use crate::{
+    cursor::{EdgeCursor, VertexCur, VertexCursor},
+    field::{FieldData, FieldType},
+    traverse::Direction,
+    txn::TxnRead,
+    types::{Date, DateTime, EdgeUid},
+    Error, Result,
+};
+
+/// A value a traversal is at.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Value {
+    /// A vertex of the vid.
+    Vertex(i64),
+    /// An edge of the uid.
+    Edge(EdgeUid),
+    /// A field value or a count.
+    Data(FieldData),
+    /// A path, produced by [`GraphTraversal::path`].
+    List(Vec<Value>),
+    /// Labeled values, produced by [`GraphTraversal::select`] with many labels.
+    Map(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Get the vid if this is a vertex.
+    pub fn as_vertex(&self) -> Option<i64> {
+        match self {
+            Value::Vertex(vid) => Some(*vid),
+            _ => None,
+        }
+    }
+
+    /// Get the uid if this is an edge.
+    pub fn as_edge(&self) -> Option<EdgeUid> {
+        match self {
+            Value::Edge(uid) => Some(*uid),
+            _ => None,
+        }
+    }
+
+    /// Get the field value if this is a data.
+    pub fn as_data(&self) -> Option<&FieldData> {
+        match self {
+            Value::Data(data) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+/// A predicate on field values, used by [`GraphTraversal::has`].
+///
+/// Numeric values compare by their numbers whatever their types, e.g. `Int32(30)` equals
+/// `Int64(30)` and `Double(30.0)`, while values of other types only compare to values of
+/// the same type. `Null` satisfies no predicate.
+#[derive(Debug, Clone, PartialEq)]
+pub enum P {
+    /// Equal to the value.
+    Eq(FieldData),
+    /// Not equal to the value.
+    Neq(FieldData),
+    /// Greater than the value.
+    Gt(FieldData),
+    /// Greater than or equal to the value.
+    Gte(FieldData),
+    /// Less than the value.
+    Lt(FieldData),
+    /// Less than or equal to the value.
+    Lte(FieldData),
+    /// In the range `[start, end)`.
+    Between(FieldData, FieldData),
+    /// In the range `(start, end)`.
+    Inside(FieldData, FieldData),
+    /// Less than `start` or greater than `end`.
+    Outside(FieldData, FieldData),
+    /// Equal to any of the values.
+    Within(Vec<FieldData>),
+    /// Equal to none of the values.
+    Without(Vec<FieldData>),
+}
+
+impl P {
+    /// Check whether `data` satisfies the predicate.
+    pub fn test(&self, data: &FieldData) -> bool {
+        if *data == FieldData::Null {
+            return false;
+        }
+        let cmp = |other: &FieldData| compare(data, other);
+        match self {
+            P::Eq(v) => cmp(v) == Some(Ordering::Equal),
+            P::Neq(v) => cmp(v) != Some(Ordering::Equal),
+            P::Gt(v) => cmp(v) == Some(Ordering::Greater),
+            P::Gte(v) => matches!(cmp(v), Some(Ordering::Greater | Ordering::Equal)),
+            P::Lt(v) => cmp(v) == Some(Ordering::Less),
+            P::Lte(v) => matches!(cmp(v), Some(Ordering::Less | Ordering::Equal)),
+            P::Between(start, end) => {
+                matches!(cmp(start), Some(Ordering::Greater | Ordering::Equal))
+                    && cmp(end) == Some(Ordering::Less)
+            }
+            P::Inside(start, end) => {
+                cmp(start) == Some(Ordering::Greater) && cmp(end) == Some(Ordering::Less)
+            }
+            P::Outside(start, end) => {
+                cmp(start) == Some(Ordering::Less) || cmp(end) == Some(Ordering::Greater)
+            }
+            P::Within(vs) => vs.iter().any(|v| cmp(v) == Some(Ordering::Equal)),
+            P::Without(vs) => vs.iter().all(|v| cmp(v) != Some(Ordering::Equal)),
+        }
+    }
+}
+
+/// Equal to `value`.
+pub fn eq<V: Into<FieldData>>(value: V) -> P {
+    P::Eq(value.into())
+}
+
+/// Not equal to `value`.
+pub fn neq<V: Into<FieldData>>(value: V) -> P {
+    P::Neq(value.into())
+}
+
+/// Greater than `value`.
+pub fn gt<V: Into<FieldData>>(value: V) -> P {
+    P::Gt(value.into())
+}
+
+/// Greater than or equal to `value`.
+pub fn gte<V: Into<FieldData>>(value: V) -> P {
+    P::Gte(value.into())
+}
+
+/// Less than `value`.
+pub fn lt<V: Into<FieldData>>(value: V) -> P {
+    P::Lt(value.into())
+}
+
+/// Less than or equal to `value`.
+pub fn lte<V: Into<FieldData>>(value: V) -> P {
+    P::Lte(value.into())
+}
+
+/// In the range `[start, end)`.
+pub fn between<V: Into<FieldData>>(start: V, end: V) -> P {
+    P::Between(start.into(), end.into())
+}
+
+/// In the range `(start, end)`.
+pub fn inside<V: Into<FieldData>>(start: V, end: V) -> P {
+    P::Inside(start.into(), end.into())
+}
+
+/// Less than `start` or greater than `end`.
+pub fn outside<V: Into<FieldData>>(start: V, end: V) -> P {
+    P::Outside(start.into(), end.into())
+}
+
+/// Equal to any of `values`.
+pub fn within<I, V>(values: I) -> P
+where
+    I: IntoIterator<Item = V>,
+    V: Into<FieldData>,
+{
+    P::Within(values.into_iter().map(Into::into).collect())
+}
+
+/// Equal to none of `values`.
+pub fn without<I, V>(values: I) -> P
+where
+    I: IntoIterator<Item = V>,
+    V: Into<FieldData>,
+{
+    P::Without(values.into_iter().map(Into::into).collect())
+}
+
+/// The sort order of [`GraphTraversal::order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Order {
+    /// Ascending order.
+    #[default]
+    Asc,
+    /// Descending order.
+    Desc,
+}
+
+/// The labels given to a step, where `()` means all labels.
+pub trait Labels {
+    /// Get the labels, or `None` for all labels.
+    fn into_labels(self) -> Option<Vec<String>>;
+}
+
+impl Labels for () {
+    fn into_labels(self) -> Option<Vec<String>> {
+        None
+    }
+}
+
+impl Labels for &str {
+    fn into_labels(self) -> Option<Vec<String>> {
+        Some(vec![self.to_string()])
+    }
+}
+
+impl Labels for String {
+    fn into_labels(self) -> Option<Vec<String>> {
+        Some(vec![self])
+    }
+}
+
+impl<S: Into<String>, const N: usize> Labels for [S; N] {
+    fn into_labels(self) -> Option<Vec<String>> {
+        Some(self.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<S: Into<String>> Labels for Vec<S> {
+    fn into_labels(self) -> Option<Vec<String>> {
+        Some(self.into_iter().map(Into::into).collect())
+    }
+}
+
+/// The vids to start from, where `()` means all vertices.
+pub trait Ids {
+    /// Get the vids, or `None` for all vertices.
+    fn into_ids(self) -> Option<Vec<i64>>;
+}
+
+impl Ids for () {
+    fn into_ids(self) -> Option<Vec<i64>> {
+        None
+    }
+}
+
+impl Ids for i64 {
+    fn into_ids(self) -> Option<Vec<i64>> {
+        Some(vec![self])
+    }
+}
+
+impl<const N: usize> Ids for [i64; N] {
+    fn into_ids(self) -> Option<Vec<i64>> {
+        Some(self.to_vec())
+    }
+}
+
+impl Ids for &[i64] {
+    fn into_ids(self) -> Option<Vec<i64>> {
+        Some(self.to_vec())
+    }
+}
+
+impl Ids for Vec<i64> {
+    fn into_ids(self) -> Option<Vec<i64>> {
+        Some(self)
+    }
+}
+
+/// `GraphTraversalSource` spawns [`GraphTraversal`]s over a transaction.
+pub struct GraphTraversalSource<'t, T> {
+    txn: &'t T,
+}
+
+impl<'t, T: TxnRead> GraphTraversalSource<'t, T> {
+    /// Create a source of traversals reading through `txn`.
+    pub fn new(txn: &'t T) -> Self {
+        GraphTraversalSource { txn }
+    }
+
+    /// Start a traversal from the vertices of `ids`, or all vertices by `()`.
+    ///
+    /// Vids of no vertex are skipped.
+    pub fn v<I: Ids>(&self, ids: I) -> GraphTraversal<'t, T> {
+        GraphTraversal {
+            txn: self.txn,
+            starts: ids.into_ids(),
+            steps: vec![],
+        }
+    }
+}
+
+enum Step {
+    Adjacent {
+        direction: Direction,
+        labels: Option<Vec<String>>,
+        edges: bool,
+    },
+    EdgeVertex(Direction),
+    Has(String, P),
+    HasLabel(Option<Vec<String>>),
+    Where(Vec<Step>),
+    Dedup,
+    Limit(usize),
+    Order(Option<String>, Order),
+    Count,
+    Path,
+    As(String),
+    Select(Option<Vec<String>>),
+    Values(String),
+}
+
+/// `GraphTraversal` is a lazy chain of steps started by a [`GraphTraversalSource`].
+///
+/// Steps that do not apply to the value a traverser is at drop the traverser, e.g.
+/// `out` drops edges and `has` drops field values, so do `has` and `values` if the
+/// field is missing from the label of the element or is null.
+///
+/// # Examples
+/// ```no_run
+/// use tugraph::{
+///     db::OpenOptions,
+///     gremlin::{eq, GraphTraversalSource, Order},
+/// };
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let g = GraphTraversalSource::new(&ro_txn);
+/// // found by the index on `Person.name` if there is one
+/// let friends = g
+///     .v(())
+///     .has_label("Person")
+///     .has("name", eq("marko"))
+///     .as_("a")
+///     .out_e("knows")
+///     .in_v()
+///     .where_(|t| t.out("created"))
+///     .order_by("age", Order::Desc)
+///     .as_("b")
+///     .select(["a", "b"])
+///     .to_list()?;
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub struct GraphTraversal<'t, T> {
+    txn: &'t T,
+    starts: Option<Vec<i64>>,
+    steps: Vec<Step>,
+}
+
+impl<'t, T: TxnRead> GraphTraversal<'t, T> {
+    fn step(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Move to the vertices out edges of the labels point to.
+    pub fn out<L: Labels>(self, labels: L) -> Self {
+        self.adjacent(Direction::Out, labels, false)
+    }
+
+    /// Move to the vertices in edges of the labels come from.
+    pub fn in_<L: Labels>(self, labels: L) -> Self {
+        self.adjacent(Direction::In, labels, false)
+    }
+
+    /// Move to the vertices adjacent through edges of the labels in both directions.
+    pub fn both<L: Labels>(self, labels: L) -> Self {
+        self.adjacent(Direction::Both, labels, false)
+    }
+
+    /// Move to the out edges of the labels.
+    pub fn out_e<L: Labels>(self, labels: L) -> Self {
+        self.adjacent(Direction::Out, labels, true)
+    }
+
+    /// Move to the in edges of the labels.
+    pub fn in_e<L: Labels>(self, labels: L) -> Self {
+        self.adjacent(Direction::In, labels, true)
+    }
+
+    /// Move to both the out and in edges of the labels.
+    pub fn both_e<L: Labels>(self, labels: L) -> Self {
+        self.adjacent(Direction::Both, labels, true)
+    }
+
+    fn adjacent<L: Labels>(self, direction: Direction, labels: L, edges: bool) -> Self {
+        self.step(Step::Adjacent {
+            direction,
+            labels: labels.into_labels(),
+            edges,
+        })
+    }
+
+    /// Move from an edge to the vertex it points to.
+    pub fn in_v(self) -> Self {
+        self.step(Step::EdgeVertex(Direction::In))
+    }
+
+    /// Move from an edge to the vertex it comes from.
+    pub fn out_v(self) -> Self {
+        self.step(Step::EdgeVertex(Direction::Out))
+    }
+
+    /// Move from an edge to both its vertices, the source first.
+    pub fn both_v(self) -> Self {
+        self.step(Step::EdgeVertex(Direction::Both))
+    }
+
+    /// Keep the elements whose field `name` satisfies `predicate`.
+    ///
+    /// Right after starting from all vertices and [`GraphTraversal::has_label`] of a
+    /// single label, an [`eq`] or [`within`] predicate on an indexed field looks the
+    /// vertices up by the index instead of scanning all vertices.
+    pub fn has(self, name: &str, predicate: P) -> Self {
+        self.step(Step::Has(name.to_string(), predicate))
+    }
+
+    /// Keep the elements of the labels.
+    pub fn has_label<L: Labels>(self, labels: L) -> Self {
+        self.step(Step::HasLabel(labels.into_labels()))
+    }
+
+    /// Keep the traversers from which the traversal built by `sub` yields anything.
+    ///
+    /// `sub` is given an empty traversal to chain the steps on.
+    pub fn where_<F>(self, sub: F) -> Self
+    where
+        F: FnOnce(Self) -> Self,
+    {
+        let anonymous = GraphTraversal {
+            txn: self.txn,
+            starts: Some(vec![]),
+            steps: vec![],
+        };
+        self.step(Step::Where(sub(anonymous).steps))
+    }
+
+    /// Drop the traversers at a value seen before.
+    pub fn dedup(self) -> Self {
+        self.step(Step::Dedup)
+    }
+
+    /// Keep the first `n` traversers.
+    pub fn limit(self, n: usize) -> Self {
+        self.step(Step::Limit(n))
+    }
+
+    /// Sort the traversers by their values.
+    ///
+    /// Numbers compare numerically, with NaN above all of them, and other values within
+    /// their types. Values of different types sort by type: null, booleans, numbers,
+    /// dates, datetimes, strings, blobs, vertices, edges, lists and maps.
+    pub fn order(self, order: Order) -> Self {
+        self.step(Step::Order(None, order))
+    }
+
+    /// Sort the traversers by the field `name` of their elements as [`order`](Self::order),
+    /// where missing fields sort as null, i.e. first in ascending order.
+    pub fn order_by(self, name: &str, order: Order) -> Self {
+        self.step(Step::Order(Some(name.to_string()), order))
+    }
+
+    /// Replace the traversers with the number of them as an `Int64`.
+    pub fn count(self) -> Self {
+        self.step(Step::Count)
+    }
+
+    /// Move to the path of values each traverser has been at, as a [`Value::List`].
+    pub fn path(self) -> Self {
+        self.step(Step::Path)
+    }
+
+    /// Label the current value of each traverser, to be selected later.
+    pub fn as_(self, label: &str) -> Self {
+        self.step(Step::As(label.to_string()))
+    }
+
+    /// Move to the values labeled by [`GraphTraversal::as_`].
+    ///
+    /// A single label selects the value itself, while many labels, or `()` for all
+    /// labels, select a [`Value::Map`]. Traversers missing a label are dropped.
+    pub fn select<L: Labels>(self, labels: L) -> Self {
+        self.step(Step::Select(labels.into_labels()))
+    }
+
+    /// Move to the value of field `name` of each element.
+    pub fn values(self, name: &str) -> Self {
+        self.step(Step::Values(name.to_string()))
+    }
+
+    /// Evaluate the traversal, yielding the values the traversers end at.
+    ///
+    /// # Errors
+    /// If a label given to a step does not exist, an error is returned.
+    pub fn iter(self) -> Result<Iter<'t>> {
+        let stages = resolve(self.txn, &self.steps)?;
+        let track = has_path(&stages);
+        let input = match index_lookup(self.txn, &self.starts, &self.steps, track)? {
+            Some(input) => input,
+            None => starts(self.txn, self.starts, track)?,
+        };
+        Ok(Iter {
+            stream: stream(self.txn, &stages, input)?,
+            failed: false,
+        })
+    }
+
+    /// Evaluate the traversal and collect the values the traversers end at.
+    ///
+    /// # Errors
+    /// If a label given to a step does not exist, or reading the graph fails, an
+    /// error is returned.
+    pub fn to_list(self) -> Result<Vec<Value>> {
+        self.iter()?.collect()
+    }
+}
+
+/// An iterator over the values a [`GraphTraversal`] ends at.
+///
+/// It stops after yielding an error.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Iter<'t> {
+    stream: Stream<'t>,
+    failed: bool,
+}
+
+impl<'t> Iterator for Iter<'t> {
+    type Item = Result<Value>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        match self.stream.next()? {
+            Ok(traverser) => Some(Ok(traverser.value)),
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Traverser {
+    value: Value,
+    // only tracked if there is a `path` step
+    path: Option<Vec<Value>>,
+    labels: Vec<(String, Value)>,
+}
+
+impl Traverser {
+    fn new(value: Value, track: bool) -> Self {
+        Traverser {
+            path: track.then(|| vec![value.clone()]),
+            value,
+            labels: vec![],
+        }
+    }
+
+    // The traverser moved to `value`.
+    fn moved(&self, value: Value) -> Self {
+        Traverser {
+            path: self.path.as_ref().map(|path| {
+                let mut path = path.clone();
+                path.push(value.clone());
+                path
+            }),
+            value,
+            labels: self.labels.clone(),
+        }
+    }
+}
+
+type Stream<'t> = Box<dyn Iterator<Item = Result<Traverser>> + 't>;
+
+// A step whose labels are resolved into label ids.
+#[derive(Clone)]
+enum Stage {
+    Adjacent {
+        direction: Direction,
+        lids: Option<Vec<u16>>,
+        edges: bool,
+    },
+    EdgeVertex(Direction),
+    Has(String, P),
+    HasLabel {
+        vertex: Vec<u16>,
+        edge: Vec<u16>,
+    },
+    Where(Vec<Stage>),
+    Dedup,
+    Limit(usize),
+    Order(Option<String>, Order),
+    Count,
+    Path,
+    As(String),
+    Select(Option<Vec<String>>),
+    Values(String),
+}
+
+fn resolve<T: TxnRead>(txn: &T, steps: &[Step]) -> Result<Vec<Stage>> {
+    let mut stages = vec![];
+    for step in steps {
+        stages.push(match step {
+            Step::Adjacent {
+                direction,
+                labels,
+                edges,
+            } => Stage::Adjacent {
+                direction: *direction,
+                lids: labels
+                    .as_ref()
+                    .map(|labels| {
+                        labels
+                            .iter()
+                            .map(|label| txn.edge_label_id(label).map(|lid| lid as u16))
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .transpose()?,
+                edges: *edges,
+            },
+            Step::EdgeVertex(direction) => Stage::EdgeVertex(*direction),
+            Step::Has(name, predicate) => Stage::Has(name.clone(), predicate.clone()),
+            Step::HasLabel(None) => continue,
+            Step::HasLabel(Some(labels)) => {
+                let (mut vertex, mut edge) = (vec![], vec![]);
+                for label in labels {
+                    let vlid = txn.vertex_label_id(label).ok();
+                    let elid = txn.edge_label_id(label).ok();
+                    if vlid.is_none() && elid.is_none() {
+                        return Err(Error::invalid_parameter());
+                    }
+                    vertex.extend(vlid.map(|lid| lid as u16));
+                    edge.extend(elid.map(|lid| lid as u16));
+                }
+                Stage::HasLabel { vertex, edge }
+            }
+            Step::Where(steps) => Stage::Where(resolve(txn, steps)?),
+            Step::Dedup => Stage::Dedup,
+            Step::Limit(n) => Stage::Limit(*n),
+            Step::Order(name, order) => Stage::Order(name.clone(), *order),
+            Step::Count => Stage::Count,
+            Step::Path => Stage::Path,
+            Step::As(label) => Stage::As(label.clone()),
+            Step::Select(labels) => Stage::Select(labels.clone()),
+            Step::Values(name) => Stage::Values(name.clone()),
+        });
+    }
+    Ok(stages)
+}
+
+fn has_path(stages: &[Stage]) -> bool {
+    stages.iter().any(|stage| match stage {
+        Stage::Path => true,
+        Stage::Where(stages) => has_path(stages),
+        _ => false,
+    })
+}
+
+fn starts<T: TxnRead>(txn: &T, ids: Option<Vec<i64>>, track: bool) -> Result<Stream<'_>> {
+    let mut vcur = txn.vertex_cur()?;
+    match ids {
+        Some(ids) => Ok(Box::new(ids.into_iter().filter_map(
+            move |vid| match vcur.seek(vid, false) {
+                Ok(vcur) if vcur.is_valid() => Some(Ok(Traverser::new(Value::Vertex(vid), track))),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            },
+        ))),
+        None => {
+            vcur.seek(0, true)?;
+            let mut failed = false;
+            Ok(Box::new(iter::from_fn(move || {
+                if failed || !vcur.is_valid() {
+                    return None;
+                }
+                let vid = vcur.id().and_then(|vid| vcur.seek_to_next().map(|_| vid));
+                failed = vid.is_err();
+                Some(vid.map(|vid| Traverser::new(Value::Vertex(vid), track)))
+            })))
+        }
+    }
+}
+
+// Look the start vertices up by an index if the traversal starts with
+// `v(()).has_label(label).has(field, eq(..) or within(..))` on an indexed field.
+fn index_lookup<'t, T: TxnRead>(
+    txn: &'t T,
+    starts: &Option<Vec<i64>>,
+    steps: &[Step],
+    track: bool,
+) -> Result<Option<Stream<'t>>> {
+    let (label, field, values) = match (starts, steps) {
+        (None, [Step::HasLabel(Some(labels)), Step::Has(field, predicate), ..])
+            if labels.len() == 1 =>
+        {
+            match predicate {
+                P::Eq(value) => (&labels[0], field, vec![value.clone()]),
+                P::Within(values) => (&labels[0], field, values.clone()),
+                _ => return Ok(None),
+            }
+        }
+        _ => return Ok(None),
+    };
+    // the label may be an edge label, or the field may be missing, then fall back to scan
+    let Ok(schema) = txn.vertex_schema(label) else {
+        return Ok(None);
+    };
+    let Some(spec) = schema.iter().find(|spec| spec.name == *field) else {
+        return Ok(None);
+    };
+    if !txn.is_vertex_indexed(label, field)? {
+        return Ok(None);
+    }
+    // an index is keyed by values of the field type
+    let Some(mut values) = values
+        .iter()
+        .map(|value| cast(value, spec.ty))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(None);
+    };
+    let mut seen = HashSet::new();
+    values.retain(|value| seen.insert(Key::of_data(value)));
+    let unique = txn
+        .all_vertex_indexes()?
+        .iter()
+        .any(|index| index.label == *label && index.field == *field && index.unique);
+    let mut found = vec![];
+    let mut scans = vec![];
+    for value in &values {
+        if unique {
+            let vcur = txn.unique_index_vertex_cur(label, field, value)?;
+            if vcur.is_valid() {
+                found.push(vcur.id()?);
+            }
+        } else {
+            scans.push(txn.vertex_index_iter_ids_from(label, field, value, value)?);
+        }
+    }
+    let vids = found.into_iter().chain(scans.into_iter().flatten());
+    Ok(Some(Box::new(vids.map(move |vid| {
+        Ok(Traverser::new(Value::Vertex(vid), track))
+    }))))
+}
+
+// Convert a value to `ty` if it keeps the same value.
+fn cast(value: &FieldData, ty: FieldType) -> Option<FieldData> {
+    if value.ty() == ty {
+        return Some(value.clone());
+    }
+    let int = as_int(value);
+    let float = as_float(value);
+    match ty {
+        FieldType::Int8 => int.and_then(|i| i.try_into().ok()).map(FieldData::Int8),
+        FieldType::Int16 => int.and_then(|i| i.try_into().ok()).map(FieldData::Int16),
+        FieldType::Int32 => int.and_then(|i| i.try_into().ok()).map(FieldData::Int32),
+        FieldType::Int64 => int.map(FieldData::Int64),
+        // an integer may be rounded in a float
+        FieldType::Float => float
+            .map(|f| FieldData::Float(f as f32))
+            .filter(|cast| compare(value, cast) == Some(Ordering::Equal)),
+        FieldType::Double => float
+            .map(FieldData::Double)
+            .filter(|cast| compare(value, cast) == Some(Ordering::Equal)),
+        _ => None,
+    }
+}
+
+fn as_int(data: &FieldData) -> Option<i64> {
+    match *data {
+        FieldData::Int8(i) => Some(i as i64),
+        FieldData::Int16(i) => Some(i as i64),
+        FieldData::Int32(i) => Some(i as i64),
+        FieldData::Int64(i) => Some(i),
+        _ => None,
+    }
+}
+
+fn as_float(data: &FieldData) -> Option<f64> {
+    match *data {
+        FieldData::Float(f) => Some(f as f64),
+        FieldData::Double(f) => Some(f),
+        _ => as_int(data).map(|i| i as f64),
+    }
+}
+
+// Get the integer equal to `f`, if any.
+fn exact_int(f: f64) -> Option<i64> {
+    // `i64::MIN as f64` is exact, while `i64::MAX as f64` is rounded up to 2^63
+    if f.fract() == 0.0 && f >= i64::MIN as f64 && f < i64::MAX as f64 {
+        Some(f as i64)
+    } else {
+        None
+    }
+}
+
+// Compare numbers exactly, rather than rounding integers to floats, or none if either is
+// NaN or not a number.
+fn compare_numbers(a: &FieldData, b: &FieldData) -> Option<Ordering> {
+    match (as_int(a), as_int(b)) {
+        (Some(a), Some(b)) => Some(a.cmp(&b)),
+        (Some(a), None) => compare_int_float(a, as_float(b)?),
+        (None, Some(b)) => compare_int_float(b, as_float(a)?).map(Ordering::reverse),
+        (None, None) => as_float(a)?.partial_cmp(&as_float(b)?),
+    }
+}
+
+fn compare_int_float(i: i64, f: f64) -> Option<Ordering> {
+    if f.is_nan() {
+        None
+    } else if f >= i64::MAX as f64 {
+        Some(Ordering::Less)
+    } else if f < i64::MIN as f64 {
+        Some(Ordering::Greater)
+    } else {
+        // the integral part of `f` is in the range of i64 now
+        let trunc = f.trunc();
+        Some(
+            i.cmp(&(trunc as i64))
+                .then_with(|| 0.0.partial_cmp(&(f - trunc)).unwrap()),
+        )
+    }
+}
+
+fn is_number(data: &FieldData) -> bool {
+    as_float(data).is_some()
+}
+
+// Compare values numerically if both are numbers, otherwise only of the same type.
+fn compare(a: &FieldData, b: &FieldData) -> Option<Ordering> {
+    if is_number(a) && is_number(b) {
+        compare_numbers(a, b)
+    } else if a.ty() == b.ty() {
+        a.partial_cmp(b)
+    } else {
+        None
+    }
+}
+
+// A total order of values for `order`. Numbers compare numerically, with NaN above all
+// other numbers. Values of other types compare within their type, while values of
+// different types compare by the fixed rank of their types, where null is the lowest.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Vertex(a), Value::Vertex(b)) => a.cmp(b),
+        (Value::Edge(a), Value::Edge(b)) => a.cmp(b),
+        (Value::Data(a), Value::Data(b)) => compare_data(a, b),
+        (Value::List(a), Value::List(b)) => a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| compare_values(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::Map(a), Value::Map(b)) => a
+            .iter()
+            .zip(b)
+            .map(|((a_label, a), (b_label, b))| {
+                a_label.cmp(b_label).then_with(|| compare_values(a, b))
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn compare_data(a: &FieldData, b: &FieldData) -> Ordering {
+    if is_number(a) && is_number(b) {
+        // NaN is the only number not comparable, which is above the others
+        let is_nan = |data: &FieldData| as_float(data).map_or(false, f64::is_nan);
+        return compare_numbers(a, b).unwrap_or_else(|| is_nan(a).cmp(&is_nan(b)));
+    }
+    match (a, b) {
+        (FieldData::Bool(a), FieldData::Bool(b)) => a.cmp(b),
+        (FieldData::Date(a), FieldData::Date(b)) => a.cmp(b),
+        (FieldData::DateTime(a), FieldData::DateTime(b)) => a.cmp(b),
+        (FieldData::String(a), FieldData::String(b)) => a.cmp(b),
+        (FieldData::Blob(a), FieldData::Blob(b)) => a.cmp(b),
+        _ => data_rank(a).cmp(&data_rank(b)),
+    }
+}
+
+// The rank of the type of a value in the order of values.
+fn rank(value: &Value) -> u8 {
+    match value {
+        Value::Data(data) => data_rank(data),
+        Value::Vertex(_) => 7,
+        Value::Edge(_) => 8,
+        Value::List(_) => 9,
+        Value::Map(_) => 10,
+    }
+}
+
+fn data_rank(data: &FieldData) -> u8 {
+    match data {
+        FieldData::Null => 0,
+        FieldData::Bool(_) => 1,
+        FieldData::Int8(_)
+        | FieldData::Int16(_)
+        | FieldData::Int32(_)
+        | FieldData::Int64(_)
+        | FieldData::Float(_)
+        | FieldData::Double(_) => 2,
+        FieldData::Date(_) => 3,
+        FieldData::DateTime(_) => 4,
+        FieldData::String(_) => 5,
+        FieldData::Blob(_) => 6,
+    }
+}
+
+// A hashable key of a value for `dedup`, equal for values compared equal.
+#[derive(PartialEq, Eq, Hash)]
+enum Key {
+    Vertex(i64),
+    Edge(EdgeUid),
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(u64),
+    Date(Date),
+    DateTime(DateTime),
+    String(String),
+    Blob(Vec<u8>),
+    List(Vec<Key>),
+    Map(Vec<(String, Key)>),
+}
+
+impl Key {
+    fn new(value: &Value) -> Self {
+        match value {
+            Value::Vertex(vid) => Key::Vertex(*vid),
+            Value::Edge(uid) => Key::Edge(*uid),
+            Value::Data(data) => Key::of_data(data),
+            Value::List(values) => Key::List(values.iter().map(Key::new).collect()),
+            Value::Map(entries) => Key::Map(
+                entries
+                    .iter()
+                    .map(|(label, value)| (label.clone(), Key::new(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn of_data(data: &FieldData) -> Self {
+        match data {
+            FieldData::Null => Key::Null,
+            FieldData::Bool(b) => Key::Bool(*b),
+            FieldData::Int8(_) | FieldData::Int16(_) | FieldData::Int32(_) => {
+                Key::Int(as_int(data).unwrap())
+            }
+            FieldData::Int64(i) => Key::Int(*i),
+            FieldData::Float(_) | FieldData::Double(_) => {
+                let f = as_float(data).unwrap();
+                // integral floats equal to integers
+                match exact_int(f) {
+                    Some(i) => Key::Int(i),
+                    // all NaNs compare equal in the order of values
+                    None if f.is_nan() => Key::Float(f64::NAN.to_bits()),
+                    None => Key::Float(f.to_bits()),
+                }
+            }
+            FieldData::Date(date) => Key::Date(*date),
+            FieldData::DateTime(datetime) => Key::DateTime(datetime.clone()),
+            FieldData::String(s) => Key::String(s.clone()),
+            FieldData::Blob(b) => Key::Blob(b.clone()),
+        }
+    }
+}
+
+// Reads a field of vertices and edges, caching the field id of each label, or none if the
+// label has no such field.
+struct Fields<'t, T> {
+    txn: &'t T,
+    vcur: VertexCur<'t>,
+    name: String,
+    vertex_ids: HashMap<u16, Option<usize>>,
+    edge_ids: HashMap<u16, Option<usize>>,
+}
+
+impl<'t, T: TxnRead> Fields<'t, T> {
+    fn new(txn: &'t T, name: String) -> Result<Self> {
+        Ok(Fields {
+            txn,
+            vcur: txn.vertex_cur()?,
+            name,
+            vertex_ids: HashMap::new(),
+            edge_ids: HashMap::new(),
+        })
+    }
+
+    // Get the field of an element, or none if it is missing or null.
+    fn get(&mut self, value: &Value) -> Result<Option<FieldData>> {
+        let data = match value {
+            Value::Vertex(vid) => {
+                if !self.vcur.seek(*vid, false)?.is_valid() {
+                    return Ok(None);
+                }
+                let lid = self.vcur.lid()?;
+                let id = match self.vertex_ids.get(&lid) {
+                    Some(id) => *id,
+                    None => {
+                        let schema = self.txn.vertex_schema(&self.vcur.label()?)?;
+                        let id = if schema.iter().any(|spec| spec.name == self.name) {
+                            Some(self.txn.vertex_field_id(lid as usize, &self.name)?)
+                        } else {
+                            None
+                        };
+                        *self.vertex_ids.entry(lid).or_insert(id)
+                    }
+                };
+                match id {
+                    Some(id) => self.vcur.field_by_id(id)?,
+                    None => return Ok(None),
+                }
+            }
+            Value::Edge(uid) => {
+                let ecur = self.txn.out_edge_cur(uid)?;
+                let id = match self.edge_ids.get(&uid.lid) {
+                    Some(id) => *id,
+                    None => {
+                        let schema = self.txn.edge_schema(&ecur.label()?)?;
+                        let id = if schema.iter().any(|spec| spec.name == self.name) {
+                            Some(self.txn.edge_field_id(uid.lid as usize, &self.name)?)
+                        } else {
+                            None
+                        };
+                        *self.edge_ids.entry(uid.lid).or_insert(id)
+                    }
+                };
+                match id {
+                    Some(id) => ecur.field_by_id(id)?,
+                    None => return Ok(None),
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(match data {
+            FieldData::Null => None,
+            data => Some(data),
+        })
+    }
+}
+
+// Applies `f` to each traverser, yielding the traversers it returns. It stops after an error.
+struct FlatMap<'t, F> {
+    input: Stream<'t>,
+    f: F,
+    pending: vec::IntoIter<Traverser>,
+    failed: bool,
+}
+
+impl<'t, F> Iterator for FlatMap<'t, F>
+where
+    F: FnMut(Traverser) -> Result<Vec<Traverser>>,
+{
+    type Item = Result<Traverser>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(traverser) = self.pending.next() {
+                return Some(Ok(traverser));
+            }
+            if self.failed {
+                return None;
+            }
+            match self.input.next()?.and_then(&mut self.f) {
+                Ok(traversers) => self.pending = traversers.into_iter(),
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+fn flat_map<'t, F>(input: Stream<'t>, f: F) -> Stream<'t>
+where
+    F: FnMut(Traverser) -> Result<Vec<Traverser>> + 't,
+{
+    Box::new(FlatMap {
+        input,
+        f,
+        pending: vec![].into_iter(),
+        failed: false,
+    })
+}
+
+fn filter<'t, F>(input: Stream<'t>, mut f: F) -> Stream<'t>
+where
+    F: FnMut(&Traverser) -> Result<bool> + 't,
+{
+    flat_map(input, move |traverser| {
+        Ok(match f(&traverser)? {
+            true => vec![traverser],
+            false => vec![],
+        })
+    })
+}
+
+// Gathers all traversers once the first is pulled, yielding what `f` returns of them.
+fn barrier<'t, F>(input: Stream<'t>, f: F) -> Stream<'t>
+where
+    F: FnOnce(Vec<Traverser>) -> Result<Vec<Traverser>> + 't,
+{
+    Box::new(
+        iter::once_with(
+            move || match input.collect::<Result<Vec<_>>>().and_then(f) {
+                Ok(traversers) => traversers.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            },
+        )
+        .flatten(),
+    )
+}
+
+// Collect the adjacent edges of `vid` with the vertices at their other ends.
+fn adjacent(
+    vcur: &mut VertexCur<'_>,
+    vid: i64,
+    direction: Direction,
+    lids: &Option<Vec<u16>>,
+) -> Result<Vec<(EdgeUid, i64)>> {
+    let mut edges = vec![];
+    if !vcur.seek(vid, false)?.is_valid() {
+        return Ok(edges);
+    }
+    let accept = |lid: u16| lids.as_ref().map_or(true, |lids| lids.contains(&lid));
+    if direction.has_out() {
+        let mut ecur = vcur.out_edge_cursor()?;
+        while ecur.is_valid() {
+            let uid = ecur.uid()?;
+            if accept(uid.lid) {
+                edges.push((uid, uid.dst));
+            }
+            ecur.seek_to_next()?;
+        }
+    }
+    if direction.has_in() {
+        let mut ecur = vcur.in_edge_cursor()?;
+        while ecur.is_valid() {
+            let uid = ecur.uid()?;
+            if accept(uid.lid) {
+                edges.push((uid, uid.src));
+            }
+            ecur.seek_to_next()?;
+        }
+    }
+    Ok(edges)
+}
+
+// Chain the stages on `input`.
+fn stream<'t, T: TxnRead>(
+    txn: &'t T,
+    stages: &[Stage],
+    mut input: Stream<'t>,
+) -> Result<Stream<'t>> {
+    for stage in stages {
+        input = match stage.clone() {
+            Stage::Adjacent {
+                direction,
+                lids,
+                edges,
+            } => {
+                let mut vcur = txn.vertex_cur()?;
+                flat_map(input, move |traverser| {
+                    let Value::Vertex(vid) = traverser.value else {
+                        return Ok(vec![]);
+                    };
+                    Ok(adjacent(&mut vcur, vid, direction, &lids)?
+                        .into_iter()
+                        .map(|(uid, other)| {
+                            traverser.moved(match edges {
+                                true => Value::Edge(uid),
+                                false => Value::Vertex(other),
+                            })
+                        })
+                        .collect())
+                })
+            }
+            Stage::EdgeVertex(direction) => flat_map(input, move |traverser| {
+                let Value::Edge(uid) = traverser.value else {
+                    return Ok(vec![]);
+                };
+                let mut vids = vec![];
+                if direction.has_out() {
+                    vids.push(uid.src);
+                }
+                if direction.has_in() {
+                    vids.push(uid.dst);
+                }
+                Ok(vids
+                    .into_iter()
+                    .map(|vid| traverser.moved(Value::Vertex(vid)))
+                    .collect())
+            }),
+            Stage::Has(name, predicate) => {
+                let mut fields = Fields::new(txn, name)?;
+                filter(input, move |traverser| {
+                    Ok(fields
+                        .get(&traverser.value)?
+                        .map_or(false, |data| predicate.test(&data)))
+                })
+            }
+            Stage::HasLabel { vertex, edge } => {
+                let mut vcur = txn.vertex_cur()?;
+                filter(input, move |traverser| match traverser.value {
+                    Value::Vertex(vid) => {
+                        Ok(vcur.seek(vid, false)?.is_valid() && vertex.contains(&vcur.lid()?))
+                    }
+                    Value::Edge(uid) => Ok(edge.contains(&uid.lid)),
+                    _ => Ok(false),
+                })
+            }
+            Stage::Where(stages) => filter(input, move |traverser| {
+                let sub: Stream<'t> = Box::new(iter::once(Ok(traverser.clone())));
+                Ok(stream(txn, &stages, sub)?.next().transpose()?.is_some())
+            }),
+            Stage::Dedup => {
+                let mut seen = HashSet::new();
+                filter(input, move |traverser| {
+                    Ok(seen.insert(Key::new(&traverser.value)))
+                })
+            }
+            Stage::Limit(n) => Box::new(input.take(n)),
+            Stage::Order(name, order) => {
+                let mut fields = name.map(|name| Fields::new(txn, name)).transpose()?;
+                barrier(input, move |traversers| {
+                    let mut keyed = traversers
+                        .into_iter()
+                        .map(|traverser| {
+                            let key = match &mut fields {
+                                Some(fields) => {
+                                    Value::Data(fields.get(&traverser.value)?.unwrap_or_default())
+                                }
+                                None => traverser.value.clone(),
+                            };
+                            Ok((key, traverser))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    keyed.sort_by(|(a, _), (b, _)| match order {
+                        Order::Asc => compare_values(a, b),
+                        Order::Desc => compare_values(b, a),
+                    });
+                    Ok(keyed.into_iter().map(|(_, traverser)| traverser).collect())
+                })
+            }
+            Stage::Count => barrier(input, |traversers| {
+                let count = Value::Data(FieldData::Int64(traversers.len() as i64));
+                let track = traversers.first().map_or(false, |t| t.path.is_some());
+                Ok(vec![Traverser::new(count, track)])
+            }),
+            Stage::Path => flat_map(input, |traverser| {
+                let path = traverser.path.clone().unwrap_or_default();
+                Ok(vec![traverser.moved(Value::List(path))])
+            }),
+            Stage::As(label) => flat_map(input, move |mut traverser| {
+                traverser
+                    .labels
+                    .push((label.clone(), traverser.value.clone()));
+                Ok(vec![traverser])
+            }),
+            Stage::Select(labels) => flat_map(input, move |traverser| {
+                // the latest value of a label is selected
+                let get = |label: &str| {
+                    traverser
+                        .labels
+                        .iter()
+                        .rev()
+                        .find(|(l, _)| l == label)
+                        .map(|(_, value)| value.clone())
+                };
+                let selected = match &labels {
+                    Some(labels) if labels.len() == 1 => get(&labels[0]),
+                    Some(labels) => labels
+                        .iter()
+                        .map(|label| get(label).map(|value| (label.clone(), value)))
+                        .collect::<Option<Vec<_>>>()
+                        .map(Value::Map),
+                    None => {
+                        let mut entries: Vec<(String, Value)> = vec![];
+                        for (label, value) in &traverser.labels {
+                            match entries.iter_mut().find(|(l, _)| l == label) {
+                                Some(entry) => entry.1 = value.clone(),
+                                None => entries.push((label.clone(), value.clone())),
+                            }
+                        }
+                        Some(Value::Map(entries))
+                    }
+                };
+                Ok(selected
+                    .map(|value| traverser.moved(value))
+                    .into_iter()
+                    .collect())
+            }),
+            Stage::Values(name) => {
+                let mut fields = Fields::new(txn, name)?;
+                flat_map(input, move |traverser| {
+                    Ok(fields
+                        .get(&traverser.value)?
+                        .map(|data| traverser.moved(Value::Data(data)))
+                        .into_iter()
+                        .collect())
+                })
+            }
+        };
+    }
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{
+        between, cast, compare_values, eq, gt, inside, lte, neq, outside, within, without, Key,
+        Value,
+    };
+    use crate::field::{FieldData, FieldType};
+
+    #[test]
+    fn test_predicates() {
+        let age = FieldData::Int64(30);
+        assert!(eq(30).test(&age));
+        assert!(eq(30.0).test(&age));
+        assert!(!neq(30i8).test(&age));
+        assert!(gt(29.5).test(&age));
+        assert!(lte(30).test(&age));
+        assert!(between(30, 31).test(&age));
+        assert!(!between(29, 30).test(&age));
+        assert!(!inside(30, 31).test(&age));
+        assert!(outside(31, 40).test(&age));
+        assert!(within([1, 30]).test(&age));
+        assert!(without([1, 2]).test(&age));
+        // values of different types do not compare
+        assert!(!eq("30").test(&age));
+        assert!(!gt(false).test(&age));
+        assert!(eq("marko").test(&FieldData::String("marko".into())));
+        assert!(!neq(1).test(&FieldData::Null));
+        let big = FieldData::Int64((1 << 53) + 1);
+        assert!(!eq((1u64 << 53) as f64).test(&big));
+        assert!(gt((1u64 << 53) as f64).test(&big));
+        assert!(!eq(f64::NAN).test(&FieldData::Double(f64::NAN)));
+    }
+
+    #[test]
+    fn test_cast() {
+        let int = FieldData::Int32(300);
+        assert_eq!(cast(&int, FieldType::Int64), Some(FieldData::Int64(300)));
+        assert_eq!(cast(&int, FieldType::Int8), None);
+        assert_eq!(
+            cast(&int, FieldType::Double),
+            Some(FieldData::Double(300.0))
+        );
+        assert_eq!(cast(&FieldData::Double(0.5), FieldType::Int32), None);
+        assert_eq!(
+            cast(&FieldData::Int64((1 << 53) + 1), FieldType::Double),
+            None
+        );
+        assert_eq!(
+            cast(&FieldData::Int32((1 << 24) + 1), FieldType::Float),
+            None
+        );
+        assert_eq!(cast(&int, FieldType::String), None);
+    }
+
+    #[test]
+    fn test_key() {
+        let key = |data: FieldData| Key::new(&Value::Data(data));
+        assert!(key(FieldData::Int32(1)) == key(FieldData::Int64(1)));
+        assert!(key(FieldData::Double(1.0)) == key(FieldData::Int8(1)));
+        assert!(key(FieldData::Double(1.5)) == key(FieldData::Float(1.5)));
+        assert!(key(FieldData::Double(1.5)) != key(FieldData::Double(2.5)));
+        assert!(key(FieldData::String("1".into())) != key(FieldData::Int8(1)));
+        assert!(Key::new(&Value::Vertex(1)) != key(FieldData::Int64(1)));
+    }
+
+    #[test]
+    fn test_compare_values() {
+        let data = |data: FieldData| Value::Data(data);
+        let mut values = vec![
+            Value::Vertex(1),
+            data(FieldData::Double(-f64::NAN)),
+            data(FieldData::String("a".into())),
+            data(FieldData::Int32(2)),
+            Value::List(vec![Value::Vertex(1)]),
+            data(FieldData::Null),
+            data(FieldData::Double(1.5)),
+            data(FieldData::Bool(true)),
+            Value::List(vec![]),
+            data(FieldData::Int64(-1)),
+        ];
+        values.sort_by(compare_values);
+        assert_eq!(values[0], data(FieldData::Null));
+        assert_eq!(values[1], data(FieldData::Bool(true)));
+        assert_eq!(values[2], data(FieldData::Int64(-1)));
+        assert_eq!(values[3], data(FieldData::Double(1.5)));
+        assert_eq!(values[4], data(FieldData::Int32(2)));
+        // even a negative NaN
+        assert!(matches!(values[5], Value::Data(FieldData::Double(f)) if f.is_nan()));
+        assert_eq!(
+            values[6..],
+            [
+                data(FieldData::String("a".into())),
+                Value::Vertex(1),
+                Value::List(vec![]),
+                Value::List(vec![Value::Vertex(1)]),
+            ]
+        );
+        // antisymmetric across types
+        for a in &values {
+            for b in &values {
+                assert_eq!(compare_values(a, b), compare_values(b, a).reverse());
+            }
+        }
+        assert_eq!(
+            compare_values(&data(FieldData::Int8(1)), &data(FieldData::Double(1.0))),
+            Ordering::Equal
+        );
+
+        // integers are not rounded to compare with floats
+        let big = 1i64 << 53;
+        let numbers = [
+            data(FieldData::Int64(big)),
+            data(FieldData::Int64(big + 1)),
+            data(FieldData::Double(big as f64)),
+            data(FieldData::Double(big as f64 + 2.0)),
+            data(FieldData::Int64(i64::MAX)),
+            data(FieldData::Double(i64::MAX as f64)),
+            data(FieldData::Int64(i64::MIN)),
+            data(FieldData::Double(i64::MIN as f64)),
+            data(FieldData::Double(-0.5)),
+            data(FieldData::Int32(0)),
+            data(FieldData::Double(-0.0)),
+        ];
+        let cmp = |a: usize, b: usize| compare_values(&numbers[a], &numbers[b]);
+        assert_eq!(cmp(0, 2), Ordering::Equal);
+        assert_eq!(cmp(1, 2), Ordering::Greater);
+        assert_eq!(cmp(1, 3), Ordering::Less);
+        assert_eq!(cmp(4, 5), Ordering::Less);
+        assert_eq!(cmp(6, 7), Ordering::Equal);
+        assert_eq!(cmp(8, 9), Ordering::Less);
+        assert_eq!(cmp(9, 10), Ordering::Equal);
+        // consistent with `Key`, and transitive
+        for a in 0..numbers.len() {
+            for b in 0..numbers.len() {
+                let equal = Key::new(&numbers[a]) == Key::new(&numbers[b]);
+                assert_eq!(cmp(a, b) == Ordering::Equal, equal);
+                for c in 0..numbers.len() {
+                    if cmp(a, b).is_le() && cmp(b, c).is_le() {
+                        assert!(cmp(a, c).is_le());
+                    }
+                }
+            }
+        }
+    }
+}
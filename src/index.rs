@@ -19,6 +19,7 @@ use std::fmt::Display;
 
 /// `IndexSpec` describes the characteristics of a particular index.
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IndexSpec {
     /// The label of the index built on
     pub label: String,
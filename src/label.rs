@@ -0,0 +1,389 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Label handles which cache the label id, field specs and field ids of a label.
+//!
+//! Operations taking label and field names, e.g. [`TxnWrite::add_vertex`], resolve
+//! the names through TuGraph each time they are called. A handle resolves them once
+//! when it is obtained by [`TxnRead::vertex_label`] or [`TxnRead::edge_label`], and
+//! then goes through the `_by_id` operations.
+//!
+//! A handle can be reused across transactions on the same graph, so its operations take
+//! the transaction. The first time a handle is used in a transaction, the cached schema
+//! is checked against the current one. If the label is altered underneath, an error of kind
+//! [`ErrorKind::SchemaChanged`] is returned and a new handle should be obtained.
+//!
+//! # Examples
+//! ```
+//! use tugraph::{
+//!     cursor::VertexCursor,
+//!     db::OpenOptions,
+//!     field::{FieldData, FieldSpec, FieldType},
+//!     txn::TxnRead,
+//!     Error,
+//! };
+//!
+//! let galaxy = OpenOptions::new()
+//!     .create(true)
+//!     .open("/tmp/rust_tugraph/doc/label", "admin", "73@TuGraph")?;
+//! let graph = galaxy.open_graph("default", false)?;
+//! graph.add_vertex_label(
+//!     "Person",
+//!     &[
+//!         FieldSpec { name: "name".into(), ty: FieldType::String, optional: false },
+//!         FieldSpec { name: "age".into(), ty: FieldType::Int32, optional: true },
+//!     ],
+//!     "name",
+//! )?;
+//! let person = graph.create_ro_txn()?.vertex_label("Person")?;
+//!
+//! let mut rw_txn = graph.create_rw_txn(false)?;
+//! let (name, age) = (FieldData::String("alice".into()), FieldData::Int32(30));
+//! let vid = person.add(&mut rw_txn, [("name", &name), ("age", &age)])?;
+//! rw_txn.commit()?;
+//!
+//! let ro_txn = graph.create_ro_txn()?;
+//! let mut vcur = ro_txn.vertex_cur()?;
+//! vcur.seek(vid, false)?;
+//! assert_eq!(person.get(&ro_txn, &vcur, "age")?, FieldData::Int32(30));
+//! # Ok::<(), Error>(())
+//! ```
+//!
+//! [`TxnWrite::add_vertex`]: crate::txn::TxnWrite::add_vertex
+//! [`TxnRead::vertex_label`]: crate::txn::TxnRead::vertex_label
+//! [`TxnRead::edge_label`]: crate::txn::TxnRead::edge_label
+//! [`ErrorKind::SchemaChanged`]: crate::ErrorKind::SchemaChanged
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{
+    cursor::{EdgeCursor, EdgeCursorMut, VertexCursor, VertexCursorMut},
+    field::{FieldData, FieldSpec},
+    txn::{RwTxn, TxnRead, TxnWrite},
+    types::EdgeUid,
+    Error, Result,
+};
+
+#[derive(Debug)]
+struct LabelSchema {
+    name: String,
+    id: usize,
+    field_specs: Vec<FieldSpec>,
+    // field ids in the same order as field_specs
+    field_ids: Vec<usize>,
+    // serial of the last transaction in which the schema is checked, 0 if never
+    checked_in: AtomicU64,
+}
+
+impl Clone for LabelSchema {
+    fn clone(&self) -> Self {
+        LabelSchema {
+            name: self.name.clone(),
+            id: self.id,
+            field_specs: self.field_specs.clone(),
+            field_ids: self.field_ids.clone(),
+            checked_in: AtomicU64::new(self.checked_in.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl LabelSchema {
+    fn field_pos(&self, field: &str) -> Result<usize> {
+        self.field_specs
+            .iter()
+            .position(|fs| fs.name == field)
+            .ok_or_else(Error::invalid_parameter)
+    }
+
+    fn field_id(&self, field: &str) -> Result<usize> {
+        self.field_pos(field).map(|pos| self.field_ids[pos])
+    }
+
+    fn check(&self, id: usize, field_specs: &[FieldSpec]) -> Result<()> {
+        if self.id == id && self.field_specs == field_specs {
+            Ok(())
+        } else {
+            Err(schema_changed())
+        }
+    }
+
+    fn check_once<F>(&self, serial: u64, check: F) -> Result<()>
+    where
+        F: FnOnce() -> Result<()>,
+    {
+        if self.checked_in.load(Ordering::Relaxed) != serial {
+            check()?;
+            self.checked_in.store(serial, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn check_lid(&self, lid: u16) -> Result<()> {
+        if lid as usize == self.id {
+            Ok(())
+        } else {
+            Err(Error::invalid_parameter())
+        }
+    }
+
+    fn pick<'a, 'b, V>(&self, values: V) -> Result<(Vec<usize>, Vec<&'a FieldData>)>
+    where
+        V: IntoIterator<Item = (&'b str, &'a FieldData)>,
+    {
+        values
+            .into_iter()
+            .map(|(field, fd)| self.field_id(field).map(|id| (id, fd)))
+            .collect::<Result<Vec<_>>>()
+            .map(|pairs| pairs.into_iter().unzip())
+    }
+}
+
+fn schema_changed() -> Error {
+    Error::new("Label schema changed.".to_string())
+}
+
+/// A handle of vertex label, obtained by [`TxnRead::vertex_label`].
+///
+/// See the [module level documentation](self) for more details.
+///
+/// [`TxnRead::vertex_label`]: crate::txn::TxnRead::vertex_label
+#[derive(Debug, Clone)]
+pub struct VertexLabel {
+    schema: LabelSchema,
+    primary_field: String,
+}
+
+impl VertexLabel {
+    pub(crate) fn load<T: TxnRead + ?Sized>(txn: &T, label: &str) -> Result<VertexLabel> {
+        let id = txn.vertex_label_id(label)?;
+        let field_specs = txn.vertex_schema(label)?;
+        let field_ids = txn.vertex_fields_ids(id, field_specs.iter().map(|fs| fs.name.as_str()))?;
+        let primary_field = txn.get_vertex_primary_field(label)?;
+        Ok(VertexLabel {
+            schema: LabelSchema {
+                name: label.to_string(),
+                id,
+                field_specs,
+                field_ids,
+                checked_in: AtomicU64::new(0),
+            },
+            primary_field,
+        })
+    }
+
+    /// Get the label name.
+    pub fn name(&self) -> &str {
+        &self.schema.name
+    }
+
+    /// Get the label id.
+    pub fn id(&self) -> usize {
+        self.schema.id
+    }
+
+    /// Get the field specs of the label.
+    pub fn field_specs(&self) -> &[FieldSpec] {
+        &self.schema.field_specs
+    }
+
+    /// Get the primary field of the label.
+    pub fn primary_field(&self) -> &str {
+        &self.primary_field
+    }
+
+    /// Get the field id of `field` without calling into TuGraph.
+    ///
+    /// # Errors
+    /// If the label has no such field, an [`ErrorKind::InvalidParameter`] is returned.
+    ///
+    /// [`ErrorKind::InvalidParameter`]: crate::ErrorKind::InvalidParameter
+    pub fn field_id(&self, field: &str) -> Result<usize> {
+        self.schema.field_id(field)
+    }
+
+    /// Check the cached schema against the current one seen by `txn`.
+    ///
+    /// # Errors
+    /// If the label id or the field specs differ, an [`ErrorKind::SchemaChanged`] is returned.
+    ///
+    /// [`ErrorKind::SchemaChanged`]: crate::ErrorKind::SchemaChanged
+    pub fn check<T: TxnRead>(&self, txn: &T) -> Result<()> {
+        let id = txn.vertex_label_id(self.name())?;
+        let field_specs = txn.vertex_schema(self.name())?;
+        self.schema.check(id, &field_specs)
+    }
+
+    /// Add a vertex with `values` given as `(field, value)` pairs.
+    ///
+    /// Field ids are looked up in the cached schema, so no name is resolved by TuGraph.
+    ///
+    /// # Errors
+    /// If the label has no such field, an [`ErrorKind::InvalidParameter`] is returned. If the schema changed since the handle
+    /// was obtained, an [`ErrorKind::SchemaChanged`] is returned. See [`TxnWrite::add_vertex`]
+    /// for other errors.
+    ///
+    /// [`ErrorKind::InvalidParameter`]: crate::ErrorKind::InvalidParameter
+    /// [`ErrorKind::SchemaChanged`]: crate::ErrorKind::SchemaChanged
+    /// [`TxnWrite::add_vertex`]: crate::txn::TxnWrite::add_vertex
+    pub fn add<'a, 'b, V>(&self, txn: &mut RwTxn<'_>, values: V) -> Result<i64>
+    where
+        V: IntoIterator<Item = (&'b str, &'a FieldData)>,
+    {
+        self.schema.check_once(txn.serial(), || self.check(txn))?;
+        let (field_ids, values) = self.schema.pick(values)?;
+        txn.add_vertex_by_id(self.id(), &field_ids, values)
+    }
+
+    /// Get the value of `field` of the vertex the cursor of `txn` points to.
+    ///
+    /// # Errors
+    /// If the vertex is not of this label, or the label has no such field, an
+    /// [`ErrorKind::InvalidParameter`] is returned. If the schema changed since the handle
+    /// was obtained, an [`ErrorKind::SchemaChanged`] is returned.
+    ///
+    /// [`ErrorKind::InvalidParameter`]: crate::ErrorKind::InvalidParameter
+    /// [`ErrorKind::SchemaChanged`]: crate::ErrorKind::SchemaChanged
+    pub fn get<T, C>(&self, txn: &T, cursor: &C, field: &str) -> Result<FieldData>
+    where
+        T: TxnRead,
+        C: VertexCursor,
+    {
+        let field_id = self.field_id(field)?;
+        self.schema.check_once(txn.serial(), || self.check(txn))?;
+        self.schema.check_lid(cursor.lid()?)?;
+        cursor.field_by_id(field_id)
+    }
+
+    /// Set the value of `field` of the vertex the cursor of `txn` points to.
+    ///
+    /// See [`VertexLabel::get`] for errors.
+    pub fn set<C>(&self, txn: &RwTxn<'_>, cursor: &C, field: &str, value: &FieldData) -> Result<()>
+    where
+        C: VertexCursor + VertexCursorMut,
+    {
+        let field_id = self.field_id(field)?;
+        self.schema.check_once(txn.serial(), || self.check(txn))?;
+        self.schema.check_lid(cursor.lid()?)?;
+        cursor.set_field_by_id(field_id, value)
+    }
+}
+
+/// A handle of edge label, obtained by [`TxnRead::edge_label`].
+///
+/// See the [module level documentation](self) for more details.
+///
+/// [`TxnRead::edge_label`]: crate::txn::TxnRead::edge_label
+#[derive(Debug, Clone)]
+pub struct EdgeLabel {
+    schema: LabelSchema,
+}
+
+impl EdgeLabel {
+    pub(crate) fn load<T: TxnRead + ?Sized>(txn: &T, label: &str) -> Result<EdgeLabel> {
+        let id = txn.edge_label_id(label)?;
+        let field_specs = txn.edge_schema(label)?;
+        let field_ids = txn.edge_fields_ids(id, field_specs.iter().map(|fs| fs.name.as_str()))?;
+        Ok(EdgeLabel {
+            schema: LabelSchema {
+                name: label.to_string(),
+                id,
+                field_specs,
+                field_ids,
+                checked_in: AtomicU64::new(0),
+            },
+        })
+    }
+
+    /// Get the label name.
+    pub fn name(&self) -> &str {
+        &self.schema.name
+    }
+
+    /// Get the label id.
+    pub fn id(&self) -> usize {
+        self.schema.id
+    }
+
+    /// Get the field specs of the label.
+    pub fn field_specs(&self) -> &[FieldSpec] {
+        &self.schema.field_specs
+    }
+
+    /// Get the field id of `field` without calling into TuGraph.
+    ///
+    /// # Errors
+    /// If the label has no such field, an [`ErrorKind::InvalidParameter`] is returned.
+    ///
+    /// [`ErrorKind::InvalidParameter`]: crate::ErrorKind::InvalidParameter
+    pub fn field_id(&self, field: &str) -> Result<usize> {
+        self.schema.field_id(field)
+    }
+
+    /// Check the cached schema against the current one seen by `txn`.
+    ///
+    /// # Errors
+    /// If the label id or the field specs differ, an [`ErrorKind::SchemaChanged`] is returned.
+    ///
+    /// [`ErrorKind::SchemaChanged`]: crate::ErrorKind::SchemaChanged
+    pub fn check<T: TxnRead>(&self, txn: &T) -> Result<()> {
+        let id = txn.edge_label_id(self.name())?;
+        let field_specs = txn.edge_schema(self.name())?;
+        self.schema.check(id, &field_specs)
+    }
+
+    /// Add an edge from `src` to `dst` with `values` given as `(field, value)` pairs.
+    ///
+    /// See [`VertexLabel::add`] for how values are added and errors.
+    pub fn add<'a, 'b, V>(
+        &self,
+        txn: &mut RwTxn<'_>,
+        src: i64,
+        dst: i64,
+        values: V,
+    ) -> Result<EdgeUid>
+    where
+        V: IntoIterator<Item = (&'b str, &'a FieldData)>,
+    {
+        self.schema.check_once(txn.serial(), || self.check(txn))?;
+        let (field_ids, values) = self.schema.pick(values)?;
+        txn.add_edge_by_id(src, dst, self.id(), &field_ids, values)
+    }
+
+    /// Get the value of `field` of the edge the cursor of `txn` points to.
+    ///
+    /// See [`VertexLabel::get`] for errors.
+    pub fn get<T, C>(&self, txn: &T, cursor: &C, field: &str) -> Result<FieldData>
+    where
+        T: TxnRead,
+        C: EdgeCursor,
+    {
+        let field_id = self.field_id(field)?;
+        self.schema.check_once(txn.serial(), || self.check(txn))?;
+        self.schema.check_lid(cursor.lid()?)?;
+        cursor.field_by_id(field_id)
+    }
+
+    /// Set the value of `field` of the edge the cursor of `txn` points to.
+    ///
+    /// See [`VertexLabel::get`] for errors.
+    pub fn set<C>(&self, txn: &RwTxn<'_>, cursor: &C, field: &str, value: &FieldData) -> Result<()>
+    where
+        C: EdgeCursor + EdgeCursorMut,
+    {
+        let field_id = self.field_id(field)?;
+        self.schema.check_once(txn.serial(), || self.check(txn))?;
+        self.schema.check_lid(cursor.lid()?)?;
+        cursor.set_field_by_id(field_id, value)
+    }
+}
@@ -28,13 +28,20 @@
 //! [`TimedTaskScheduler`]: https://github.com/TuGraph-family/fma-common/blob/7007036315e861e1d53174784592c337c22cbeb9/fma-common/timed_task.h#L88
 //! [`destructor`]: https://github.com/TuGraph-family/fma-common/blob/7007036315e861e1d53174784592c337c22cbeb9/fma-common/timed_task.h#L118
 
+pub mod algo;
 pub mod cursor;
 pub mod db;
 pub mod field;
+pub mod gremlin;
 pub mod index;
+pub mod label;
+pub mod parallel;
+#[cfg(feature = "petgraph")]
+pub mod petgraph;
 mod raw;
 // pub mod rc;
 pub mod role_info;
+pub mod traverse;
 pub mod txn;
 pub mod types;
 pub mod user_info;
@@ -72,6 +79,12 @@ pub enum ErrorKind {
     IOError,
     // UnauthorizedError
     Unauthorized,
+    // Not in lgraph_exceptions.h, the schema cached by a label handle is outdated,
+    // see label::VertexLabel
+    SchemaChanged,
+    // Not in lgraph_exceptions.h, a field data can not be converted to the requested Rust type,
+    // see field::FieldValue
+    TypeMismatch,
     // Errors not in lgraph_exceptions.h but from C++ std::exception
     // e.g. OutOfBound("whose msg is variant"), std::runtime_error("custom error msg")
     Other,
@@ -88,6 +101,15 @@ impl Error {
         Error { message }
     }
 
+    /// Create an `Error` of [`ErrorKind::InvalidParameter`], as reported for invalid inputs
+    /// by this crate and by TuGraph itself.
+    ///
+    /// Crates built upon this one, e.g. the derive macros of `tugraph-plugin-util`, can use
+    /// it to report invalid inputs the same way.
+    pub fn invalid_parameter() -> Error {
+        Error::new("Invalid parameter.".to_string())
+    }
+
     /// Converts `Error` into a `String`.
     pub fn into_string(self) -> String {
         self.msg().to_string()
@@ -112,6 +134,8 @@ impl Error {
             "The specified TuGraph DB does not exist." => ErrorKind::DBNotExist,
             "IO Error." => ErrorKind::IOError,
             "Unauthorized." => ErrorKind::Unauthorized,
+            "Label schema changed." => ErrorKind::SchemaChanged,
+            msg if msg.starts_with("Field type mismatch:") => ErrorKind::TypeMismatch,
             _ => ErrorKind::Other,
         }
     }
@@ -160,6 +184,11 @@ mod tests {
             ),
             ("IO Error.", ErrorKind::IOError),
             ("Unauthorized.", ErrorKind::Unauthorized),
+            ("Label schema changed.", ErrorKind::SchemaChanged),
+            (
+                "Field type mismatch: expected Int64, found String.",
+                ErrorKind::TypeMismatch,
+            ),
             ("Other Unkown error.", ErrorKind::Other),
         ]
         .into_iter()
@@ -0,0 +1,349 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parallel read operations on forked read-only transactions.
+//!
+//! [`Parallel`] spreads read work over worker threads. Each worker forks its own
+//! [`RoTxn`] from the given one, so all workers share the same view of the graph.
+//! Vertex scans split the vid space into ranges, which workers claim one by one and
+//! walk by [`VertexCursor::seek`] with `nearest = true`.
+//!
+//! ```no_run
+//! use tugraph::{cursor::VertexCursor, db::OpenOptions, parallel::Parallel};
+//!
+//! # fn main() -> Result<(), tugraph::Error> {
+//! let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+//! let graph = galaxy.open_graph("default", true)?;
+//! let ro_txn = graph.create_ro_txn()?;
+//! let num_out_edges = Parallel::new(&graph, &ro_txn).par_map_reduce(
+//!     || 0,
+//!     |vertex| Ok(vertex.num_out_edges(usize::MAX)?.1),
+//!     |a, b| a + b,
+//! )?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `rayon` feature, workers can run on a rayon thread pool instead,
+//! see [`Parallel::thread_pool`].
+
+use std::{
+    panic,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{
+    cursor::{EdgeCursor, VertexCur, VertexCursor},
+    db::Graph,
+    traverse::{Direction, Visited, VisitedSet},
+    txn::{RoTxn, TxnRead},
+    Result,
+};
+
+// The number of work units per worker, more units balance the load better.
+pub(crate) const UNITS_PER_WORKER: usize = 8;
+
+/// `Parallel` runs read operations over worker threads, each of which works on
+/// a read-only transaction forked from the given one.
+///
+/// **Note**: Since one thread can only have one active read-only transaction, the closures
+/// given to `Parallel` must not create read-only transactions themselves.
+#[must_use]
+pub struct Parallel<'a> {
+    graph: &'a Graph<'a>,
+    txn: &'a RoTxn<'a>,
+    num_threads: usize,
+    #[cfg(feature = "rayon")]
+    thread_pool: Option<&'a rayon::ThreadPool>,
+}
+
+impl<'a> Parallel<'a> {
+    /// Create a `Parallel` forking from `txn`, which runs as many workers as the
+    /// available parallelism.
+    pub fn new(graph: &'a Graph<'a>, txn: &'a RoTxn<'a>) -> Self {
+        Parallel {
+            graph,
+            txn,
+            num_threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            #[cfg(feature = "rayon")]
+            thread_pool: None,
+        }
+    }
+
+    /// Set the number of worker threads, at least 1.
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads.max(1);
+        self
+    }
+
+    /// Run workers on `thread_pool`, one on each of its threads, instead of
+    /// spawning threads.
+    #[cfg(feature = "rayon")]
+    pub fn thread_pool(mut self, thread_pool: &'a rayon::ThreadPool) -> Self {
+        self.thread_pool = Some(thread_pool);
+        self
+    }
+
+    pub(crate) fn txn(&self) -> &'a RoTxn<'a> {
+        self.txn
+    }
+
+    pub(crate) fn num_workers(&self) -> usize {
+        #[cfg(feature = "rayon")]
+        if let Some(pool) = self.thread_pool {
+            return pool.current_num_threads();
+        }
+        self.num_threads
+    }
+
+    // Run `worker` on each worker thread, and stop the others once one fails.
+    pub(crate) fn run<T, W>(&self, worker: W) -> Result<Vec<T>>
+    where
+        T: Send,
+        W: Fn(&RoTxn<'_>, &AtomicBool) -> Result<T> + Sync,
+    {
+        let stop = AtomicBool::new(false);
+        let work = || {
+            #[allow(deprecated)]
+            let result = self
+                .graph
+                .fork_ro_txn(self.txn)
+                .and_then(|txn| worker(&txn, &stop));
+            if result.is_err() {
+                stop.store(true, Ordering::Relaxed);
+            }
+            result
+        };
+        #[cfg(feature = "rayon")]
+        if let Some(pool) = self.thread_pool {
+            return pool.broadcast(|_| work()).into_iter().collect();
+        }
+        thread::scope(|s| {
+            let handles: Vec<_> = (0..self.num_threads).map(|_| s.spawn(work)).collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .collect()
+        })
+    }
+
+    // Fold the vertices of the vid ranges claimed by this worker.
+    pub(crate) fn fold_vertices<T, F>(
+        txn: &RoTxn<'_>,
+        ranges: &Units,
+        stop: &AtomicBool,
+        init: T,
+        mut f: F,
+    ) -> Result<T>
+    where
+        F: FnMut(T, &mut VertexCur<'_>) -> Result<T>,
+    {
+        let mut acc = init;
+        let mut vcur = txn.vertex_cur()?;
+        while let Some(range) = ranges.claim() {
+            let (start, end) = (range.start as i64, range.end as i64);
+            vcur.seek(start, true)?;
+            while vcur.is_valid() && (range.is_last || vcur.id()? < end) {
+                if stop.load(Ordering::Relaxed) {
+                    return Ok(acc);
+                }
+                acc = f(acc, &mut vcur)?;
+                vcur.seek_to_next()?;
+            }
+        }
+        Ok(acc)
+    }
+
+    // Split the vid space into ranges, where the last one is unbounded.
+    pub(crate) fn vid_ranges(&self) -> Result<Units> {
+        let num_vids = self.graph.estimate_num_vertices()?;
+        Ok(Units::new(num_vids, self.num_workers() * UNITS_PER_WORKER))
+    }
+
+    /// Call `f` on every vertex in parallel.
+    ///
+    /// The vertices are not visited in any particular order.
+    ///
+    /// # Errors
+    /// If `f` fails on any vertex, the workers stop and the error is returned.
+    pub fn par_for_each_vertex<F>(&self, f: F) -> Result<()>
+    where
+        F: Fn(&VertexCur<'_>) -> Result<()> + Sync,
+    {
+        let ranges = self.vid_ranges()?;
+        self.run(|txn, stop| Self::fold_vertices(txn, &ranges, stop, (), |_, vertex| f(vertex)))
+            .map(|_| ())
+    }
+
+    /// Map every vertex to a value by `map` and reduce the values by `reduce` in parallel.
+    ///
+    /// `identity` creates the initial value of each worker, which should be an identity
+    /// of `reduce`, e.g. 0 for sum. `reduce` should be associative and commutative
+    /// since the vertices are not visited in any particular order.
+    ///
+    /// # Errors
+    /// If `map` fails on any vertex, the workers stop and the error is returned.
+    pub fn par_map_reduce<T, I, M, R>(&self, identity: I, map: M, reduce: R) -> Result<T>
+    where
+        T: Send,
+        I: Fn() -> T + Sync,
+        M: Fn(&VertexCur<'_>) -> Result<T> + Sync,
+        R: Fn(T, T) -> T + Sync,
+    {
+        let ranges = self.vid_ranges()?;
+        let partials = self.run(|txn, stop| {
+            Self::fold_vertices(txn, &ranges, stop, identity(), |acc, vertex| {
+                Ok(reduce(acc, map(vertex)?))
+            })
+        })?;
+        Ok(partials.into_iter().fold(identity(), &reduce))
+    }
+
+    /// Level-synchronous breadth-first search from `starts` following edges in `direction`.
+    ///
+    /// Returns the vids of each level, where `levels[d]` holds the vertices of depth `d`.
+    /// Each level is expanded in parallel, and vertices deeper than `max_depth` are not reached.
+    ///
+    /// # Errors
+    /// If reading any vertex or edge fails, the workers stop and the error is returned.
+    pub fn par_bfs<I>(
+        &self,
+        starts: I,
+        direction: Direction,
+        max_depth: usize,
+        visited: VisitedSet,
+    ) -> Result<Vec<Vec<i64>>>
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        let mut visited = Visited::new(visited);
+        let mut frontier = vec![];
+        let mut vcur = self.txn.vertex_cur()?;
+        for vid in starts {
+            if vcur.seek(vid, false)?.is_valid() && visited.insert(vid) {
+                frontier.push(vid);
+            }
+        }
+        drop(vcur);
+
+        let mut levels = vec![];
+        while !frontier.is_empty() && levels.len() < max_depth {
+            let chunks = Units::new(frontier.len(), self.num_workers() * UNITS_PER_WORKER);
+            let (frontier_ref, visited_ref) = (&frontier, &visited);
+            let found = self.run(|txn, stop| {
+                let mut found = vec![];
+                let mut vcur = txn.vertex_cur()?;
+                while let Some(chunk) = chunks.claim() {
+                    for &vid in &frontier_ref[chunk.start..chunk.end] {
+                        if stop.load(Ordering::Relaxed) {
+                            return Ok(found);
+                        }
+                        if !vcur.seek(vid, false)?.is_valid() {
+                            continue;
+                        }
+                        if direction.has_out() {
+                            let mut ecur = vcur.out_edge_cursor()?;
+                            while ecur.is_valid() {
+                                let dst = ecur.dst()?;
+                                if !visited_ref.contains(dst) {
+                                    found.push(dst);
+                                }
+                                ecur.seek_to_next()?;
+                            }
+                        }
+                        if direction.has_in() {
+                            let mut ecur = vcur.in_edge_cursor()?;
+                            while ecur.is_valid() {
+                                let src = ecur.src()?;
+                                if !visited_ref.contains(src) {
+                                    found.push(src);
+                                }
+                                ecur.seek_to_next()?;
+                            }
+                        }
+                    }
+                }
+                Ok(found)
+            })?;
+            let next = found
+                .into_iter()
+                .flatten()
+                .filter(|&vid| visited.insert(vid))
+                .collect();
+            levels.push(std::mem::replace(&mut frontier, next));
+        }
+        if !frontier.is_empty() {
+            levels.push(frontier);
+        }
+        Ok(levels)
+    }
+}
+
+pub(crate) struct Unit {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) is_last: bool,
+}
+
+// `Units` splits `0..len` into units to be claimed by workers.
+pub(crate) struct Units {
+    next: AtomicUsize,
+    num_units: usize,
+    unit_len: usize,
+    len: usize,
+}
+
+impl Units {
+    pub(crate) fn new(len: usize, max_units: usize) -> Self {
+        let unit_len = ((len + max_units - 1) / max_units).max(1);
+        Units {
+            next: AtomicUsize::new(0),
+            num_units: ((len + unit_len - 1) / unit_len).max(1),
+            unit_len,
+            len,
+        }
+    }
+
+    pub(crate) fn claim(&self) -> Option<Unit> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed);
+        (i < self.num_units).then(|| Unit {
+            start: i * self.unit_len,
+            end: ((i + 1) * self.unit_len).min(self.len),
+            is_last: i + 1 == self.num_units,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Units;
+
+    #[test]
+    fn test_units() {
+        let claim_all = |units: Units| {
+            let mut claimed = vec![];
+            while let Some(unit) = units.claim() {
+                claimed.push((unit.start, unit.end, unit.is_last));
+            }
+            claimed
+        };
+        assert_eq!(
+            claim_all(Units::new(10, 4)),
+            [(0, 3, false), (3, 6, false), (6, 9, false), (9, 10, true)]
+        );
+        assert_eq!(claim_all(Units::new(2, 8)), [(0, 1, false), (1, 2, true)]);
+        assert_eq!(claim_all(Units::new(0, 8)), [(0, 0, true)]);
+    }
+}
@@ -0,0 +1,322 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Adapters to run [petgraph] algorithms against a read-only transaction.
+//!
+//! [`TxnGraph`] implements the petgraph `visit` traits by reading vertices and edges
+//! through cursors on demand, so algorithms such as `dijkstra`, `astar`, `tarjan_scc`
+//! and `toposort` run in place without copying the graph.
+//!
+//! This module is available with the `petgraph` feature.
+//!
+//! [petgraph]: https://docs.rs/petgraph
+
+use std::{cell::Cell, collections::HashSet, vec};
+
+use ::petgraph::{
+    visit::{
+        Data, EdgeRef, GraphBase, IntoEdgeReferences, IntoEdges, IntoEdgesDirected, IntoNeighbors,
+        IntoNeighborsDirected, IntoNodeIdentifiers, NodeIndexable, Visitable,
+    },
+    Direction,
+};
+
+use crate::{
+    algo::weight_of,
+    cursor::{EdgeCursor, VertexCur, VertexCursor},
+    txn::{RoTxn, TxnRead},
+    types::EdgeUid,
+    Result,
+};
+
+/// `TxnGraph` is a petgraph view of the graph seen by a read-only transaction.
+///
+/// Nodes are identified by vids and edges by [`EdgeUid`]s. Edges of all labels are
+/// visited, weighted by the numeric field given by [`TxnGraph::weight_field`], or 1.
+/// Edges can be filtered by their label ids with petgraph's `EdgeFiltered`.
+///
+/// `NodeIndexable` maps a vid to itself as the index, so the node bound is the largest
+/// vid plus one, which is found by scanning the vertices once.
+///
+/// # Panics
+/// The visit traits cannot return errors, so visiting panics if reading through a
+/// cursor fails, or the weight field is missing or not numeric.
+///
+/// # Examples
+/// ```no_run
+/// use petgraph::{
+///     algo::{dijkstra, tarjan_scc},
+///     visit::EdgeRef,
+/// };
+/// use tugraph::{db::OpenOptions, petgraph::TxnGraph};
+///
+/// # fn main() -> Result<(), tugraph::Error> {
+/// let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+/// let graph = galaxy.open_graph("default", true)?;
+/// let ro_txn = graph.create_ro_txn()?;
+/// let txn_graph = TxnGraph::new(&ro_txn).weight_field("weight");
+/// let costs = dijkstra(&txn_graph, 0, None, |e| *e.weight());
+/// let sccs = tarjan_scc(&txn_graph);
+/// # Ok(())
+/// # }
+/// ```
+pub struct TxnGraph<'a> {
+    txn: &'a RoTxn<'a>,
+    weight_field: Option<String>,
+    node_bound: Cell<Option<usize>>,
+}
+
+impl<'a> TxnGraph<'a> {
+    /// Create a view of the graph seen by `txn`, whose edges are of weight 1.
+    pub fn new(txn: &'a RoTxn<'a>) -> Self {
+        TxnGraph {
+            txn,
+            weight_field: None,
+            node_bound: Cell::new(None),
+        }
+    }
+
+    /// Weight edges by the numeric field `weight_field`.
+    pub fn weight_field(mut self, weight_field: &str) -> Self {
+        self.weight_field = Some(weight_field.to_string());
+        self
+    }
+
+    // The out or in edges of `vid`, or none if it does not exist.
+    fn edges_of(&self, vcur: &mut VertexCur<'_>, vid: i64, dir: Direction) -> Result<Vec<Edge>> {
+        let mut edges = vec![];
+        if !vcur.seek(vid, false)?.is_valid() {
+            return Ok(edges);
+        }
+        match dir {
+            Direction::Outgoing => {
+                let mut ecur = vcur.out_edge_cursor()?;
+                while ecur.is_valid() {
+                    edges.push(self.edge(&ecur)?);
+                    ecur.seek_to_next()?;
+                }
+            }
+            Direction::Incoming => {
+                let mut ecur = vcur.in_edge_cursor()?;
+                while ecur.is_valid() {
+                    edges.push(self.edge(&ecur)?);
+                    ecur.seek_to_next()?;
+                }
+            }
+        }
+        Ok(edges)
+    }
+
+    fn edge<E: EdgeCursor>(&self, ecur: &E) -> Result<Edge> {
+        Ok(Edge {
+            uid: ecur.uid()?,
+            weight: match &self.weight_field {
+                Some(field) => weight_of(ecur.field(field)?)?,
+                None => 1.0,
+            },
+        })
+    }
+
+    fn edges_or_panic(&self, vid: i64, dir: Direction) -> Vec<Edge> {
+        self.txn
+            .vertex_cur()
+            .and_then(|mut vcur| self.edges_of(&mut vcur, vid, dir))
+            .unwrap_or_else(|e| panic!("failed to read edges of vertex {}: {}", vid, e))
+    }
+}
+
+/// An edge visited in a [`TxnGraph`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edge {
+    uid: EdgeUid,
+    weight: f64,
+}
+
+impl EdgeRef for Edge {
+    type NodeId = i64;
+    type EdgeId = EdgeUid;
+    type Weight = f64;
+
+    fn source(&self) -> i64 {
+        self.uid.src
+    }
+
+    fn target(&self) -> i64 {
+        self.uid.dst
+    }
+
+    fn weight(&self) -> &f64 {
+        &self.weight
+    }
+
+    fn id(&self) -> EdgeUid {
+        self.uid
+    }
+}
+
+/// An iterator over the vids of a [`TxnGraph`] in ascending order.
+pub struct NodeIdentifiers<'a> {
+    vcur: VertexCur<'a>,
+}
+
+impl<'a> Iterator for NodeIdentifiers<'a> {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        if !self.vcur.is_valid() {
+            return None;
+        }
+        let vid = self
+            .vcur
+            .id()
+            .and_then(|vid| self.vcur.seek_to_next().map(|_| vid))
+            .unwrap_or_else(|e| panic!("failed to read vertices: {}", e));
+        Some(vid)
+    }
+}
+
+/// An iterator over the edges of a [`TxnGraph`], in ascending order of their sources.
+pub struct EdgeReferences<'a> {
+    graph: &'a TxnGraph<'a>,
+    vids: NodeIdentifiers<'a>,
+    edges: vec::IntoIter<Edge>,
+}
+
+impl<'a> Iterator for EdgeReferences<'a> {
+    type Item = Edge;
+
+    fn next(&mut self) -> Option<Edge> {
+        loop {
+            if let Some(edge) = self.edges.next() {
+                return Some(edge);
+            }
+            let vid = self.vids.next()?;
+            self.edges = self
+                .graph
+                .edges_or_panic(vid, Direction::Outgoing)
+                .into_iter();
+        }
+    }
+}
+
+impl<'a> GraphBase for TxnGraph<'a> {
+    type NodeId = i64;
+    type EdgeId = EdgeUid;
+}
+
+impl<'a> Data for TxnGraph<'a> {
+    type NodeWeight = ();
+    type EdgeWeight = f64;
+}
+
+impl<'a> IntoNeighbors for &'a TxnGraph<'a> {
+    type Neighbors = vec::IntoIter<i64>;
+
+    fn neighbors(self, a: i64) -> Self::Neighbors {
+        self.neighbors_directed(a, Direction::Outgoing)
+    }
+}
+
+impl<'a> IntoNeighborsDirected for &'a TxnGraph<'a> {
+    type NeighborsDirected = vec::IntoIter<i64>;
+
+    fn neighbors_directed(self, a: i64, dir: Direction) -> Self::NeighborsDirected {
+        let neighbors: Vec<_> = self
+            .edges_or_panic(a, dir)
+            .into_iter()
+            .map(|edge| match dir {
+                Direction::Outgoing => edge.uid.dst,
+                Direction::Incoming => edge.uid.src,
+            })
+            .collect();
+        neighbors.into_iter()
+    }
+}
+
+impl<'a> IntoEdgeReferences for &'a TxnGraph<'a> {
+    type EdgeRef = Edge;
+    type EdgeReferences = EdgeReferences<'a>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        EdgeReferences {
+            graph: self,
+            vids: self.node_identifiers(),
+            edges: vec![].into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoEdges for &'a TxnGraph<'a> {
+    type Edges = vec::IntoIter<Edge>;
+
+    fn edges(self, a: i64) -> Self::Edges {
+        self.edges_directed(a, Direction::Outgoing)
+    }
+}
+
+impl<'a> IntoEdgesDirected for &'a TxnGraph<'a> {
+    type EdgesDirected = vec::IntoIter<Edge>;
+
+    fn edges_directed(self, a: i64, dir: Direction) -> Self::EdgesDirected {
+        self.edges_or_panic(a, dir).into_iter()
+    }
+}
+
+impl<'a> IntoNodeIdentifiers for &'a TxnGraph<'a> {
+    type NodeIdentifiers = NodeIdentifiers<'a>;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        let vcur = self.txn.vertex_cur().and_then(|mut vcur| {
+            vcur.seek(0, true)?;
+            Ok(vcur)
+        });
+        NodeIdentifiers {
+            vcur: vcur.unwrap_or_else(|e| panic!("failed to read vertices: {}", e)),
+        }
+    }
+}
+
+impl<'a> NodeIndexable for TxnGraph<'a> {
+    fn node_bound(&self) -> usize {
+        if let Some(bound) = self.node_bound.get() {
+            return bound;
+        }
+        let bound = self
+            .node_identifiers()
+            .last()
+            .map_or(0, |vid| vid as usize + 1);
+        self.node_bound.set(Some(bound));
+        bound
+    }
+
+    fn to_index(&self, a: i64) -> usize {
+        a as usize
+    }
+
+    fn from_index(&self, i: usize) -> i64 {
+        i as i64
+    }
+}
+
+impl<'a> Visitable for TxnGraph<'a> {
+    type Map = HashSet<i64>;
+
+    fn visit_map(&self) -> Self::Map {
+        HashSet::new()
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        map.clear();
+    }
+}
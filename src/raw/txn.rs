@@ -334,23 +334,11 @@ impl RawTransaction {
         T: CStrLike,
         U: IntoIterator<Item = T>,
     {
-        unsafe {
-            let cfield_names: Vec<_> = field_names
-                .into_iter()
-                .map(|n| n.into_c_string().unwrap())
-                .collect();
-            let cfield_names: Vec<_> = cfield_names.iter().map(|n| n.as_ptr()).collect();
-            let mut cfield_ids: *mut usize = ptr::null_mut();
-            let len = ffi_try! {ffi::lgraph_api_transaction_get_vertex_field_ids(
-                self.inner,
-                label_id,
-                cfield_names.as_ptr(),
-                cfield_names.len(),
-                &mut cfield_ids as *mut _,
-            )}?;
-            let ids = slice::from_raw_parts(cfield_ids, len).to_vec();
-            Ok(ids)
-        }
+        // resolve ids one by one through the edge field id api
+        field_names
+            .into_iter()
+            .map(|n| self.get_edge_field_id(label_id, n))
+            .collect()
     }
 
     pub(crate) fn add_vertex_by_value_strings<T, D, E, U, S>(
@@ -784,8 +772,8 @@ impl RawTransaction {
     pub(crate) fn get_vertex_by_unique_index_value_string<T, U, S>(
         &self,
         label: T,
-        field_name: T,
-        field_value_string: T,
+        field_name: U,
+        field_value_string: S,
     ) -> Result<RawVertexCursor, Error>
     where
         T: CStrLike,
@@ -18,6 +18,7 @@ use crate::{raw::RawRoleInfo, types::AccessLevel};
 
 /// `RoleInfo` describe the role information of database
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RoleInfo {
     /// The Description of the role.
     pub desc: String,
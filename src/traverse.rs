@@ -0,0 +1,529 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Breadth-first and depth-first traversals over the graph.
+//!
+//! A [`Traversal`] describes how to walk the graph: the edge direction, the edge labels
+//! to follow, the max depth and the per-hop predicates. It starts walking from one or more
+//! vertices by [`Traversal::bfs`] or [`Traversal::dfs`], which return an iterator yielding
+//! `(depth, vid, via)` for each vertex reached, where `via` is the edge the vertex is
+//! reached through, or `None` for a start vertex.
+//!
+//! ```no_run
+//! use tugraph::{db::OpenOptions, traverse::{Direction, Traversal}, txn::TxnRead};
+//!
+//! # fn main() -> Result<(), tugraph::Error> {
+//! let galaxy = OpenOptions::new().open("/var/lib/lgraph/data", "admin", "73@TuGraph")?;
+//! let graph = galaxy.open_graph("default", true)?;
+//! let ro_txn = graph.create_ro_txn()?;
+//! for step in Traversal::new()
+//!     .direction(Direction::Both)
+//!     .edge_labels(["knows"])
+//!     .max_depth(2)
+//!     .bfs(&ro_txn, [0])?
+//! {
+//!     let (depth, vid, via) = step?;
+//!     println!("{depth} {vid} {via:?}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    cursor::{EdgeCursor, InEdgeCur, OutEdgeCur, VertexCur, VertexCursor},
+    field::{FieldData, FieldValue},
+    txn::TxnRead,
+    types::EdgeUid,
+    Result,
+};
+
+/// The direction of edges to follow from a vertex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Direction {
+    /// Follow out edges, from src to dst.
+    #[default]
+    Out,
+    /// Follow in edges, from dst to src.
+    In,
+    /// Follow both out and in edges.
+    Both,
+}
+
+impl Direction {
+    pub(crate) fn has_out(self) -> bool {
+        matches!(self, Direction::Out | Direction::Both)
+    }
+
+    pub(crate) fn has_in(self) -> bool {
+        matches!(self, Direction::In | Direction::Both)
+    }
+
+    pub(crate) fn reversed(self) -> Self {
+        match self {
+            Direction::Out => Direction::In,
+            Direction::In => Direction::Out,
+            Direction::Both => Direction::Both,
+        }
+    }
+}
+
+/// The strategy to record visited vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VisitedSet {
+    /// A hash set of vids, suitable for sparse vids or small traversals.
+    #[default]
+    Hash,
+    /// A bitmap indexed by vid, suitable for dense vids, which costs one bit
+    /// per vid up to the largest visited vid.
+    ///
+    /// Only vids in `[0, 2^32)` are recorded in the bitmap, so it takes at most 512 MiB.
+    /// Other vids are recorded in a hash set instead.
+    Bitmap,
+}
+
+// The exclusive upper bound of vids recorded in a bitmap.
+const BITMAP_VID_BOUND: i64 = 1 << 32;
+
+pub(crate) enum Visited {
+    Hash(HashSet<i64>),
+    Bitmap {
+        bits: Vec<u64>,
+        // vids out of the bitmap bound
+        others: HashSet<i64>,
+    },
+}
+
+impl Visited {
+    pub(crate) fn new(strategy: VisitedSet) -> Self {
+        match strategy {
+            VisitedSet::Hash => Visited::Hash(HashSet::new()),
+            VisitedSet::Bitmap => Visited::Bitmap {
+                bits: Vec::new(),
+                others: HashSet::new(),
+            },
+        }
+    }
+
+    pub(crate) fn contains(&self, vid: i64) -> bool {
+        match self {
+            Visited::Hash(set) => set.contains(&vid),
+            Visited::Bitmap { others, .. } if !(0..BITMAP_VID_BOUND).contains(&vid) => {
+                others.contains(&vid)
+            }
+            Visited::Bitmap { bits, .. } => {
+                let (word, bit) = (vid as usize / 64, vid as usize % 64);
+                bits.get(word).map_or(false, |w| w & (1 << bit) != 0)
+            }
+        }
+    }
+
+    /// Returns `false` if `vid` was visited.
+    pub(crate) fn insert(&mut self, vid: i64) -> bool {
+        match self {
+            Visited::Hash(set) => set.insert(vid),
+            Visited::Bitmap { others, .. } if !(0..BITMAP_VID_BOUND).contains(&vid) => {
+                others.insert(vid)
+            }
+            Visited::Bitmap { bits, .. } => {
+                let (word, bit) = (vid as usize / 64, vid as usize % 64);
+                if word >= bits.len() {
+                    bits.resize(word + 1, 0);
+                }
+                let inserted = bits[word] & (1 << bit) == 0;
+                bits[word] |= 1 << bit;
+                inserted
+            }
+        }
+    }
+}
+
+/// The edge a traversal is about to follow, given to the edge predicate.
+pub struct HopEdge<'a, 'txn> {
+    inner: HopEdgeInner<'a, 'txn>,
+}
+
+enum HopEdgeInner<'a, 'txn> {
+    Out(&'a OutEdgeCur<'txn>),
+    In(&'a InEdgeCur<'txn>),
+}
+
+impl<'a, 'txn> HopEdge<'a, 'txn> {
+    /// Get uid of the edge.
+    pub fn uid(&self) -> Result<EdgeUid> {
+        match self.inner {
+            HopEdgeInner::Out(ecur) => ecur.uid(),
+            HopEdgeInner::In(ecur) => ecur.uid(),
+        }
+    }
+
+    /// Get label id of the edge.
+    pub fn lid(&self) -> Result<u16> {
+        match self.inner {
+            HopEdgeInner::Out(ecur) => ecur.lid(),
+            HopEdgeInner::In(ecur) => ecur.lid(),
+        }
+    }
+
+    /// Get field value with given name of the edge.
+    pub fn field(&self, name: &str) -> Result<FieldData> {
+        match self.inner {
+            HopEdgeInner::Out(ecur) => ecur.field(name),
+            HopEdgeInner::In(ecur) => ecur.field(name),
+        }
+    }
+
+    /// Get field value with given name of the edge, converted to `T`.
+    ///
+    /// See [`EdgeCursor::get`] for the conversion rules.
+    pub fn get<T: FieldValue>(&self, name: &str) -> Result<T> {
+        T::from_field_data(self.field(name)?)
+    }
+}
+
+type VertexPredicate<'f> = Box<dyn FnMut(&VertexCur<'_>) -> Result<bool> + 'f>;
+type EdgePredicate<'f> = Box<dyn FnMut(&HopEdge<'_, '_>) -> Result<bool> + 'f>;
+
+/// `Traversal` describes how to walk the graph from some start vertices.
+///
+/// Each vertex is reached at most once. The start vertices are always yielded,
+/// while the predicates only apply to the hops from them.
+#[must_use]
+pub struct Traversal<'f> {
+    direction: Direction,
+    edge_labels: Option<Vec<String>>,
+    max_depth: usize,
+    visited: VisitedSet,
+    vertex_predicate: Option<VertexPredicate<'f>>,
+    edge_predicate: Option<EdgePredicate<'f>>,
+}
+
+impl<'f> Default for Traversal<'f> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'f> Traversal<'f> {
+    /// Create a traversal following out edges of all labels without depth limit.
+    pub fn new() -> Self {
+        Traversal {
+            direction: Direction::Out,
+            edge_labels: None,
+            max_depth: usize::MAX,
+            visited: VisitedSet::Hash,
+            vertex_predicate: None,
+            edge_predicate: None,
+        }
+    }
+
+    /// Set the direction of edges to follow.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Only follow edges of the given labels.
+    pub fn edge_labels<I, S>(mut self, labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.edge_labels = Some(labels.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the max depth to reach, where start vertices are of depth 0.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Set the strategy to record visited vertices.
+    pub fn visited(mut self, visited: VisitedSet) -> Self {
+        self.visited = visited;
+        self
+    }
+
+    /// Only reach vertices accepted by `predicate`.
+    ///
+    /// The predicate is given a cursor pointing to the vertex to reach.
+    pub fn vertex_predicate<P>(mut self, predicate: P) -> Self
+    where
+        P: FnMut(&VertexCur<'_>) -> Result<bool> + 'f,
+    {
+        self.vertex_predicate = Some(Box::new(predicate));
+        self
+    }
+
+    /// Only follow edges accepted by `predicate`.
+    pub fn edge_predicate<P>(mut self, predicate: P) -> Self
+    where
+        P: FnMut(&HopEdge<'_, '_>) -> Result<bool> + 'f,
+    {
+        self.edge_predicate = Some(Box::new(predicate));
+        self
+    }
+
+    /// Walk the graph breadth-first from `starts`.
+    ///
+    /// The depth yielded of each vertex is the length of the shortest path to it.
+    ///
+    /// # Errors
+    /// If an edge label does not exist, an error is returned.
+    pub fn bfs<'t, T, I>(self, txn: &'t T, starts: I) -> Result<Traverse<'t, 'f>>
+    where
+        T: TxnRead,
+        I: IntoIterator<Item = i64>,
+    {
+        Traverse::new(self, txn, starts, Order::Bfs)
+    }
+
+    /// Walk the graph depth-first from `starts`, yielding vertices in preorder.
+    ///
+    /// The depth yielded of each vertex is the length of the first path found to it.
+    /// With a max depth, a vertex first reached by a deeper path is expanded again once a
+    /// shallower path reaches it, so that all vertices within the max depth are yielded.
+    ///
+    /// # Errors
+    /// If an edge label does not exist, an error is returned.
+    pub fn dfs<'t, T, I>(self, txn: &'t T, starts: I) -> Result<Traverse<'t, 'f>>
+    where
+        T: TxnRead,
+        I: IntoIterator<Item = i64>,
+    {
+        Traverse::new(self, txn, starts, Order::Dfs)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Bfs,
+    Dfs,
+}
+
+/// An iterator over the vertices reached by a [`Traversal`].
+///
+/// See the [`Traversal::bfs`] and [`Traversal::dfs`] for details.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Traverse<'t, 'f> {
+    vcur: VertexCur<'t>,
+    order: Order,
+    direction: Direction,
+    edge_lids: Option<Vec<u16>>,
+    max_depth: usize,
+    visited: Visited,
+    vertex_predicate: Option<VertexPredicate<'f>>,
+    edge_predicate: Option<EdgePredicate<'f>>,
+    pending: VecDeque<(usize, i64, Option<EdgeUid>)>,
+    // the smallest depth each vertex is expanded at, only for dfs with a max depth
+    depths: Option<HashMap<i64, usize>>,
+    failed: bool,
+}
+
+impl<'t, 'f> Traverse<'t, 'f> {
+    fn new<T, I>(traversal: Traversal<'f>, txn: &'t T, starts: I, order: Order) -> Result<Self>
+    where
+        T: TxnRead,
+        I: IntoIterator<Item = i64>,
+    {
+        let edge_lids = traversal
+            .edge_labels
+            .map(|labels| {
+                labels
+                    .iter()
+                    .map(|label| txn.edge_label_id(label).map(|lid| lid as u16))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+        let mut traverse = Traverse {
+            vcur: txn.vertex_cur()?,
+            order,
+            direction: traversal.direction,
+            edge_lids,
+            max_depth: traversal.max_depth,
+            visited: Visited::new(traversal.visited),
+            vertex_predicate: traversal.vertex_predicate,
+            edge_predicate: traversal.edge_predicate,
+            pending: VecDeque::new(),
+            depths: (order == Order::Dfs && traversal.max_depth != usize::MAX).then(HashMap::new),
+            failed: false,
+        };
+        for vid in starts {
+            if !traverse.vcur.seek(vid, false)?.is_valid() {
+                continue;
+            }
+            if order == Order::Dfs || traverse.visited.insert(vid) {
+                traverse.pending.push_back((0, vid, None));
+            }
+        }
+        Ok(traverse)
+    }
+
+    // Collect the vertices reachable in one hop from `vid`.
+    fn hops(&mut self, vid: i64) -> Result<Vec<(i64, EdgeUid)>> {
+        let mut hops = vec![];
+        if !self.vcur.seek(vid, false)?.is_valid() {
+            return Ok(hops);
+        }
+        if self.direction.has_out() {
+            let mut ecur = self.vcur.out_edge_cursor()?;
+            while ecur.is_valid() {
+                if accept_edge(
+                    &self.edge_lids,
+                    &mut self.edge_predicate,
+                    &HopEdge {
+                        inner: HopEdgeInner::Out(&ecur),
+                    },
+                )? {
+                    hops.push((ecur.dst()?, ecur.uid()?));
+                }
+                ecur.seek_to_next()?;
+            }
+        }
+        if self.direction.has_in() {
+            let mut ecur = self.vcur.in_edge_cursor()?;
+            while ecur.is_valid() {
+                if accept_edge(
+                    &self.edge_lids,
+                    &mut self.edge_predicate,
+                    &HopEdge {
+                        inner: HopEdgeInner::In(&ecur),
+                    },
+                )? {
+                    hops.push((ecur.src()?, ecur.uid()?));
+                }
+                ecur.seek_to_next()?;
+            }
+        }
+        Ok(hops)
+    }
+
+    fn accept_vertex(&mut self, vid: i64) -> Result<bool> {
+        match &mut self.vertex_predicate {
+            Some(predicate) => {
+                self.vcur.seek(vid, false)?;
+                Ok(self.vcur.is_valid() && predicate(&self.vcur)?)
+            }
+            None => Ok(true),
+        }
+    }
+
+    // Whether `vid` is visited, but expanded at a depth larger than `depth`.
+    fn shallower(&self, vid: i64, depth: usize) -> bool {
+        self.depths
+            .as_ref()
+            .and_then(|depths| depths.get(&vid))
+            .map_or(false, |&expanded| depth < expanded)
+    }
+
+    fn expand(&mut self, depth: usize, vid: i64) -> Result<()> {
+        let hops = self.hops(vid)?;
+        let mut next = Vec::with_capacity(hops.len());
+        for (dst, uid) in hops {
+            if (self.visited.contains(dst) && !self.shallower(dst, depth + 1))
+                || !self.accept_vertex(dst)?
+            {
+                continue;
+            }
+            if self.order == Order::Bfs {
+                self.visited.insert(dst);
+            }
+            next.push((depth + 1, dst, Some(uid)));
+        }
+        match self.order {
+            Order::Bfs => self.pending.extend(next),
+            // the first hop is visited first
+            Order::Dfs => next
+                .into_iter()
+                .rev()
+                .for_each(|hop| self.pending.push_front(hop)),
+        }
+        Ok(())
+    }
+}
+
+fn accept_edge(
+    edge_lids: &Option<Vec<u16>>,
+    edge_predicate: &mut Option<EdgePredicate<'_>>,
+    edge: &HopEdge<'_, '_>,
+) -> Result<bool> {
+    if let Some(lids) = edge_lids {
+        if !lids.contains(&edge.lid()?) {
+            return Ok(false);
+        }
+    }
+    match edge_predicate {
+        Some(predicate) => predicate(edge),
+        None => Ok(true),
+    }
+}
+
+impl<'t, 'f> Iterator for Traverse<'t, 'f> {
+    type Item = Result<(usize, i64, Option<EdgeUid>)>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            let (depth, vid, via) = self.pending.pop_front()?;
+            // dfs marks a vertex visited when it is popped, so that it may be pushed many times
+            let first = self.order == Order::Bfs || self.visited.insert(vid);
+            // a vertex popped again is only expanded again if it is reached by a shallower path
+            if !first && !self.shallower(vid, depth) {
+                continue;
+            }
+            if let Some(depths) = &mut self.depths {
+                depths.insert(vid, depth);
+            }
+            if depth < self.max_depth {
+                if let Err(e) = self.expand(depth, vid) {
+                    self.failed = true;
+                    return Some(Err(e));
+                }
+            }
+            if first {
+                return Some(Ok((depth, vid, via)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Visited, VisitedSet};
+
+    #[test]
+    fn test_visited() {
+        for strategy in [VisitedSet::Hash, VisitedSet::Bitmap] {
+            let mut visited = Visited::new(strategy);
+            assert!(!visited.contains(0));
+            assert!(visited.insert(0));
+            assert!(!visited.insert(0));
+            assert!(visited.contains(0));
+            assert!(visited.insert(1000));
+            assert!(visited.contains(1000));
+            assert!(!visited.contains(999));
+            assert!(!visited.contains(100_000));
+            // out of the bitmap bound
+            for vid in [-1, i64::MIN, 1 << 32, i64::MAX] {
+                assert!(!visited.contains(vid));
+                assert!(visited.insert(vid));
+                assert!(!visited.insert(vid));
+                assert!(visited.contains(vid));
+            }
+        }
+    }
+}
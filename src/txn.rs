@@ -29,30 +29,44 @@
 //! view as the forked one, and it can be used in a separate thread. By forking from one
 //! read transaction and using the forked copies in different threads, we can parallelize
 //! the execution of specific operations. For example, you can implement a parallel BFS
-//! with this capability. Also, you can dump a snapshot of the whole graph using the forked
-//! one.
+//! with this capability, as [`Parallel::par_bfs`] does. Also, you can dump a snapshot of
+//! the whole graph using the forked one.
 //!
 //! [`Graph`]: crate::db::Graph
+//! [`Parallel::par_bfs`]: crate::parallel::Parallel::par_bfs
 //! [`Graph::create_ro_txn`]: crate::db::Graph::create_ro_txn
 //! [`Graph::create_rw_txn`]: crate::db::Graph::create_rw_txn
 
-use std::{fmt::Debug, marker::PhantomData};
+use std::{
+    cmp,
+    collections::hash_map::RandomState,
+    fmt::Debug,
+    hash::{BuildHasher, Hasher},
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::Duration,
+};
 
 use crate::{
     cursor::{
         EdgeIndexDsts, EdgeIndexEdgeIds, EdgeIndexIter, EdgeIndexLabelIds, EdgeIndexSrcs,
-        EdgeIndexUids, EdgeIndexValues, OutEdgeCur, OutEdgeCurMut, VertexCur, VertexCurMut,
-        VertexIndexIds, VertexIndexIter, VertexIndexValues,
+        EdgeIndexUids, EdgeIndexValues, InEdgeCur, InEdgeCurMut, OutEdgeCur, OutEdgeCurMut,
+        VertexCur, VertexCurMut, VertexCursor, VertexCursorMut, VertexIndexIds, VertexIndexIter,
+        VertexIndexValues,
     },
     field::{FieldData, FieldSpec},
     index::IndexSpec,
+    label::{EdgeLabel, VertexLabel},
     raw::RawTransaction,
     types::EdgeUid,
-    Result,
+    Error, Result,
 };
 
 trait AsRawTransaction {
     fn as_raw(&self) -> &RawTransaction;
+
+    fn serial(&self) -> u64;
 }
 
 /// `TxnRead` trait provides all read operations of a transaction.
@@ -67,6 +81,11 @@ pub trait TxnRead {
     /// Query if this txn is read only.
     fn is_read_only(&self) -> bool;
 
+    // The serial number identifying this transaction, used by label handles to check
+    // their schemas once per transaction.
+    #[doc(hidden)]
+    fn serial(&self) -> u64;
+
     /// Get a read-only vertex cursor pointing to the first vertex. If there is no vertex, the
     /// cursor is invalid.
     fn vertex_cur(&self) -> Result<VertexCur<'_>>;
@@ -89,6 +108,16 @@ pub trait TxnRead {
     /// Get edge label id corresponding to the label name.
     fn edge_label_id(&self, label: &str) -> Result<usize>;
 
+    /// Get a handle of the vertex label, which caches the label id and field ids.
+    ///
+    /// See [`VertexLabel`] for more details.
+    fn vertex_label(&self, label: &str) -> Result<VertexLabel>;
+
+    /// Get a handle of the edge label, which caches the label id and field ids.
+    ///
+    /// See [`EdgeLabel`] for more details.
+    fn edge_label(&self, label: &str) -> Result<EdgeLabel>;
+
     /// Get vertex schema definition corresponding to the vertex label.
     fn vertex_schema(&self, label: &str) -> Result<Vec<FieldSpec>>;
 
@@ -373,6 +402,17 @@ pub trait TxnRead {
         field_value: &FieldData,
     ) -> Result<VertexCur<'_>>;
 
+    /// Get vertex cursor by unique index.
+    ///
+    /// An overloaded one of [`TxnRead::unique_index_vertex_cur`] whose value is given as a string,
+    /// which is parsed into the type of the indexed field by TuGraph.
+    fn unique_index_vertex_cur_str(
+        &self,
+        label: &str,
+        field: &str,
+        value_string: &str,
+    ) -> Result<VertexCur<'_>>;
+
     /// Get out edge cursor by unique index.
     fn unique_index_out_edgr_cur(
         &self,
@@ -391,6 +431,61 @@ pub trait TxnRead {
         value: &FieldData,
     ) -> Result<OutEdgeCur<'_>>;
 
+    /// Get an out edge cursor pointing to the edge specified by `euid`.
+    ///
+    /// If the edge does not exist, the cursor is invalid. Use [`EdgeCursor::seek`] to position
+    /// the cursor at the nearest edge instead.
+    ///
+    /// [`EdgeCursor::seek`]: crate::cursor::EdgeCursor::seek
+    fn out_edge_cur(&self, euid: &EdgeUid) -> Result<OutEdgeCur<'_>>;
+
+    /// Get an in edge cursor pointing to the edge specified by `euid`.
+    ///
+    /// If the edge does not exist, the cursor is invalid.
+    fn in_edge_cur(&self, euid: &EdgeUid) -> Result<InEdgeCur<'_>>;
+
+    /// Get an out edge cursor pointing to the first edge from `src` to `dst` with `label`.
+    ///
+    /// If there is no such edge, the cursor is invalid. Note that the cursor keeps moving
+    /// along the out edges of `src` by [`EdgeCursor::seek_to_next`], so check the [`EdgeCursor::dst`]
+    /// and [`EdgeCursor::lid`] to stop at the last edge between `src` and `dst`.
+    ///
+    /// # Examples
+    /// ```
+    /// use tugraph::{cursor::EdgeCursor, db::OpenOptions, txn::TxnRead, Error};
+    ///
+    /// let galaxy = OpenOptions::new()
+    ///     .create(true)
+    ///     .open("/tmp/rust_tugraph/doc/edges_between", "admin", "73@TuGraph")?;
+    /// let graph = galaxy.open_graph("default", true)?;
+    /// let ro_txn = graph.create_ro_txn()?;
+    /// // suppose there is an edge label "knows"
+    /// if let Ok(cursor) = ro_txn.edges_between(0, 1, "knows") {
+    ///     let exists = cursor.is_valid();
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    ///
+    /// [`EdgeCursor::seek_to_next`]: crate::cursor::EdgeCursor::seek_to_next
+    /// [`EdgeCursor::dst`]: crate::cursor::EdgeCursor::dst
+    /// [`EdgeCursor::lid`]: crate::cursor::EdgeCursor::lid
+    fn edges_between(&self, src: i64, dst: i64, label: &str) -> Result<OutEdgeCur<'_>>;
+
+    /// Get an out edge cursor pointing to the first edge from `src` to `dst` with `label_id`.
+    ///
+    /// An overloaded one of [`TxnRead::edges_between`]
+    fn edges_between_by_id(&self, src: i64, dst: i64, label_id: usize) -> Result<OutEdgeCur<'_>>;
+
+    /// Get an in edge cursor pointing to the first edge to `dst` from `src` with `label`.
+    ///
+    /// If there is no such edge, the cursor is invalid. See [`TxnRead::edges_between`].
+    fn in_edges_between(&self, src: i64, dst: i64, label: &str) -> Result<InEdgeCur<'_>>;
+
+    /// Get an in edge cursor pointing to the first edge to `dst` from `src` with `label_id`.
+    ///
+    /// An overloaded one of [`TxnRead::in_edges_between`]
+    fn in_edges_between_by_id(&self, src: i64, dst: i64, label_id: usize) -> Result<InEdgeCur<'_>>;
+
     /// Get the number of vertices.
     fn num_vertices(&self) -> Result<usize>;
 
@@ -410,6 +505,10 @@ where
         self.as_raw().is_read_only()
     }
 
+    fn serial(&self) -> u64 {
+        AsRawTransaction::serial(self)
+    }
+
     fn vertex_cur(&self) -> Result<VertexCur<'_>> {
         self.as_raw().get_vertex_iterator().map(VertexCur::new)
     }
@@ -438,6 +537,14 @@ where
         self.as_raw().get_edge_label_id(label)
     }
 
+    fn vertex_label(&self, label: &str) -> Result<VertexLabel> {
+        VertexLabel::load(self, label)
+    }
+
+    fn edge_label(&self, label: &str) -> Result<EdgeLabel> {
+        EdgeLabel::load(self, label)
+    }
+
     fn vertex_schema(&self, label: &str) -> Result<Vec<FieldSpec>> {
         self.as_raw().get_vertex_schema(label).map(|fss| {
             fss.into_iter()
@@ -845,6 +952,17 @@ where
             .map(VertexCur::new)
     }
 
+    fn unique_index_vertex_cur_str(
+        &self,
+        label: &str,
+        field: &str,
+        value_string: &str,
+    ) -> Result<VertexCur<'_>> {
+        self.as_raw()
+            .get_vertex_by_unique_index_value_string(label, field, value_string)
+            .map(VertexCur::new)
+    }
+
     fn unique_index_out_edgr_cur(
         &self,
         label: &str,
@@ -867,6 +985,40 @@ where
             .map(OutEdgeCur::new)
     }
 
+    fn out_edge_cur(&self, euid: &EdgeUid) -> Result<OutEdgeCur<'_>> {
+        self.as_raw()
+            .get_out_edge_iterator_by_euid(&euid.as_raw(), false)
+            .map(OutEdgeCur::new)
+    }
+
+    fn in_edge_cur(&self, euid: &EdgeUid) -> Result<InEdgeCur<'_>> {
+        self.as_raw()
+            .get_in_edge_iterator_by_euid(&euid.as_raw(), false)
+            .map(InEdgeCur::new)
+    }
+
+    fn edges_between(&self, src: i64, dst: i64, label: &str) -> Result<OutEdgeCur<'_>> {
+        let label_id = self.edge_label_id(label)?;
+        self.edges_between_by_id(src, dst, label_id)
+    }
+
+    fn edges_between_by_id(&self, src: i64, dst: i64, label_id: usize) -> Result<OutEdgeCur<'_>> {
+        self.as_raw()
+            .get_out_edge_iterator_by_src_dst_lid(src, dst, edge_lid(label_id)?)
+            .map(OutEdgeCur::new)
+    }
+
+    fn in_edges_between(&self, src: i64, dst: i64, label: &str) -> Result<InEdgeCur<'_>> {
+        let label_id = self.edge_label_id(label)?;
+        self.in_edges_between_by_id(src, dst, label_id)
+    }
+
+    fn in_edges_between_by_id(&self, src: i64, dst: i64, label_id: usize) -> Result<InEdgeCur<'_>> {
+        self.as_raw()
+            .get_in_edge_iterator_by_src_dst_lid(src, dst, edge_lid(label_id)?)
+            .map(InEdgeCur::new)
+    }
+
     fn num_vertices(&self) -> Result<usize> {
         self.as_raw().get_num_vertices()
     }
@@ -876,6 +1028,10 @@ where
     }
 }
 
+fn edge_lid(label_id: usize) -> Result<i16> {
+    i16::try_from(label_id).map_err(|_| Error::invalid_parameter())
+}
+
 /// `TxnWrite` trait provides all write operations of a transaction.
 ///
 /// You can use `TxnWrite` to get vertex cursor which can move back and forth
@@ -918,6 +1074,52 @@ pub trait TxnWrite {
         value: &FieldData,
     ) -> Result<OutEdgeCurMut<'_>>;
 
+    /// Get an out edge cursor pointing to the edge specified by `euid` that allows
+    /// modifying each edge.
+    ///
+    /// See [`TxnRead::out_edge_cur`].
+    fn out_edge_cur_mut(&self, euid: &EdgeUid) -> Result<OutEdgeCurMut<'_>>;
+
+    /// Get an in edge cursor pointing to the edge specified by `euid` that allows
+    /// modifying each edge.
+    ///
+    /// See [`TxnRead::in_edge_cur`].
+    fn in_edge_cur_mut(&self, euid: &EdgeUid) -> Result<InEdgeCurMut<'_>>;
+
+    /// Get an out edge cursor pointing to the first edge from `src` to `dst` with `label`
+    /// that allows modifying each edge.
+    ///
+    /// See [`TxnRead::edges_between`].
+    fn edges_between_mut(&self, src: i64, dst: i64, label: &str) -> Result<OutEdgeCurMut<'_>>;
+
+    /// Get an out edge cursor pointing to the first edge from `src` to `dst` with `label_id`
+    /// that allows modifying each edge.
+    ///
+    /// An overloaded one of [`TxnWrite::edges_between_mut`]
+    fn edges_between_mut_by_id(
+        &self,
+        src: i64,
+        dst: i64,
+        label_id: usize,
+    ) -> Result<OutEdgeCurMut<'_>>;
+
+    /// Get an in edge cursor pointing to the first edge to `dst` from `src` with `label`
+    /// that allows modifying each edge.
+    ///
+    /// See [`TxnRead::in_edges_between`].
+    fn in_edges_between_mut(&self, src: i64, dst: i64, label: &str) -> Result<InEdgeCurMut<'_>>;
+
+    /// Get an in edge cursor pointing to the first edge to `dst` from `src` with `label_id`
+    /// that allows modifying each edge.
+    ///
+    /// An overloaded one of [`TxnWrite::in_edges_between_mut`]
+    fn in_edges_between_mut_by_id(
+        &self,
+        src: i64,
+        dst: i64,
+        label_id: usize,
+    ) -> Result<InEdgeCurMut<'_>>;
+
     /// Add a vertex.
     ///
     /// All non-nullable fields must be specified. All indexes of vertex are also
@@ -935,6 +1137,23 @@ pub trait TxnWrite {
     where
         T: IntoIterator<Item = &'a FieldData>;
 
+    /// Add a vertex whose field values are given as strings.
+    ///
+    /// An overloaded one of [`TxnWrite::add_vertex`]. Each value string is parsed into the
+    /// type of its field by TuGraph, e.g. `"2023-01-01"` for a [`FieldType::Date`] field, which
+    /// comes in handy when ingesting text data such as CSV.
+    ///
+    /// # Errors
+    /// If a value string cannot be parsed into the type of its field, an error is returned.
+    ///
+    /// [`FieldType::Date`]: crate::field::FieldType::Date
+    fn add_vertex_str(
+        &mut self,
+        label: &str,
+        field_names: &[&str],
+        field_value_strings: &[&str],
+    ) -> Result<i64>;
+
     /// Add a vertex.
     ///
     /// An overloaded one of [`TxnWrite::add_vertex`].
@@ -966,6 +1185,72 @@ pub trait TxnWrite {
     where
         T: IntoIterator<Item = &'a FieldData>;
 
+    /// Upsert a vertex keyed on the primary field of `label`.
+    ///
+    /// If there is no vertex whose primary field equals to the given one, insert it.
+    /// Otherwise, update the fields of the existing vertex with the given values. It returns
+    /// whether a vertex was inserted(`true`) or updated(`false`), and the vertex id.
+    ///
+    /// # Errors
+    /// If the primary field of `label` is not in `field_names`, or `field_names` and
+    /// `field_values` differ in length, an [`ErrorKind::InvalidParameter`] is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use tugraph::{
+    ///     db::OpenOptions,
+    ///     field::{FieldData, FieldSpec, FieldType},
+    ///     txn::TxnWrite,
+    ///     Error,
+    /// };
+    ///
+    /// let galaxy = OpenOptions::new()
+    ///     .create(true)
+    ///     .open("/tmp/rust_tugraph/doc/upsert_vertex", "admin", "73@TuGraph")?;
+    /// let graph = galaxy.open_graph("default", false)?;
+    /// graph.add_vertex_label(
+    ///     "Person",
+    ///     &[
+    ///         FieldSpec { name: "name".into(), ty: FieldType::String, optional: false },
+    ///         FieldSpec { name: "age".into(), ty: FieldType::Int32, optional: true },
+    ///     ],
+    ///     "name",
+    /// )?;
+    /// let mut rw_txn = graph.create_rw_txn(false)?;
+    /// let name = FieldData::String("alice".into());
+    /// let (inserted, vid) = rw_txn.upsert_vertex("Person", &["name"], [&name])?;
+    /// assert!(inserted);
+    /// let (inserted, same_vid) =
+    ///     rw_txn.upsert_vertex("Person", &["name", "age"], [&name, &FieldData::Int32(30)])?;
+    /// assert!(!inserted);
+    /// assert_eq!(vid, same_vid);
+    /// rw_txn.commit()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    ///
+    /// [`ErrorKind::InvalidParameter`]: crate::ErrorKind::InvalidParameter
+    fn upsert_vertex<'a, T>(
+        &mut self,
+        label: &str,
+        field_names: &[&str],
+        field_values: T,
+    ) -> Result<(bool, i64)>
+    where
+        T: IntoIterator<Item = &'a FieldData>;
+
+    /// Add a edge whose field values are given as strings.
+    ///
+    /// An overloaded one of [`TxnWrite::add_edge`]. See [`TxnWrite::add_vertex_str`] for how
+    /// value strings are parsed.
+    fn add_edge_str(
+        &mut self,
+        src: i64,
+        dst: i64,
+        label: &str,
+        field_names: &[&str],
+        field_value_strings: &[&str],
+    ) -> Result<EdgeUid>;
+
     /// Add a edge.
     ///
     /// An overloaded one of [`TxnWrite::add_edge`].
@@ -1002,6 +1287,19 @@ pub trait TxnWrite {
     where
         T: IntoIterator<Item = &'a FieldData>;
 
+    /// Upsert edge whose field values are given as strings.
+    ///
+    /// An overloaded one of [`TxnWrite::upsert_edge`]. See [`TxnWrite::add_vertex_str`] for how
+    /// value strings are parsed.
+    fn upsert_edge_str(
+        &mut self,
+        src: i64,
+        dst: i64,
+        label: &str,
+        field_names: &[&str],
+        field_value_strings: &[&str],
+    ) -> Result<bool>;
+
     /// Upsert edge.
     ///
     /// An overloaded one of [`TxnWrite::upsert_edge`]
@@ -1015,10 +1313,126 @@ pub trait TxnWrite {
     ) -> Result<bool>
     where
         T: IntoIterator<Item = &'a FieldData>;
+
+    /// Add vertices of the same label in batch.
+    ///
+    /// Label and field ids are resolved only once before the rows are added, so it is much
+    /// cheaper than calling [`TxnWrite::add_vertex`] for each row. A row that fails to be added
+    /// does not stop the following ones, it is reported in [`BatchReport::failed`] instead.
+    ///
+    /// # Errors
+    /// If the label or any field does not exist, an error is returned and no row is added.
+    ///
+    /// # Examples
+    /// ```
+    /// use tugraph::{
+    ///     db::OpenOptions,
+    ///     field::{FieldData, FieldSpec, FieldType},
+    ///     txn::TxnWrite,
+    ///     Error,
+    /// };
+    ///
+    /// let galaxy = OpenOptions::new()
+    ///     .create(true)
+    ///     .open("/tmp/rust_tugraph/doc/add_vertices", "admin", "73@TuGraph")?;
+    /// let graph = galaxy.open_graph("default", false)?;
+    /// graph.add_vertex_label(
+    ///     "Person",
+    ///     &[FieldSpec { name: "id".into(), ty: FieldType::Int64, optional: false }],
+    ///     "id",
+    /// )?;
+    /// let mut rw_txn = graph.create_rw_txn(false)?;
+    /// let rows: Vec<_> = [1, 2, 2].into_iter().map(|id| [FieldData::Int64(id)]).collect();
+    /// let report = rw_txn.add_vertices("Person", &["id"], &rows)?;
+    /// assert_eq!(report.succeeded.len(), 2);
+    /// // the third row has a duplicate primary key
+    /// assert_eq!(report.failed[0].0, 2);
+    /// rw_txn.commit()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    fn add_vertices<'a, R, V>(
+        &mut self,
+        label: &str,
+        field_names: &[&str],
+        rows: R,
+    ) -> Result<BatchReport<i64>>
+    where
+        R: IntoIterator<Item = V>,
+        V: IntoIterator<Item = &'a FieldData>;
+
+    /// Add edges of the same label in batch.
+    ///
+    /// Each row is a tuple of `(src, dst, field_values)`. See [`TxnWrite::add_vertices`] for
+    /// how rows are added and reported.
+    ///
+    /// # Errors
+    /// If the label or any field does not exist, an error is returned and no row is added.
+    fn add_edges<'a, R, V>(
+        &mut self,
+        label: &str,
+        field_names: &[&str],
+        rows: R,
+    ) -> Result<BatchReport<EdgeUid>>
+    where
+        R: IntoIterator<Item = (i64, i64, V)>,
+        V: IntoIterator<Item = &'a FieldData>;
+}
+
+/// `BatchReport` is the result of adding rows in batch, e.g. [`TxnWrite::add_vertices`].
+///
+/// Rows are identified by their zero-based positions in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchReport<T> {
+    /// Rows that were added, together with the vertex id or edge uid of each one.
+    pub succeeded: Vec<(usize, T)>,
+    /// Rows that failed to be added, together with the error of each one.
+    pub failed: Vec<(usize, Error)>,
+}
+
+impl<T> Default for BatchReport<T> {
+    fn default() -> Self {
+        BatchReport {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+}
+
+impl<T> BatchReport<T> {
+    /// Get the number of rows reported.
+    pub fn num_rows(&self) -> usize {
+        self.succeeded.len() + self.failed.len()
+    }
+
+    /// Query if all rows were added.
+    pub fn is_all_succeeded(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Push the result of the row at `pos`.
+    pub(crate) fn push(&mut self, pos: usize, res: Result<T>) {
+        match res {
+            Ok(v) => self.succeeded.push((pos, v)),
+            Err(e) => self.failed.push((pos, e)),
+        }
+    }
+
+    /// Append `other` whose row positions are offset by `offset`.
+    pub(crate) fn append(&mut self, offset: usize, other: BatchReport<T>) {
+        self.succeeded.extend(
+            other
+                .succeeded
+                .into_iter()
+                .map(|(pos, v)| (pos + offset, v)),
+        );
+        self.failed
+            .extend(other.failed.into_iter().map(|(pos, e)| (pos + offset, e)));
+    }
 }
 
 pub struct RoTxn<'g> {
     inner: RawTransaction,
+    serial: u64,
     // the underlying ffi transaction of `RawTransaction` has a reference
     // to ffi graph db
     _graph: PhantomData<&'g ()>,
@@ -1034,6 +1448,7 @@ impl<'g> RoTxn<'g> {
     pub(crate) fn from_raw(raw: RawTransaction) -> Self {
         RoTxn {
             inner: raw,
+            serial: next_serial(),
             _graph: PhantomData,
         }
     }
@@ -1046,6 +1461,10 @@ impl<'g> AsRawTransaction for RoTxn<'g> {
     fn as_raw(&self) -> &RawTransaction {
         &self.inner
     }
+
+    fn serial(&self) -> u64 {
+        self.serial
+    }
 }
 
 unsafe impl Sync for RoTxn<'_> {}
@@ -1099,6 +1518,50 @@ impl<'g> TxnWrite for RwTxn<'g> {
             .map(OutEdgeCurMut::new)
     }
 
+    fn out_edge_cur_mut(&self, euid: &EdgeUid) -> Result<OutEdgeCurMut<'_>> {
+        self.as_raw()
+            .get_out_edge_iterator_by_euid(&euid.as_raw(), false)
+            .map(OutEdgeCurMut::new)
+    }
+
+    fn in_edge_cur_mut(&self, euid: &EdgeUid) -> Result<InEdgeCurMut<'_>> {
+        self.as_raw()
+            .get_in_edge_iterator_by_euid(&euid.as_raw(), false)
+            .map(InEdgeCurMut::new)
+    }
+
+    fn edges_between_mut(&self, src: i64, dst: i64, label: &str) -> Result<OutEdgeCurMut<'_>> {
+        let label_id = self.edge_label_id(label)?;
+        self.edges_between_mut_by_id(src, dst, label_id)
+    }
+
+    fn edges_between_mut_by_id(
+        &self,
+        src: i64,
+        dst: i64,
+        label_id: usize,
+    ) -> Result<OutEdgeCurMut<'_>> {
+        self.as_raw()
+            .get_out_edge_iterator_by_src_dst_lid(src, dst, edge_lid(label_id)?)
+            .map(OutEdgeCurMut::new)
+    }
+
+    fn in_edges_between_mut(&self, src: i64, dst: i64, label: &str) -> Result<InEdgeCurMut<'_>> {
+        let label_id = self.edge_label_id(label)?;
+        self.in_edges_between_mut_by_id(src, dst, label_id)
+    }
+
+    fn in_edges_between_mut_by_id(
+        &self,
+        src: i64,
+        dst: i64,
+        label_id: usize,
+    ) -> Result<InEdgeCurMut<'_>> {
+        self.as_raw()
+            .get_in_edge_iterator_by_src_dst_lid(src, dst, edge_lid(label_id)?)
+            .map(InEdgeCurMut::new)
+    }
+
     fn add_vertex<'a, V>(
         &mut self,
         label: &str,
@@ -1115,6 +1578,18 @@ impl<'g> TxnWrite for RwTxn<'g> {
         self.as_raw()
             .add_vertex_by_data(label, field_names.iter().copied(), &raw_field_values)
     }
+    fn add_vertex_str(
+        &mut self,
+        label: &str,
+        field_names: &[&str],
+        field_value_strings: &[&str],
+    ) -> Result<i64> {
+        self.as_raw().add_vertex_by_value_strings(
+            label,
+            field_names.iter().copied(),
+            field_value_strings.iter().copied(),
+        )
+    }
     fn add_vertex_by_id<'a, 'b, V>(
         &mut self,
         label_id: usize,
@@ -1157,6 +1632,52 @@ impl<'g> TxnWrite for RwTxn<'g> {
             )
             .map(|raw| EdgeUid::from_raw(&raw))
     }
+    fn upsert_vertex<'a, V>(
+        &mut self,
+        label: &str,
+        field_names: &[&str],
+        field_values: V,
+    ) -> Result<(bool, i64)>
+    where
+        V: IntoIterator<Item = &'a FieldData>,
+    {
+        let field_values: Vec<_> = field_values.into_iter().cloned().collect();
+        let primary_field = self.get_vertex_primary_field(label)?;
+        let primary_value = field_names
+            .iter()
+            .position(|name| *name == primary_field)
+            .filter(|_| field_names.len() == field_values.len())
+            .map(|pos| &field_values[pos])
+            .ok_or_else(Error::invalid_parameter)?;
+        {
+            let vcur = self.unique_index_vertex_cur_mut(label, &primary_field, primary_value)?;
+            if vcur.is_valid() {
+                vcur.set_fields(field_names, &field_values)?;
+                return vcur.id().map(|vid| (false, vid));
+            }
+        }
+        self.add_vertex(label, field_names, &field_values)
+            .map(|vid| (true, vid))
+    }
+
+    fn add_edge_str(
+        &mut self,
+        src: i64,
+        dst: i64,
+        label: &str,
+        field_names: &[&str],
+        field_value_strings: &[&str],
+    ) -> Result<EdgeUid> {
+        self.as_raw()
+            .add_edge_by_value_strings(
+                src,
+                dst,
+                label,
+                field_names.iter().copied(),
+                field_value_strings.iter().copied(),
+            )
+            .map(|raw| EdgeUid::from_raw(&raw))
+    }
     fn add_edge_by_id<'a, V>(
         &mut self,
         src: i64,
@@ -1201,6 +1722,22 @@ impl<'g> TxnWrite for RwTxn<'g> {
         )
     }
 
+    fn upsert_edge_str(
+        &mut self,
+        src: i64,
+        dst: i64,
+        label: &str,
+        field_names: &[&str],
+        field_value_strings: &[&str],
+    ) -> Result<bool> {
+        self.as_raw().upsert_edge_by_value_strings(
+            src,
+            dst,
+            label,
+            field_names.iter().copied(),
+            field_value_strings.iter().copied(),
+        )
+    }
     fn upsert_edge_by_id<'a, V>(
         &mut self,
         src: i64,
@@ -1219,19 +1756,121 @@ impl<'g> TxnWrite for RwTxn<'g> {
         self.as_raw()
             .upsert_edge_by_id(src, dst, label_id, field_ids, &raw_field_values)
     }
+
+    fn add_vertices<'a, R, V>(
+        &mut self,
+        label: &str,
+        field_names: &[&str],
+        rows: R,
+    ) -> Result<BatchReport<i64>>
+    where
+        R: IntoIterator<Item = V>,
+        V: IntoIterator<Item = &'a FieldData>,
+    {
+        let label_id = self.vertex_label_id(label)?;
+        let field_ids = self.vertex_fields_ids(label_id, field_names.iter().copied())?;
+        let mut report = BatchReport::default();
+        for (pos, field_values) in rows.into_iter().enumerate() {
+            let res = self.add_vertex_by_id(label_id, &field_ids, field_values);
+            report.push(pos, res);
+        }
+        Ok(report)
+    }
+
+    fn add_edges<'a, R, V>(
+        &mut self,
+        label: &str,
+        field_names: &[&str],
+        rows: R,
+    ) -> Result<BatchReport<EdgeUid>>
+    where
+        R: IntoIterator<Item = (i64, i64, V)>,
+        V: IntoIterator<Item = &'a FieldData>,
+    {
+        let label_id = self.edge_label_id(label)?;
+        let field_ids = self.edge_fields_ids(label_id, field_names.iter().copied())?;
+        let mut report = BatchReport::default();
+        for (pos, (src, dst, field_values)) in rows.into_iter().enumerate() {
+            let res = self.add_edge_by_id(src, dst, label_id, &field_ids, field_values);
+            report.push(pos, res);
+        }
+        Ok(report)
+    }
 }
 
+/// A read-write transaction.
+///
+/// A `RwTxn` should be finished explicitly by [`RwTxn::commit`] or [`RwTxn::abort`].
+/// Dropping an unfinished one rolls back all its changes, which is easy to miss when
+/// returning early with `?`. So in debug builds, dropping an unfinished `RwTxn` is
+/// reported according to its [`UnfinishedDrop`] behavior.
+#[must_use = "write transaction should be committed or aborted"]
 pub struct RwTxn<'g> {
     inner: RawTransaction,
+    // declared after `inner` so that the transaction is aborted before
+    // the guard panics
+    guard: FinishGuard,
+    serial: u64,
     // the underlying ffi transaction of `RawTransaction` has a reference
     // to ffi graph db
     _graph: PhantomData<&'g ()>,
 }
 
+// Serial numbers identify transactions of this process, they are never
+// reused, unlike the addresses of ffi transactions.
+static NEXT_SERIAL: AtomicU64 = AtomicU64::new(1);
+
+fn next_serial() -> u64 {
+    NEXT_SERIAL.fetch_add(1, Ordering::Relaxed)
+}
+
+/// `UnfinishedDrop` decides what happens in debug builds when a [`RwTxn`] is dropped
+/// without being committed or aborted.
+///
+/// In release builds, an unfinished `RwTxn` is always aborted silently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UnfinishedDrop {
+    /// Abort silently.
+    Ignore,
+    /// Abort and print a warning to stderr. This is the default.
+    #[default]
+    Warn,
+    /// Abort and panic, unless the thread is already panicking.
+    Panic,
+}
+
+#[derive(Debug)]
+struct FinishGuard {
+    finished: bool,
+    on_unfinished_drop: UnfinishedDrop,
+}
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        if !cfg!(debug_assertions) || self.finished || thread::panicking() {
+            return;
+        }
+        match self.on_unfinished_drop {
+            UnfinishedDrop::Ignore => {}
+            UnfinishedDrop::Warn => {
+                eprintln!("warning: write transaction dropped without commit or abort")
+            }
+            UnfinishedDrop::Panic => {
+                panic!("write transaction dropped without commit or abort")
+            }
+        }
+    }
+}
+
 impl<'g> RwTxn<'g> {
     pub(crate) fn from_raw(raw: RawTransaction) -> Self {
         RwTxn {
             inner: raw,
+            guard: FinishGuard {
+                finished: false,
+                on_unfinished_drop: UnfinishedDrop::default(),
+            },
+            serial: next_serial(),
             _graph: PhantomData,
         }
     }
@@ -1241,6 +1880,10 @@ impl<'g> AsRawTransaction for RwTxn<'g> {
     fn as_raw(&self) -> &RawTransaction {
         &self.inner
     }
+
+    fn serial(&self) -> u64 {
+        self.serial
+    }
 }
 
 impl<'g> Debug for RwTxn<'g> {
@@ -1258,6 +1901,195 @@ impl<'g> RwTxn<'g> {
     ///
     /// [`ErrorKind::TxnConflict`]: crate::ErrorKind::TxnConflict
     pub fn commit(self) -> Result<()> {
-        self.inner.commit()
+        let RwTxn {
+            inner, mut guard, ..
+        } = self;
+        guard.finished = true;
+        inner.commit()
+    }
+
+    /// Abort transaction, discarding all changes made in it.
+    ///
+    /// # Errors
+    /// If the transaction is no longer valid, e.g. it has been invalidated by TuGraph after
+    /// an internal failure, a [`ErrorKind::InvalidTxn`] will be returned. The changes are
+    /// discarded in that case as well.
+    ///
+    /// [`ErrorKind::InvalidTxn`]: crate::ErrorKind::InvalidTxn
+    ///
+    /// # Examples
+    /// ```
+    /// use tugraph::{db::OpenOptions, Error};
+    ///
+    /// let galaxy = OpenOptions::new()
+    ///     .create(true)
+    ///     .open("/tmp/rust_tugraph/doc/abort", "admin", "73@TuGraph")?;
+    /// let graph = galaxy.open_graph("default", false)?;
+    /// let rw_txn = graph.create_rw_txn(false)?;
+    /// // do some update work in rw_txn
+    /// // ...
+    /// rw_txn.abort()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn abort(self) -> Result<()> {
+        let RwTxn {
+            inner, mut guard, ..
+        } = self;
+        guard.finished = true;
+        inner.abort()
+    }
+
+    /// Set what happens in debug builds if this transaction is dropped without
+    /// being committed or aborted.
+    ///
+    /// See [`UnfinishedDrop`] for more details.
+    pub fn set_unfinished_drop(&mut self, on_unfinished_drop: UnfinishedDrop) {
+        self.guard.on_unfinished_drop = on_unfinished_drop;
+    }
+}
+
+/// `RetryPolicy` configures how [`Graph::write_with`] retries a write closure whose
+/// transaction failed with [`ErrorKind::TxnConflict`].
+///
+/// The delay before the n-th retry is `backoff * 2^(n-1)`, capped at `max_backoff`.
+/// If jitter is enabled, the real delay is picked uniformly from `[0, delay]`, which
+/// keeps conflicting writers from retrying in lockstep.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use tugraph::txn::RetryPolicy;
+///
+/// let policy = RetryPolicy::new()
+///     .max_attempts(10)
+///     .backoff(Duration::from_millis(2))
+///     .max_backoff(Duration::from_millis(200))
+///     .jitter(true);
+/// ```
+///
+/// [`Graph::write_with`]: crate::db::Graph::write_with
+/// [`ErrorKind::TxnConflict`]: crate::ErrorKind::TxnConflict
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new()
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy which makes at most 5 attempts, starting with a 1ms backoff capped
+    /// at 100ms, with jitter enabled.
+    pub fn new() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(100),
+            jitter: true,
+        }
+    }
+
+    /// Creates a policy which never retries.
+    pub fn no_retry() -> Self {
+        RetryPolicy::new().max_attempts(1)
+    }
+
+    /// Sets the maximum number of attempts, including the first one.
+    ///
+    /// A value of 0 is treated as 1.
+    pub fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = cmp::max(max_attempts, 1);
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets the upper bound of the delay between two attempts.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Sets whether the delay between two attempts is randomized.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Get the maximum number of attempts.
+    pub fn get_max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    /// Get the delay to sleep before the attempt numbered `attempt + 1`.
+    pub(crate) fn delay(&self, attempt: usize) -> Duration {
+        let shift = cmp::min(attempt.saturating_sub(1), 31) as u32;
+        let delay = cmp::min(self.backoff.saturating_mul(1u32 << shift), self.max_backoff);
+        if self.jitter {
+            let nanos = delay.as_nanos().min(u64::MAX as u128) as u64;
+            Duration::from_nanos(random_u64() % nanos.saturating_add(1))
+        } else {
+            delay
+        }
+    }
+}
+
+fn random_u64() -> u64 {
+    // each RandomState is seeded with fresh random keys
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    hasher.finish()
+}
+
+/// `Committed` is the result of a write closure run by [`Graph::write`] whose
+/// transaction has been committed.
+///
+/// [`Graph::write`]: crate::db::Graph::write
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Committed<T> {
+    /// The value returned by the closure in the committed attempt.
+    pub value: T,
+    /// How many attempts were made, including the committed one.
+    pub attempts: usize,
+}
+
+impl<T> Committed<T> {
+    /// Unwraps the value returned by the closure.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy_delay() {
+        let policy = RetryPolicy::new()
+            .backoff(Duration::from_millis(1))
+            .max_backoff(Duration::from_millis(5))
+            .jitter(false);
+        assert_eq!(policy.delay(1), Duration::from_millis(1));
+        assert_eq!(policy.delay(2), Duration::from_millis(2));
+        assert_eq!(policy.delay(3), Duration::from_millis(4));
+        assert_eq!(policy.delay(4), Duration::from_millis(5));
+        assert_eq!(policy.delay(100), Duration::from_millis(5));
+
+        let policy = policy.jitter(true);
+        (1..10).for_each(|attempt| assert!(policy.delay(attempt) <= Duration::from_millis(5)));
+
+        assert_eq!(RetryPolicy::new().max_attempts(0).get_max_attempts(), 1);
+        assert_eq!(RetryPolicy::no_retry().get_max_attempts(), 1);
     }
 }
@@ -26,6 +26,7 @@ use crate::{
 
 /// `EdgeUid` is the primary key, or key for short, of Edge element.
 #[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EdgeUid {
     /// The key of source vertex.
     pub src: i64,
@@ -77,6 +78,7 @@ impl Display for EdgeUid {
 
 /// `AccessLevel` is a type that represents all possible priorities of database.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AccessLevel {
     /// Neither can read nor write
     #[default]
@@ -97,7 +99,7 @@ impl TryFrom<u32> for AccessLevel {
             ffi::lgraph_api_access_level_read => Ok(Self::Read),
             ffi::lgraph_api_access_level_write => Ok(Self::Write),
             ffi::lgraph_api_access_level_full => Ok(Self::Full),
-            _ => Err(crate::Error::new("Invalid parameter.".to_string())),
+            _ => Err(crate::Error::invalid_parameter()),
         }
     }
 }
@@ -111,7 +113,14 @@ impl Display for AccessLevel {
 /// ISO 8601 calendar date without timezone.
 ///
 /// See the [`crate::field::FieldData`] for details
+///
+/// With the `serde` feature, it is serialized as an ISO 8601 string, e.g. `"2016-07-08"`.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 #[repr(transparent)]
 pub struct Date {
     inner: chrono::NaiveDate,
@@ -159,7 +168,14 @@ impl Display for Date {
 }
 
 /// ISO 8601 combined date and time without timezone.
+///
+/// With the `serde` feature, it is serialized as an ISO 8601 string, e.g. `"2016-07-08T09:10:11"`.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 #[repr(transparent)]
 pub struct DateTime {
     inner: chrono::NaiveDateTime,
@@ -298,4 +314,32 @@ mod tests {
         let raw_datetime = datetime.as_raw_datetime();
         assert_eq!(raw_datetime.seconds_since_epoch(), datetime.timestamp());
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_json::json;
+
+        let euid = EdgeUid {
+            src: 1,
+            lid: 2,
+            tid: 3,
+            dst: 4,
+            eid: 5,
+        };
+        let value = json!({"src": 1, "lid": 2, "tid": 3, "dst": 4, "eid": 5});
+        assert_eq!(serde_json::to_value(euid).unwrap(), value);
+        assert_eq!(serde_json::from_value::<EdgeUid>(value).unwrap(), euid);
+
+        assert_eq!(
+            serde_json::to_value(AccessLevel::Read).unwrap(),
+            json!("Read")
+        );
+
+        let date: Date = serde_json::from_value(json!("2016-07-08")).unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2016, 7, 8));
+        let datetime: DateTime = serde_json::from_value(json!("2016-07-08T09:10:11")).unwrap();
+        assert_eq!(datetime.timestamp(), 1_467_969_011);
+        assert!(serde_json::from_value::<Date>(json!("08/07/2016")).is_err());
+    }
 }
@@ -17,6 +17,7 @@ use std::{collections::HashSet, fmt::Display};
 use crate::raw::RawUserInfo;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserInfo {
     pub desc: String,
     pub roles: HashSet<String>,
@@ -0,0 +1,766 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tugraph::{
+    algo::{
+        self,
+        similarity::{Measure, PairScores, Similarity},
+        Betweenness, Closeness, Cohesion, Connectivity, Degree, LabelPropagation, Louvain,
+        NeighborSampler, PageRank, Projection, ProjectionOptions, RandomWalk, ShortestPath,
+        WriteBack,
+    },
+    cursor::{EdgeCursor, VertexCur, VertexCursor},
+    db::Graph,
+    field::{FieldData, FieldSpec, FieldType},
+    parallel::Parallel,
+    traverse::Direction,
+    txn::{TxnRead, TxnWrite},
+};
+
+mod common;
+
+// Create `num_vertices` vertices of label "V", and edges of label "E" weighted by "weight"
+// from `edges` of `(src, dst, weight)` indices. Returns the vids.
+fn create_graph(graph: &Graph, num_vertices: usize, edges: &[(usize, usize, f64)]) -> Vec<i64> {
+    graph
+        .add_vertex_label(
+            "V",
+            &[FieldSpec {
+                name: "id".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "id",
+        )
+        .unwrap();
+    for label in ["E", "Other"] {
+        graph
+            .add_edge_label(
+                label,
+                &[FieldSpec {
+                    name: "weight".into(),
+                    ty: FieldType::Double,
+                    optional: false,
+                }],
+                "",
+                [("V", "V")],
+            )
+            .unwrap();
+    }
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    let vids: Vec<_> = (0..num_vertices)
+        .map(|i| {
+            rw_txn
+                .add_vertex("V", &["id"], &[FieldData::Int64(i as i64)])
+                .unwrap()
+        })
+        .collect();
+    for &(src, dst, weight) in edges {
+        rw_txn
+            .add_edge(
+                vids[src],
+                vids[dst],
+                "E",
+                &["weight"],
+                &[FieldData::Double(weight)],
+            )
+            .unwrap();
+    }
+    rw_txn.commit().unwrap();
+    vids
+}
+
+#[test]
+fn test_pagerank() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    // 0 <-> 1 -> 2, 3 -> 2 with weight 3
+    let vids = create_graph(
+        &graph,
+        4,
+        &[(0, 1, 1.0), (1, 0, 1.0), (1, 2, 1.0), (3, 2, 3.0)],
+    );
+    let ro_txn = graph.create_ro_txn().unwrap();
+
+    let scores = algo::pagerank(&ro_txn).unwrap();
+    assert_eq!(scores.len(), 4);
+    assert!((scores.values().iter().sum::<f64>() - 1.0).abs() < 1e-6);
+    let scores = scores.into_hash_map();
+    assert!(scores[&vids[2]] > scores[&vids[3]]);
+
+    let weighted = PageRank::new()
+        .edge_labels(["E"])
+        .weight_field("weight")
+        .run(&ro_txn)
+        .unwrap();
+    assert!((weighted.values().iter().sum::<f64>() - 1.0).abs() < 1e-6);
+    let projection = Projection::build(
+        &ro_txn,
+        &ProjectionOptions::new()
+            .edge_labels(["E"])
+            .weight_field("weight"),
+    )
+    .unwrap();
+    assert_eq!(PageRank::new().run_on(&projection).unwrap(), weighted);
+
+    let personalized = PageRank::new()
+        .run_personalized(&ro_txn, [vids[3]])
+        .unwrap();
+    assert_eq!(personalized.get(vids[0]), Some(&0.0));
+    assert!(personalized.get(vids[3]).unwrap() > &0.15);
+    let dense = personalized.into_dense(0.0);
+    assert_eq!(dense.len(), *vids.last().unwrap() as usize + 1);
+
+    assert!(PageRank::new()
+        .run_personalized(&ro_txn, [i64::MAX])
+        .is_err());
+    assert!(PageRank::new().damping(2.0).run(&ro_txn).is_err());
+    assert!(PageRank::new()
+        .edge_labels(["NoSuchLabel"])
+        .run(&ro_txn)
+        .is_err());
+    assert!(PageRank::new().weight_field("id").run(&ro_txn).is_err());
+}
+
+#[test]
+fn test_components() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    // 0 -> 1 -> 2 -> 0, 2 -> 3, 4 -> 5
+    let vids = create_graph(
+        &graph,
+        6,
+        &[
+            (0, 1, 1.0),
+            (1, 2, 1.0),
+            (2, 0, 1.0),
+            (2, 3, 1.0),
+            (4, 5, 1.0),
+        ],
+    );
+    // 3 -> 4 of the other label
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    rw_txn
+        .add_edge(
+            vids[3],
+            vids[4],
+            "Other",
+            &["weight"],
+            &[FieldData::Double(1.0)],
+        )
+        .unwrap();
+    rw_txn.commit().unwrap();
+    let ro_txn = graph.create_ro_txn().unwrap();
+
+    let wcc = algo::wcc(&ro_txn).unwrap();
+    assert_eq!(wcc.num_components(), 1);
+    let wcc = Connectivity::new().edge_labels(["E"]).wcc(&ro_txn).unwrap();
+    assert_eq!(wcc.num_components(), 2);
+    assert_eq!(wcc.sizes(), [4, 2]);
+    assert_eq!(wcc.largest(), Some((0, 4)));
+    assert_eq!(wcc.component(vids[5]), Some(1));
+    assert_eq!(wcc.members(1), [vids[4], vids[5]]);
+
+    let scc = algo::scc(&ro_txn).unwrap();
+    assert_eq!(scc.num_components(), 4);
+    assert_eq!(scc.sizes(), [3, 1, 1, 1]);
+    assert_eq!(scc.component(vids[2]), scc.component(vids[0]));
+
+    let order = algo::toposort(&ro_txn).unwrap();
+    assert!(!order.is_dag());
+    assert!(order.order.is_empty());
+    assert_eq!(order.cycles, [vec![vids[0], vids[1], vids[2]]]);
+
+    let order = Connectivity::new()
+        .vertex_labels(["V"])
+        .edge_labels(["E", "Other"])
+        .toposort(&ro_txn)
+        .unwrap();
+    assert_eq!(order.cycles.len(), 1);
+    let order = Connectivity::new()
+        .edge_labels(["Other"])
+        .toposort(&ro_txn)
+        .unwrap();
+    assert!(order.is_dag());
+    assert_eq!(order.order.len(), 6);
+    let pos = |vid| order.order.iter().position(|&v| v == vid).unwrap();
+    assert!(pos(vids[3]) < pos(vids[4]));
+
+    assert!(Connectivity::new()
+        .vertex_labels(["NoSuchLabel"])
+        .wcc(&ro_txn)
+        .is_err());
+}
+
+#[test]
+fn test_shortest_path() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    // 0 -> 1 -> 2 -> 3, 0 -> 3 with weight 5, 4 -> 3
+    let vids = create_graph(
+        &graph,
+        5,
+        &[
+            (0, 1, 1.0),
+            (1, 2, 1.0),
+            (2, 3, 1.0),
+            (0, 3, 5.0),
+            (4, 3, 1.0),
+        ],
+    );
+    let ro_txn = graph.create_ro_txn().unwrap();
+
+    let path = algo::shortest_path(&ro_txn, vids[0], vids[3])
+        .unwrap()
+        .unwrap();
+    assert_eq!(path.vids, [vids[0], vids[3]]);
+    assert_eq!((path.edges[0].src, path.edges[0].dst), (vids[0], vids[3]));
+    assert!(algo::shortest_path(&ro_txn, vids[3], vids[0])
+        .unwrap()
+        .is_none());
+
+    let shortest = ShortestPath::new().direction(Direction::Both);
+    let path = shortest.bfs(&ro_txn, vids[4], vids[0]).unwrap().unwrap();
+    assert_eq!(path.vids, [vids[4], vids[3], vids[0]]);
+    assert!(path.to_string().contains("<-"));
+    assert!(shortest
+        .clone()
+        .max_depth(1)
+        .bidirectional_bfs(&ro_txn, vids[4], vids[0])
+        .unwrap()
+        .is_none());
+    assert!(ShortestPath::new()
+        .edge_labels(["Other"])
+        .bfs(&ro_txn, vids[0], vids[3])
+        .unwrap()
+        .is_none());
+
+    let weighted = ShortestPath::new().weight_field("weight");
+    let path = weighted
+        .dijkstra(&ro_txn, vids[0], vids[3])
+        .unwrap()
+        .unwrap();
+    assert_eq!(path.vids, [vids[0], vids[1], vids[2], vids[3]]);
+    assert_eq!(path.weight, 3.0);
+    let paths = weighted.k_shortest(&ro_txn, vids[0], vids[3], 3).unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[1].weight, 5.0);
+
+    assert!(ShortestPath::new()
+        .edge_labels(["NoSuchLabel"])
+        .bfs(&ro_txn, vids[0], vids[3])
+        .is_err());
+    assert!(ShortestPath::new()
+        .weight_field("id")
+        .dijkstra(&ro_txn, vids[0], vids[3])
+        .is_err());
+}
+
+#[test]
+fn test_communities() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    // triangles {0, 1, 2} and {3, 4, 5} bridged by 2 -> 3 of weight 0.1
+    let vids = create_graph(
+        &graph,
+        6,
+        &[
+            (0, 1, 1.0),
+            (1, 2, 1.0),
+            (2, 0, 1.0),
+            (3, 4, 1.0),
+            (4, 5, 1.0),
+            (5, 3, 1.0),
+            (2, 3, 0.1),
+        ],
+    );
+    let ro_txn = graph.create_ro_txn().unwrap();
+
+    let communities = algo::louvain(&ro_txn).unwrap();
+    assert_eq!(communities.assignments.num_components(), 2);
+    assert_eq!(communities.assignments.members(0), vids[..3]);
+    assert!((communities.modularity - 5.0 / 14.0).abs() < 1e-9);
+
+    let weighted = Louvain::new()
+        .edge_labels(["E"])
+        .weight_field("weight")
+        .run(&ro_txn)
+        .unwrap();
+    assert_eq!(weighted.assignments.sizes(), [3, 3]);
+    assert!(weighted.modularity > communities.modularity);
+
+    let communities = LabelPropagation::new()
+        .weight_field("weight")
+        .run(&ro_txn)
+        .unwrap();
+    assert_eq!(communities.assignments.sizes(), [3, 3]);
+    assert_eq!(communities.assignments.component(vids[4]), Some(1));
+    assert!(communities.modularity > 0.0);
+
+    assert!(Louvain::new().resolution(0.0).run(&ro_txn).is_err());
+    assert!(LabelPropagation::new()
+        .weight_field("id")
+        .run(&ro_txn)
+        .is_err());
+}
+
+#[test]
+fn test_centrality() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    // 0 -> 1, 0 -> 2, 0 -> 3, 3 -> 4
+    let vids = create_graph(
+        &graph,
+        5,
+        &[(0, 1, 1.0), (0, 2, 1.0), (0, 3, 1.0), (3, 4, 1.0)],
+    );
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let parallel = Parallel::new(&graph, &ro_txn).num_threads(2);
+
+    let degrees = algo::degree_centrality(&ro_txn).unwrap();
+    assert_eq!(degrees.get(vids[0]), Some(&3.0));
+    assert_eq!(degrees.get(vids[3]), Some(&2.0));
+    let top = Degree::new()
+        .direction(Direction::In)
+        .normalized(true)
+        .top_k(&ro_txn, 2)
+        .unwrap();
+    assert_eq!(top, [(vids[1], 0.25), (vids[2], 0.25)]);
+
+    let closeness = algo::closeness_centrality(&ro_txn).unwrap();
+    assert_eq!(closeness.top_k(1)[0].0, vids[0]);
+    assert_eq!(Closeness::new().par_run(&parallel).unwrap(), closeness);
+    let top = Closeness::new().par_top_k(&parallel, 2).unwrap();
+    assert_eq!(top, closeness.top_k(2));
+    let sampled = Closeness::new().pivots(5).run(&ro_txn).unwrap();
+    assert_eq!(sampled.len(), 5);
+
+    let betweenness = algo::betweenness_centrality(&ro_txn).unwrap();
+    assert_eq!(betweenness.get(vids[3]), Some(&1.0));
+    assert_eq!(betweenness.get(vids[0]), Some(&0.0));
+    let undirected = Betweenness::new()
+        .direction(Direction::Both)
+        .par_run(&parallel)
+        .unwrap();
+    assert_eq!(undirected.top_k(1), [(vids[0], 5.0)]);
+    let sampled = Betweenness::new()
+        .pivots(2)
+        .seed(42)
+        .par_run(&parallel)
+        .unwrap();
+    assert_eq!(sampled.len(), 5);
+
+    assert!(Betweenness::new().pivots(0).run(&ro_txn).is_err());
+    assert!(Closeness::new()
+        .edge_labels(["NoSuchLabel"])
+        .run(&ro_txn)
+        .is_err());
+}
+
+#[test]
+fn test_cohesion() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    // a triangle {0, 1, 2} with both directions of 0 - 1, and 3 hanging off 2
+    let vids = create_graph(
+        &graph,
+        4,
+        &[
+            (0, 1, 1.0),
+            (1, 0, 1.0),
+            (1, 2, 1.0),
+            (2, 0, 1.0),
+            (2, 3, 1.0),
+        ],
+    );
+    let ro_txn = graph.create_ro_txn().unwrap();
+
+    let triangles = algo::triangle_count(&ro_txn).unwrap();
+    assert_eq!(triangles.total, 1);
+    assert_eq!(triangles.per_vertex.values(), [1, 1, 1, 0]);
+
+    let coefficients = algo::clustering_coefficient(&ro_txn).unwrap();
+    assert_eq!(coefficients.get(vids[0]), Some(&1.0));
+    assert_eq!(coefficients.get(vids[2]), Some(&(1.0 / 3.0)));
+    assert_eq!(coefficients.get(vids[3]), Some(&0.0));
+
+    let cores = algo::core_numbers(&ro_txn).unwrap();
+    assert_eq!(cores.values(), [2, 2, 2, 1]);
+    let cohesion = Cohesion::new().vertex_labels(["V"]);
+    assert_eq!(cohesion.k_core(&ro_txn, 2).unwrap(), vids[..3]);
+    assert!(cohesion.k_core(&ro_txn, 3).unwrap().is_empty());
+
+    assert!(Cohesion::new()
+        .vertex_labels(["NoSuchLabel"])
+        .triangles(&ro_txn)
+        .is_err());
+}
+
+#[test]
+fn test_similarity() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    // 0 -> 1, 0 -> 2, 3 -> 1, 3 -> 2, 4 -> 2
+    let vids = create_graph(
+        &graph,
+        5,
+        &[
+            (0, 1, 1.0),
+            (0, 2, 1.0),
+            (3, 1, 1.0),
+            (3, 2, 1.0),
+            (4, 2, 1.0),
+        ],
+    );
+    let ro_txn = graph.create_ro_txn().unwrap();
+
+    let similarity = Similarity::new();
+    let scores = similarity.pair(&ro_txn, vids[0], vids[3]).unwrap();
+    assert_eq!(scores.get(Measure::CommonNeighbors), 2.0);
+    assert_eq!(scores.get(Measure::Jaccard), 1.0);
+    let adamic_adar = 1.0 / 2f64.ln() + 1.0 / 3f64.ln();
+    assert!((scores.adamic_adar - adamic_adar).abs() < 1e-12);
+    assert!((scores.resource_allocation - (0.5 + 1.0 / 3.0)).abs() < 1e-12);
+    assert_eq!(scores.preferential_attachment, 4.0);
+    assert_eq!(
+        similarity.pair(&ro_txn, vids[0], 1 << 40).unwrap(),
+        PairScores::default()
+    );
+
+    assert_eq!(
+        similarity
+            .top_k(&ro_txn, vids[0], Measure::CommonNeighbors, 5)
+            .unwrap(),
+        [(vids[3], 2.0), (vids[4], 1.0)]
+    );
+    assert_eq!(
+        similarity
+            .top_k(&ro_txn, vids[0], Measure::Jaccard, 1)
+            .unwrap(),
+        [(vids[3], 1.0)]
+    );
+    // 0 and 3 share out neighbors 1 and 2, and 1 and 3 share in neighbors 0 and 3,
+    // scored the same as by `pair`
+    for (direction, vid, expected) in [
+        (Direction::Out, 0, vec![(3, 2.0), (4, 1.0)]),
+        (Direction::In, 1, vec![(2, 2.0)]),
+    ] {
+        let directed = Similarity::new().direction(direction);
+        let top = directed
+            .top_k(&ro_txn, vids[vid], Measure::CommonNeighbors, 5)
+            .unwrap();
+        assert_eq!(
+            top,
+            expected
+                .into_iter()
+                .map(|(i, score)| (vids[i], score))
+                .collect::<Vec<_>>()
+        );
+        for measure in [
+            Measure::Jaccard,
+            Measure::AdamicAdar,
+            Measure::ResourceAllocation,
+            Measure::PreferentialAttachment,
+        ] {
+            for (c, score) in directed.top_k(&ro_txn, vids[vid], measure, 5).unwrap() {
+                let pair = directed.pair(&ro_txn, vids[vid], c).unwrap();
+                assert_eq!(score, pair.get(measure));
+            }
+        }
+    }
+    // only the first out edges of 0 and 3 are read
+    let limited = Similarity::new().max_neighbors(1);
+    assert_eq!(
+        limited
+            .pair(&ro_txn, vids[0], vids[3])
+            .unwrap()
+            .common_neighbors,
+        1.0
+    );
+}
+
+#[test]
+fn test_write_back() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    let vids = create_graph(&graph, 5, &[(0, 1, 1.0), (1, 2, 1.0), (3, 4, 1.0)]);
+    let components = graph.read(|txn| algo::wcc(txn)).unwrap();
+
+    let mut progress = vec![];
+    let report = WriteBack::new("V", "component", FieldType::Int64)
+        .batch_size(2)
+        .run_with_progress(
+            &graph,
+            components
+                .ids()
+                .iter()
+                .map(|(vid, &id)| (vid, id as i64))
+                .chain([(1 << 40, 0)]),
+            |p| progress.push((p.batches, p.written, p.failed)),
+        )
+        .unwrap();
+    assert_eq!(report.succeeded.len(), 5);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, 5);
+    assert_eq!(progress, [(1, 2, 0), (2, 4, 0), (3, 5, 1)]);
+
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let spec = ro_txn
+        .vertex_schema("V")
+        .unwrap()
+        .into_iter()
+        .find(|spec| spec.name == "component")
+        .unwrap();
+    assert!(spec.optional);
+    let mut vcur = ro_txn.vertex_cur().unwrap();
+    let read = |vcur: &mut VertexCur<'_>, vid| vcur.seek(vid, false).unwrap().field("component");
+    assert_eq!(read(&mut vcur, vids[2]).unwrap(), FieldData::Int64(0));
+    assert_eq!(read(&mut vcur, vids[4]).unwrap(), FieldData::Int64(1));
+    drop(vcur);
+    drop(ro_txn);
+
+    // an existing field is reused, but not with another type
+    let report = algo::write_back(
+        &graph,
+        "V",
+        "component",
+        FieldType::Int64,
+        [(vids[0], 7i64)],
+    )
+    .unwrap();
+    assert!(report.is_all_succeeded());
+    assert!(algo::write_back(
+        &graph,
+        "V",
+        "component",
+        FieldType::Double,
+        [(vids[0], 7.0)]
+    )
+    .is_err());
+}
+
+#[test]
+fn test_random_walk() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    // a cycle 0 -> 1 -> 2 -> 0, and 2 -> 3
+    let vids = create_graph(
+        &graph,
+        4,
+        &[(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0), (2, 3, 1.0)],
+    );
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let edges = [(0, 1), (1, 2), (2, 0), (2, 3)].map(|(src, dst)| (vids[src], vids[dst]));
+
+    for walk in [RandomWalk::new(), RandomWalk::new().p(0.25).q(4.0)] {
+        let walk = walk
+            .walk_length(5)
+            .walks_per_node(3)
+            .edge_labels(["E"])
+            .seed(7);
+        let walks: Vec<_> = walk.walks(&ro_txn).unwrap().map(Result::unwrap).collect();
+        assert_eq!(walks.len(), 12);
+        for (i, w) in walks.iter().enumerate() {
+            assert_eq!(w[0], vids[i % 4]);
+            assert!(w.len() <= 5);
+            assert!(w.windows(2).all(|e| edges.contains(&(e[0], e[1]))));
+            // only 3 is a dead end
+            assert!(w.len() == 5 || w.last() == Some(&vids[3]));
+        }
+        let again: Vec<_> = walk.walks(&ro_txn).unwrap().map(Result::unwrap).collect();
+        assert_eq!(walks, again);
+
+        let mut out = vec![];
+        assert_eq!(walk.write(&ro_txn, &mut out).unwrap(), 12);
+        let lines: Vec<_> = walks
+            .iter()
+            .map(|w| {
+                let vids: Vec<_> = w.iter().map(|vid| vid.to_string()).collect();
+                vids.join(" ")
+            })
+            .collect();
+        assert_eq!(String::from_utf8(out).unwrap(), lines.join("\n") + "\n");
+
+        let mut out = vec![];
+        let parallel = Parallel::new(&graph, &ro_txn).num_threads(2);
+        assert_eq!(walk.par_write(&parallel, &mut out).unwrap(), 12);
+        let mut par_lines: Vec<_> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        let mut lines = lines;
+        par_lines.sort();
+        lines.sort();
+        assert_eq!(par_lines, lines);
+    }
+
+    assert!(RandomWalk::new().p(0.0).walks(&ro_txn).is_err());
+    assert!(RandomWalk::new().walk_length(0).walks(&ro_txn).is_err());
+    assert!(RandomWalk::new()
+        .edge_labels(["NoSuchLabel"])
+        .write(&ro_txn, vec![])
+        .is_err());
+    // no edges of "Other" to follow
+    assert!(RandomWalk::new()
+        .edge_labels(["Other"])
+        .walks(&ro_txn)
+        .unwrap()
+        .all(|w| w.unwrap().len() == 1));
+}
+
+#[test]
+fn test_neighbor_sampler() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    // 0 -> 1..=5, and 1 -> 6, 2 -> 6
+    let vids = create_graph(
+        &graph,
+        7,
+        &[
+            (0, 1, 1.0),
+            (0, 2, 1.0),
+            (0, 3, 1.0),
+            (0, 4, 1.0),
+            (0, 5, 1.0),
+            (1, 6, 1.0),
+            (2, 6, 1.0),
+        ],
+    );
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let index = |vid| vids.iter().position(|&v| v == vid).unwrap();
+
+    let sampler = NeighborSampler::new([2, 10])
+        .edge_labels(["E"])
+        .feature_fields(["id"])
+        .seed(1);
+    let batch = sampler.sample(&ro_txn, &[vids[0], vids[0]]).unwrap();
+    assert_eq!(batch.vids[0], vids[0]);
+    assert_eq!(batch.num_vertices_per_hop[..2], [1, 2]);
+    assert_eq!(
+        batch.num_vertices(),
+        batch.num_vertices_per_hop.iter().sum()
+    );
+    assert_eq!(batch.num_edges_per_hop[0], 2);
+    assert_eq!(batch.num_edges(), batch.num_edges_per_hop.iter().sum());
+    assert_eq!(batch.dst[..2], [0, 0]);
+    for (&src, &dst) in batch.src.iter().zip(&batch.dst) {
+        let (src, dst) = (index(batch.vids[src]), index(batch.vids[dst]));
+        // messages flow against the out edges
+        assert!(dst == 0 || src == 6);
+    }
+    assert_eq!(batch.features.len(), 1);
+    for (&vid, &feature) in batch.vids.iter().zip(&batch.features[0]) {
+        assert_eq!(feature, index(vid) as f64);
+    }
+    assert_eq!(sampler.sample(&ro_txn, &[vids[0]]).unwrap(), batch);
+
+    // all out edges are taken when they do not exceed the fanout
+    let batch = NeighborSampler::new([5])
+        .sample(&ro_txn, &[vids[0]])
+        .unwrap();
+    assert_eq!(batch.num_vertices_per_hop, [1, 5]);
+    let mut sampled = batch.vids[1..].to_vec();
+    sampled.sort();
+    assert_eq!(sampled, vids[1..6]);
+
+    let batch = NeighborSampler::new([10, 10])
+        .direction(Direction::In)
+        .sample(&ro_txn, &[vids[6]])
+        .unwrap();
+    assert_eq!(batch.num_vertices_per_hop, [1, 2, 1]);
+    assert_eq!(batch.vids[3], vids[0]);
+    assert_eq!(batch.num_edges_per_hop, [2, 2]);
+
+    assert!(NeighborSampler::new([1])
+        .sample(&ro_txn, &[1 << 40])
+        .is_err());
+    assert!(NeighborSampler::new([1])
+        .feature_fields(["NoSuchField"])
+        .sample(&ro_txn, &[vids[0]])
+        .is_err());
+    assert_eq!(
+        NeighborSampler::new([1])
+            .edge_labels(["Other"])
+            .sample(&ro_txn, &[vids[0]])
+            .unwrap()
+            .num_edges(),
+        0
+    );
+}
+
+#[test]
+fn test_projection() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    // 0 -> 1, 0 -> 2, 2 -> 0, 2 -> 0 again
+    let vids = create_graph(
+        &graph,
+        3,
+        &[(0, 1, 1.0), (0, 2, 2.0), (2, 0, 3.0), (2, 0, 4.0)],
+    );
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let lid = ro_txn.edge_label_id("E").unwrap() as u16;
+
+    let options = ProjectionOptions::new()
+        .edge_labels(["E"])
+        .weight_field("weight")
+        .reverse(true);
+    let projection = Projection::build(&ro_txn, &options).unwrap();
+    assert_eq!(projection.vids(), vids);
+    assert_eq!(projection.num_edges(), 4);
+    assert_eq!(projection.out_offsets(), [0, 2, 2, 4]);
+    assert_eq!(projection.out_neighbors(0), [1, 2]);
+    assert_eq!(projection.out_neighbors(2), [0, 0]);
+    assert_eq!(projection.edge_label_ids(), [lid; 4]);
+    let mut weights = projection.weights().unwrap().to_vec();
+    weights.sort_by(f64::total_cmp);
+    assert_eq!(weights, [1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(projection.in_neighbors(0), Some(&[2, 2][..]));
+    assert_eq!(projection.in_neighbors(1), Some(&[0][..]));
+    for e in 0..projection.num_edges() {
+        let uid = projection.edge_uid(e);
+        assert_eq!(uid.src, projection.vid(projection.edge_src(e)));
+        assert_eq!(uid.dst, projection.vid(projection.out_targets()[e]));
+        let ecur = ro_txn.out_edge_cur(&uid).unwrap();
+        assert!(ecur.is_valid());
+        assert_eq!(
+            ecur.field("weight").unwrap(),
+            FieldData::Double(projection.weights().unwrap()[e])
+        );
+    }
+    for id in 0..3 {
+        for &e in projection.in_edges(id).unwrap() {
+            assert_eq!(projection.out_targets()[e], id);
+        }
+    }
+
+    let parallel = Parallel::new(&graph, &ro_txn).num_threads(2);
+    assert_eq!(
+        Projection::par_build(&parallel, &options).unwrap(),
+        projection
+    );
+
+    let projection =
+        Projection::build(&ro_txn, &ProjectionOptions::new().edge_labels(["Other"])).unwrap();
+    assert_eq!(projection.num_vertices(), 3);
+    assert_eq!(projection.num_edges(), 0);
+    assert_eq!(projection.weights(), None);
+    assert_eq!(projection.in_neighbors(0), None);
+    assert!(Projection::build(
+        &ro_txn,
+        &ProjectionOptions::new().vertex_labels(["NoSuchLabel"])
+    )
+    .is_err());
+}
@@ -15,9 +15,10 @@
 use std::{collections::HashSet, iter::repeat};
 
 use tugraph::{
-    cursor::{EdgeCursor, VertexCursor},
+    cursor::{EdgeCursor, EdgeCursorMut, VertexCursor},
     field::{FieldData, FieldSpec, FieldType},
     txn::{RwTxn, TxnRead, TxnWrite},
+    ErrorKind,
 };
 
 mod common;
@@ -310,3 +311,156 @@ fn test_edge_cursor() {
         );
     }
 }
+
+#[test]
+fn test_edge_lookup() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    graph
+        .add_vertex_label(
+            "V",
+            &[FieldSpec {
+                name: "id".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "id",
+        )
+        .unwrap();
+    graph
+        .add_edge_label(
+            "E",
+            &[FieldSpec {
+                name: "weight".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "",
+            [("V", "V")],
+        )
+        .unwrap();
+
+    let (a, b, c, euid) = {
+        let mut rw_txn = graph.create_rw_txn(false).unwrap();
+        let vids: Vec<_> = (0..3)
+            .map(|i| {
+                rw_txn
+                    .add_vertex("V", &["id"], &[FieldData::Int64(i)])
+                    .unwrap()
+            })
+            .collect();
+        let (a, b, c) = (vids[0], vids[1], vids[2]);
+        let euid = rw_txn
+            .add_edge(a, b, "E", &["weight"], &[FieldData::Int64(1)])
+            .unwrap();
+        rw_txn
+            .add_edge(a, c, "E", &["weight"], &[FieldData::Int64(2)])
+            .unwrap();
+        rw_txn.commit().unwrap();
+        (a, b, c, euid)
+    };
+
+    {
+        let ro_txn = graph.create_ro_txn().unwrap();
+        let ecur = ro_txn.out_edge_cur(&euid).unwrap();
+        assert!(ecur.is_valid());
+        assert_eq!(ecur.uid().unwrap(), euid);
+        let ecur = ro_txn.in_edge_cur(&euid).unwrap();
+        assert!(ecur.is_valid());
+        assert_eq!(ecur.uid().unwrap(), euid);
+
+        let ecur = ro_txn.edges_between(a, b, "E").unwrap();
+        assert!(ecur.is_valid());
+        assert_eq!(ecur.field("weight").unwrap(), FieldData::Int64(1));
+        let ecur = ro_txn.in_edges_between(a, c, "E").unwrap();
+        assert!(ecur.is_valid());
+        assert_eq!(ecur.field("weight").unwrap(), FieldData::Int64(2));
+        assert!(!ro_txn.edges_between(b, c, "E").unwrap().is_valid());
+        assert!(ro_txn.edges_between(a, b, "NoSuchLabel").is_err());
+    }
+
+    {
+        let rw_txn = graph.create_rw_txn(false).unwrap();
+        let ecur = rw_txn.edges_between_mut(a, b, "E").unwrap();
+        ecur.set_field("weight", &FieldData::Int64(10)).unwrap();
+        drop(ecur);
+        rw_txn.commit().unwrap();
+
+        let ro_txn = graph.create_ro_txn().unwrap();
+        assert_eq!(
+            ro_txn.out_edge_cur(&euid).unwrap().field("weight").unwrap(),
+            FieldData::Int64(10)
+        );
+    }
+}
+
+#[test]
+fn test_typed_get() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    graph
+        .add_vertex_label(
+            "V",
+            &[
+                FieldSpec {
+                    name: "id".into(),
+                    ty: FieldType::Int8,
+                    optional: false,
+                },
+                FieldSpec {
+                    name: "name".into(),
+                    ty: FieldType::String,
+                    optional: true,
+                },
+            ],
+            "id",
+        )
+        .unwrap();
+    graph
+        .add_edge_label(
+            "E",
+            &[FieldSpec {
+                name: "weight".into(),
+                ty: FieldType::Float,
+                optional: false,
+            }],
+            "",
+            [("V", "V")],
+        )
+        .unwrap();
+
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    let a = rw_txn
+        .add_vertex("V", &["id", "name"], &[1_i8.into(), "a".into()])
+        .unwrap();
+    let b = rw_txn
+        .add_vertex("V", &["id", "name"], &[2_i8.into(), None::<String>.into()])
+        .unwrap();
+    let euid = rw_txn
+        .add_edge(a, b, "E", &["weight"], &[0.5_f32.into()])
+        .unwrap();
+    rw_txn.commit().unwrap();
+
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let mut vcur = ro_txn.vertex_cur().unwrap();
+    vcur.seek(a, false).unwrap();
+    assert_eq!(vcur.get::<i8>("id").unwrap(), 1);
+    assert_eq!(vcur.get::<i64>("id").unwrap(), 1);
+    assert_eq!(vcur.get::<String>("name").unwrap(), "a");
+    assert_eq!(
+        vcur.get::<Option<String>>("name").unwrap(),
+        Some("a".into())
+    );
+    assert_eq!(
+        vcur.get::<String>("id").unwrap_err().kind(),
+        ErrorKind::TypeMismatch
+    );
+    vcur.seek(b, false).unwrap();
+    assert_eq!(vcur.get::<Option<String>>("name").unwrap(), None);
+    assert!(vcur.get::<String>("name").is_err());
+
+    let ecur = ro_txn.out_edge_cur(&euid).unwrap();
+    assert_eq!(ecur.get::<f64>("weight").unwrap(), 0.5);
+    assert_eq!(ecur.get_by_id::<f32>(0).unwrap(), 0.5);
+    assert!(ecur.get::<i64>("weight").is_err());
+}
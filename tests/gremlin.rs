@@ -0,0 +1,291 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use tugraph::{
+    field::{FieldData, FieldSpec, FieldType},
+    gremlin::{eq, gt, gte, within, GraphTraversalSource, Order, Value},
+    txn::TxnWrite,
+};
+
+mod common;
+
+fn field(name: &str, ty: FieldType) -> FieldSpec {
+    FieldSpec {
+        name: name.into(),
+        ty,
+        optional: false,
+    }
+}
+
+#[test]
+fn test_gremlin() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    graph
+        .add_vertex_label(
+            "person",
+            &[
+                field("name", FieldType::String),
+                field("age", FieldType::Int32),
+            ],
+            "name",
+        )
+        .unwrap();
+    graph
+        .add_vertex_label(
+            "software",
+            &[
+                field("name", FieldType::String),
+                field("lang", FieldType::String),
+            ],
+            "name",
+        )
+        .unwrap();
+    graph.add_vertex_index("person", "age", false).unwrap();
+    for (label, dst) in [("knows", "person"), ("created", "software")] {
+        graph
+            .add_edge_label(
+                label,
+                &[field("weight", FieldType::Double)],
+                "",
+                [("person", dst)],
+            )
+            .unwrap();
+    }
+
+    // the "modern" graph of TinkerPop
+    let vids: HashMap<&str, i64> = {
+        let mut rw_txn = graph.create_rw_txn(false).unwrap();
+        let mut vids = HashMap::new();
+        for (name, age) in [("marko", 29), ("vadas", 27), ("josh", 32), ("peter", 35)] {
+            let vid = rw_txn
+                .add_vertex(
+                    "person",
+                    &["name", "age"],
+                    &[FieldData::from(name), FieldData::Int32(age)],
+                )
+                .unwrap();
+            vids.insert(name, vid);
+        }
+        for name in ["lop", "ripple"] {
+            let vid = rw_txn
+                .add_vertex(
+                    "software",
+                    &["name", "lang"],
+                    &[FieldData::from(name), FieldData::from("java")],
+                )
+                .unwrap();
+            vids.insert(name, vid);
+        }
+        for (src, dst, label, weight) in [
+            ("marko", "vadas", "knows", 0.5),
+            ("marko", "josh", "knows", 1.0),
+            ("marko", "lop", "created", 0.4),
+            ("josh", "ripple", "created", 1.0),
+            ("josh", "lop", "created", 0.4),
+            ("peter", "lop", "created", 0.2),
+        ] {
+            rw_txn
+                .add_edge(
+                    vids[src],
+                    vids[dst],
+                    label,
+                    &["weight"],
+                    &[FieldData::Double(weight)],
+                )
+                .unwrap();
+        }
+        rw_txn.commit().unwrap();
+        vids
+    };
+    let names = |names: &[&str]| -> Vec<Value> {
+        names
+            .iter()
+            .map(|&name| Value::Data(FieldData::from(name)))
+            .collect()
+    };
+    let count = |n: i64| vec![Value::Data(FieldData::Int64(n))];
+
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let g = GraphTraversalSource::new(&ro_txn);
+    assert_eq!(
+        g.v(vids["marko"])
+            .out("knows")
+            .has("age", gt(30))
+            .dedup()
+            .limit(10)
+            .values("name")
+            .to_list()
+            .unwrap(),
+        names(&["josh"])
+    );
+    assert_eq!(
+        g.v(vids["marko"]).out(()).count().to_list().unwrap(),
+        count(3)
+    );
+    assert_eq!(
+        g.v(vids["josh"]).both(()).count().to_list().unwrap(),
+        count(3)
+    );
+    assert_eq!(g.v(()).count().to_list().unwrap(), count(6));
+    assert_eq!(
+        g.v(vids["marko"])
+            .out_e("knows")
+            .has("weight", gte(1.0))
+            .in_v()
+            .values("name")
+            .to_list()
+            .unwrap(),
+        names(&["josh"])
+    );
+    assert_eq!(
+        g.v(vids["lop"])
+            .in_e("created")
+            .out_v()
+            .values("name")
+            .order(Order::Asc)
+            .to_list()
+            .unwrap(),
+        names(&["josh", "marko", "peter"])
+    );
+    assert_eq!(
+        g.v(vids["marko"])
+            .out("created")
+            .in_("created")
+            .dedup()
+            .order_by("name", Order::Desc)
+            .values("name")
+            .to_list()
+            .unwrap(),
+        names(&["peter", "marko", "josh"])
+    );
+    // `age` is missing from software
+    assert_eq!(
+        g.v(()).values("age").order(Order::Desc).to_list().unwrap(),
+        [35, 32, 29, 27].map(|age| Value::Data(FieldData::Int32(age)))
+    );
+    // missing ages sort as null, below all ages
+    let by_age = g
+        .v(())
+        .order_by("age", Order::Asc)
+        .values("name")
+        .to_list()
+        .unwrap();
+    for name in names(&["lop", "ripple"]) {
+        assert!(by_age[..2].contains(&name));
+    }
+    assert_eq!(by_age[2..], names(&["vadas", "marko", "josh", "peter"]));
+    let by_age = g
+        .v(())
+        .order_by("age", Order::Desc)
+        .values("name")
+        .to_list()
+        .unwrap();
+    assert_eq!(by_age[..4], names(&["peter", "josh", "marko", "vadas"]));
+
+    // found by the unique index of the primary field, and the index of `age`
+    assert_eq!(
+        g.v(())
+            .has_label("person")
+            .has("name", eq("josh"))
+            .values("age")
+            .to_list()
+            .unwrap(),
+        [Value::Data(FieldData::Int32(32))]
+    );
+    assert_eq!(
+        g.v(())
+            .has_label("person")
+            .has("name", eq("nobody"))
+            .to_list()
+            .unwrap(),
+        []
+    );
+    assert_eq!(
+        g.v(())
+            .has_label("person")
+            .has("age", within([29i64, 35, 35]))
+            .values("name")
+            .order(Order::Asc)
+            .to_list()
+            .unwrap(),
+        names(&["marko", "peter"])
+    );
+
+    assert_eq!(
+        g.v(())
+            .has_label("person")
+            .where_(|t| t.out("created"))
+            .values("name")
+            .order(Order::Asc)
+            .to_list()
+            .unwrap(),
+        names(&["josh", "marko", "peter"])
+    );
+    let mut paths = g
+        .v(vids["marko"])
+        .out("knows")
+        .has("name", eq("josh"))
+        .out("created")
+        .path()
+        .to_list()
+        .unwrap();
+    paths.sort_by_key(|path| match path {
+        Value::List(path) => path[2].as_vertex(),
+        _ => None,
+    });
+    let mut expected: Vec<_> = ["lop", "ripple"]
+        .iter()
+        .map(|&name| {
+            Value::List(vec![
+                Value::Vertex(vids["marko"]),
+                Value::Vertex(vids["josh"]),
+                Value::Vertex(vids[name]),
+            ])
+        })
+        .collect();
+    expected.sort_by_key(|path| match path {
+        Value::List(path) => path[2].as_vertex(),
+        _ => None,
+    });
+    assert_eq!(paths, expected);
+    assert_eq!(
+        g.v(vids["marko"])
+            .as_("a")
+            .out("knows")
+            .has("name", eq("vadas"))
+            .as_("b")
+            .select(["a", "b"])
+            .to_list()
+            .unwrap(),
+        [Value::Map(vec![
+            ("a".to_string(), Value::Vertex(vids["marko"])),
+            ("b".to_string(), Value::Vertex(vids["vadas"])),
+        ])]
+    );
+    assert_eq!(
+        g.v(vids["marko"])
+            .as_("a")
+            .out("created")
+            .select("a")
+            .to_list()
+            .unwrap(),
+        [Value::Vertex(vids["marko"])]
+    );
+
+    assert!(g.v(vids["marko"]).out("nonexistent").iter().is_err());
+    assert!(g.v(vids["marko"]).has_label("nonexistent").iter().is_err());
+}
@@ -0,0 +1,201 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tugraph::{
+    cursor::{VertexCursor, VertexCursorMut},
+    field::{FieldData, FieldSpec, FieldType},
+    txn::{TxnRead, TxnWrite},
+    ErrorKind,
+};
+
+mod common;
+
+#[test]
+fn test_label_handle() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    graph
+        .add_vertex_label(
+            "Person",
+            &[
+                FieldSpec {
+                    name: "name".into(),
+                    ty: FieldType::String,
+                    optional: false,
+                },
+                FieldSpec {
+                    name: "age".into(),
+                    ty: FieldType::Int32,
+                    optional: true,
+                },
+            ],
+            "name",
+        )
+        .unwrap();
+    graph
+        .add_edge_label(
+            "Knows",
+            &[FieldSpec {
+                name: "since".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "",
+            [("Person", "Person")],
+        )
+        .unwrap();
+
+    let (person, knows) = {
+        let ro_txn = graph.create_ro_txn().unwrap();
+        let person = ro_txn.vertex_label("Person").unwrap();
+        let knows = ro_txn.edge_label("Knows").unwrap();
+        assert!(ro_txn.vertex_label("NoSuchLabel").is_err());
+        assert_eq!(person.id(), ro_txn.vertex_label_id("Person").unwrap());
+        assert_eq!(
+            person.field_id("age").unwrap(),
+            ro_txn.vertex_field_id(person.id(), "age").unwrap()
+        );
+        assert_eq!(person.primary_field(), "name");
+        assert_eq!(knows.field_specs().len(), 1);
+        (person, knows)
+    };
+
+    // handles are reused across transactions
+    let (alice, bob, euid) = {
+        let mut rw_txn = graph.create_rw_txn(false).unwrap();
+        let alice = person
+            .add(
+                &mut rw_txn,
+                [
+                    ("name", &FieldData::String("alice".into())),
+                    ("age", &FieldData::Int32(30)),
+                ],
+            )
+            .unwrap();
+        let bob = person
+            .add(&mut rw_txn, [("name", &FieldData::String("bob".into()))])
+            .unwrap();
+        assert!(person
+            .add(&mut rw_txn, [("no_such_field", &FieldData::Int32(1))])
+            .is_err());
+        let euid = knows
+            .add(
+                &mut rw_txn,
+                alice,
+                bob,
+                [("since", &FieldData::Int64(2023))],
+            )
+            .unwrap();
+        rw_txn.commit().unwrap();
+        (alice, bob, euid)
+    };
+    {
+        let rw_txn = graph.create_rw_txn(false).unwrap();
+        let mut vcur = rw_txn.vertex_cur_mut().unwrap();
+        vcur.seek(bob, false).unwrap();
+        assert_eq!(person.get(&rw_txn, &vcur, "age").unwrap(), FieldData::Null);
+        person
+            .set(&rw_txn, &vcur, "age", &FieldData::Int32(31))
+            .unwrap();
+        drop(vcur);
+        rw_txn.commit().unwrap();
+    }
+    {
+        let ro_txn = graph.create_ro_txn().unwrap();
+        let mut vcur = ro_txn.vertex_cur().unwrap();
+        vcur.seek(alice, false).unwrap();
+        assert_eq!(
+            person.get(&ro_txn, &vcur, "age").unwrap(),
+            FieldData::Int32(30)
+        );
+        vcur.seek(bob, false).unwrap();
+        assert_eq!(
+            person.get(&ro_txn, &vcur, "age").unwrap(),
+            FieldData::Int32(31)
+        );
+        assert!(person.get(&ro_txn, &vcur, "no_such_field").is_err());
+        let ecur = ro_txn.out_edge_cur(&euid).unwrap();
+        assert_eq!(
+            knows.get(&ro_txn, &ecur, "since").unwrap(),
+            FieldData::Int64(2023)
+        );
+    }
+
+    // alter the schema underneath the handles
+    graph
+        .alter_edge_label_add_fields(
+            "Knows",
+            &[FieldSpec {
+                name: "weight".into(),
+                ty: FieldType::Double,
+                optional: true,
+            }],
+            &[FieldData::Null],
+        )
+        .unwrap();
+    graph
+        .alter_vertex_label_add_fields(
+            "Person",
+            &[FieldSpec {
+                name: "email".into(),
+                ty: FieldType::String,
+                optional: true,
+            }],
+            &[FieldData::Null],
+        )
+        .unwrap();
+    {
+        let rw_txn = graph.create_rw_txn(false).unwrap();
+        let mut vcur = rw_txn.vertex_cur_mut().unwrap();
+        vcur.seek(alice, false).unwrap();
+        let err = person.get(&rw_txn, &vcur, "age").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::SchemaChanged);
+        let err = person
+            .set(&rw_txn, &vcur, "age", &FieldData::Int32(32))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::SchemaChanged);
+        let ecur = vcur.out_edge_cursor_mut().unwrap();
+        let err = knows
+            .set(&rw_txn, &ecur, "since", &FieldData::Int64(2024))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::SchemaChanged);
+        drop(ecur);
+        drop(vcur);
+        rw_txn.abort().unwrap();
+
+        let ro_txn = graph.create_ro_txn().unwrap();
+        let ecur = ro_txn.out_edge_cur(&euid).unwrap();
+        let err = knows.get(&ro_txn, &ecur, "since").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::SchemaChanged);
+    }
+    let carol = FieldData::String("carol".into());
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    let err = person.add(&mut rw_txn, [("name", &carol)]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::SchemaChanged);
+    assert_eq!(
+        person.check(&rw_txn).unwrap_err().kind(),
+        ErrorKind::SchemaChanged
+    );
+    let person = rw_txn.vertex_label("Person").unwrap();
+    person
+        .add(
+            &mut rw_txn,
+            [
+                ("name", &carol),
+                ("email", &FieldData::String("carol@example.com".into())),
+            ],
+        )
+        .unwrap();
+    rw_txn.commit().unwrap();
+}
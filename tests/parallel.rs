@@ -0,0 +1,109 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tugraph::{
+    cursor::VertexCursor,
+    field::{FieldData, FieldSpec, FieldType},
+    parallel::Parallel,
+    traverse::{Direction, VisitedSet},
+    txn::TxnWrite,
+};
+
+mod common;
+
+#[test]
+fn test_parallel() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    graph
+        .add_vertex_label(
+            "V",
+            &[FieldSpec {
+                name: "id".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "id",
+        )
+        .unwrap();
+    graph.add_edge_label("E", &[], "", [("V", "V")]).unwrap();
+
+    // a binary tree where vertex i links to 2i+1 and 2i+2
+    const NUM_VERTICES: i64 = 100;
+    let vids = {
+        let mut rw_txn = graph.create_rw_txn(false).unwrap();
+        let vids: Vec<_> = (0..NUM_VERTICES)
+            .map(|i| {
+                rw_txn
+                    .add_vertex("V", &["id"], &[FieldData::Int64(i)])
+                    .unwrap()
+            })
+            .collect();
+        for i in 0..NUM_VERTICES as usize {
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < NUM_VERTICES as usize {
+                    rw_txn
+                        .add_edge(vids[i], vids[child], "E", &[], &[])
+                        .unwrap();
+                }
+            }
+        }
+        rw_txn.commit().unwrap();
+        vids
+    };
+
+    let ro_txn = graph.create_ro_txn().unwrap();
+    for num_threads in [1, 3] {
+        let parallel = Parallel::new(&graph, &ro_txn).num_threads(num_threads);
+
+        let count = AtomicUsize::new(0);
+        parallel
+            .par_for_each_vertex(|_| {
+                count.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(count.into_inner(), NUM_VERTICES as usize);
+
+        let sum = parallel
+            .par_map_reduce(|| 0, |v| v.get::<i64>("id"), |a, b| a + b)
+            .unwrap();
+        assert_eq!(sum, (0..NUM_VERTICES).sum::<i64>());
+
+        assert!(parallel
+            .par_map_reduce(
+                || 0,
+                |v| v.get::<String>("id").map(|s| s.len()),
+                |a, b| a + b
+            )
+            .is_err());
+
+        let levels = parallel
+            .par_bfs([vids[0]], Direction::Out, usize::MAX, VisitedSet::Bitmap)
+            .unwrap();
+        assert_eq!(
+            levels.iter().map(Vec::len).collect::<Vec<_>>(),
+            [1, 2, 4, 8, 16, 32, 37]
+        );
+        let levels = parallel
+            .par_bfs([vids[3]], Direction::Both, 1, VisitedSet::Hash)
+            .unwrap();
+        assert_eq!(levels.len(), 2);
+        let mut level = levels[1].clone();
+        level.sort();
+        assert_eq!(level, [vids[1], vids[7], vids[8]]);
+    }
+}
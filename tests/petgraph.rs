@@ -0,0 +1,122 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "petgraph")]
+
+use petgraph::{
+    algo::{astar, dijkstra, tarjan_scc, toposort},
+    visit::{
+        EdgeRef, IntoEdgeReferences, IntoEdgesDirected, IntoNeighborsDirected, IntoNodeIdentifiers,
+        NodeIndexable,
+    },
+    Direction,
+};
+use tugraph::{
+    field::{FieldData, FieldSpec, FieldType},
+    petgraph::TxnGraph,
+    txn::TxnWrite,
+};
+
+mod common;
+
+#[test]
+fn test_txn_graph() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    graph
+        .add_vertex_label(
+            "V",
+            &[FieldSpec {
+                name: "id".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "id",
+        )
+        .unwrap();
+    graph
+        .add_edge_label(
+            "E",
+            &[FieldSpec {
+                name: "weight".into(),
+                ty: FieldType::Double,
+                optional: false,
+            }],
+            "",
+            [("V", "V")],
+        )
+        .unwrap();
+    // 0 -> 1 -> 3 of weight 1 each, 0 -> 2 -> 3 of weight 5 each, and 3 -> 2
+    let vids = {
+        let mut rw_txn = graph.create_rw_txn(false).unwrap();
+        let vids: Vec<_> = (0..4)
+            .map(|i| {
+                rw_txn
+                    .add_vertex("V", &["id"], &[FieldData::Int64(i)])
+                    .unwrap()
+            })
+            .collect();
+        for (src, dst, weight) in [
+            (0, 1, 1.0),
+            (1, 3, 1.0),
+            (0, 2, 5.0),
+            (2, 3, 5.0),
+            (3, 2, 1.0),
+        ] {
+            rw_txn
+                .add_edge(
+                    vids[src],
+                    vids[dst],
+                    "E",
+                    &["weight"],
+                    &[FieldData::Double(weight)],
+                )
+                .unwrap();
+        }
+        rw_txn.commit().unwrap();
+        vids
+    };
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let g = TxnGraph::new(&ro_txn).weight_field("weight");
+
+    assert_eq!(g.node_identifiers().collect::<Vec<_>>(), vids);
+    assert_eq!(g.node_bound(), *vids.iter().max().unwrap() as usize + 1);
+    assert_eq!(g.from_index(g.to_index(vids[2])), vids[2]);
+    assert_eq!(g.edge_references().count(), 5);
+    let mut incoming: Vec<_> = g.neighbors_directed(vids[2], Direction::Incoming).collect();
+    incoming.sort();
+    assert_eq!(incoming, [vids[0], vids[3]]);
+    for edge in g.edges_directed(vids[2], Direction::Incoming) {
+        assert_eq!(edge.target(), vids[2]);
+        assert_eq!(edge.id().dst, vids[2]);
+    }
+
+    let costs = dijkstra(&g, vids[0], None, |e| *e.weight());
+    assert_eq!(costs[&vids[3]], 2.0);
+    assert_eq!(costs[&vids[2]], 3.0);
+    let (cost, path) = astar(&g, vids[0], |n| n == vids[2], |e| *e.weight(), |_| 0.0).unwrap();
+    assert_eq!(cost, 3.0);
+    assert_eq!(path, [vids[0], vids[1], vids[3], vids[2]]);
+
+    let mut sccs: Vec<_> = tarjan_scc(&g)
+        .into_iter()
+        .map(|mut scc| {
+            scc.sort();
+            scc
+        })
+        .collect();
+    sccs.sort();
+    assert_eq!(sccs, [vec![vids[0]], vec![vids[1]], vec![vids[2], vids[3]]]);
+    assert!(toposort(&g, None).is_err());
+}
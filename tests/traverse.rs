@@ -0,0 +1,226 @@
+// Copyright 2023 antkiller
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use tugraph::{
+    cursor::VertexCursor,
+    db::Graph,
+    field::{FieldData, FieldSpec, FieldType},
+    traverse::{Direction, Traversal, VisitedSet},
+    txn::TxnWrite,
+};
+
+mod common;
+
+fn create_labels(graph: &Graph) {
+    graph
+        .add_vertex_label(
+            "V",
+            &[FieldSpec {
+                name: "id".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "id",
+        )
+        .unwrap();
+    for label in ["E", "Other"] {
+        graph
+            .add_edge_label(
+                label,
+                &[FieldSpec {
+                    name: "weight".into(),
+                    ty: FieldType::Int64,
+                    optional: false,
+                }],
+                "",
+                [("V", "V")],
+            )
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_traverse() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    create_labels(&graph);
+
+    // 0 -> 1 -> 3 -> 4 ~> 5, where ~> is an `Other` edge
+    //   \-> 2 -/
+    let vids = {
+        let mut rw_txn = graph.create_rw_txn(false).unwrap();
+        let vids: Vec<_> = (0..6)
+            .map(|i| {
+                rw_txn
+                    .add_vertex("V", &["id"], &[FieldData::Int64(i)])
+                    .unwrap()
+            })
+            .collect();
+        for (src, dst, label, weight) in [
+            (0, 1, "E", 1),
+            (0, 2, "E", 2),
+            (1, 3, "E", 1),
+            (2, 3, "E", 1),
+            (3, 4, "E", 1),
+            (4, 5, "Other", 1),
+        ] {
+            rw_txn
+                .add_edge(
+                    vids[src],
+                    vids[dst],
+                    label,
+                    &["weight"],
+                    &[FieldData::Int64(weight)],
+                )
+                .unwrap();
+        }
+        rw_txn.commit().unwrap();
+        vids
+    };
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let depths = |traversal: Traversal, start: usize| -> HashMap<i64, usize> {
+        traversal
+            .bfs(&ro_txn, [vids[start]])
+            .unwrap()
+            .map(|step| step.map(|(depth, vid, _)| (vid, depth)))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    };
+    let expected = |pairs: &[(usize, usize)]| -> HashMap<i64, usize> {
+        pairs.iter().map(|&(i, depth)| (vids[i], depth)).collect()
+    };
+
+    assert_eq!(
+        depths(Traversal::new(), 0),
+        expected(&[(0, 0), (1, 1), (2, 1), (3, 2), (4, 3), (5, 4)])
+    );
+    assert_eq!(
+        depths(
+            Traversal::new()
+                .edge_labels(["E"])
+                .visited(VisitedSet::Bitmap),
+            0
+        ),
+        expected(&[(0, 0), (1, 1), (2, 1), (3, 2), (4, 3)])
+    );
+    assert_eq!(
+        depths(Traversal::new().max_depth(1), 0),
+        expected(&[(0, 0), (1, 1), (2, 1)])
+    );
+    assert_eq!(
+        depths(Traversal::new().direction(Direction::In), 3),
+        expected(&[(3, 0), (1, 1), (2, 1), (0, 2)])
+    );
+    assert_eq!(
+        depths(Traversal::new().direction(Direction::Both).max_depth(1), 3),
+        expected(&[(3, 0), (1, 1), (2, 1), (4, 1)])
+    );
+    assert_eq!(
+        depths(
+            Traversal::new().vertex_predicate(|v| Ok(v.get::<i64>("id")? != 3)),
+            0
+        ),
+        expected(&[(0, 0), (1, 1), (2, 1)])
+    );
+    assert_eq!(
+        depths(
+            Traversal::new().edge_predicate(|e| Ok(e.get::<i64>("weight")? < 2)),
+            0
+        ),
+        expected(&[(0, 0), (1, 1), (3, 2), (4, 3), (5, 4)])
+    );
+    assert!(Traversal::new()
+        .edge_labels(["NoSuchLabel"])
+        .bfs(&ro_txn, [vids[0]])
+        .is_err());
+
+    // via edges
+    let steps: Vec<_> = Traversal::new()
+        .max_depth(1)
+        .bfs(&ro_txn, [vids[0]])
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(steps[0], (0, vids[0], None));
+    for (_, vid, via) in &steps[1..] {
+        let via = via.unwrap();
+        assert_eq!((via.src, via.dst), (vids[0], *vid));
+    }
+
+    // dfs goes deep first, and visits each vertex once
+    let order: Vec<_> = Traversal::new()
+        .dfs(&ro_txn, [vids[0], vids[3]])
+        .unwrap()
+        .map(|step| step.unwrap().1)
+        .collect();
+    assert_eq!(order, [0, 1, 3, 4, 5, 2].map(|i| vids[i]).to_vec());
+}
+
+#[test]
+fn test_dfs_max_depth() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    create_labels(&graph);
+
+    // 0 -> 1 -> 2 -> 3, and 0 -> 2, where 2 is first reached at depth 2 through 1
+    let vids = {
+        let mut rw_txn = graph.create_rw_txn(false).unwrap();
+        let vids: Vec<_> = (0..4)
+            .map(|i| {
+                rw_txn
+                    .add_vertex("V", &["id"], &[FieldData::Int64(i)])
+                    .unwrap()
+            })
+            .collect();
+        for (src, dst) in [(0, 1), (1, 2), (2, 3), (0, 2)] {
+            rw_txn
+                .add_edge(
+                    vids[src],
+                    vids[dst],
+                    "E",
+                    &["weight"],
+                    &[FieldData::Int64(1)],
+                )
+                .unwrap();
+        }
+        rw_txn.commit().unwrap();
+        vids
+    };
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let steps = |max_depth: usize| -> Vec<(usize, i64)> {
+        Traversal::new()
+            .max_depth(max_depth)
+            .dfs(&ro_txn, [vids[0]])
+            .unwrap()
+            .map(|step| step.map(|(depth, vid, _)| (depth, vid)))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    };
+
+    // 3 is within depth 2 through 0 -> 2 -> 3, and yielded once
+    assert_eq!(
+        steps(2),
+        [(0, 0), (1, 1), (2, 2), (2, 3)].map(|(depth, i)| (depth, vids[i]))
+    );
+    assert_eq!(
+        steps(1),
+        [(0, 0), (1, 1), (1, 2)].map(|(depth, i)| (depth, vids[i]))
+    );
+    assert_eq!(
+        steps(3),
+        [(0, 0), (1, 1), (2, 2), (3, 3)].map(|(depth, i)| (depth, vids[i]))
+    );
+}
@@ -20,7 +20,7 @@ use std::{
 use tugraph::{
     cursor::{EdgeCursor, VertexCursor},
     field::{FieldData, FieldSpec, FieldType},
-    txn::{TxnRead, TxnWrite},
+    txn::{RetryPolicy, TxnRead, TxnWrite, UnfinishedDrop},
 };
 
 mod common;
@@ -347,3 +347,360 @@ fn test_txn_readwrite() {
         );
     } // end read-only transaction
 }
+
+#[test]
+fn test_write_retry_on_conflict() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    let added = graph
+        .add_vertex_label(
+            "v",
+            &[FieldSpec {
+                name: "id".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "id",
+        )
+        .unwrap();
+    assert!(added);
+
+    const NTHREADS: usize = 3;
+    let (barrier, n_attempts) = (Barrier::new(NTHREADS), AtomicUsize::new(0));
+    let policy = RetryPolicy::new().max_attempts(NTHREADS);
+    thread::scope(|s| {
+        let jhs: Vec<_> = (0..NTHREADS)
+            .map(|id| {
+                let (graph, barrier, n_attempts, policy) = (&graph, &barrier, &n_attempts, &policy);
+                s.spawn(move || {
+                    let mut first = true;
+                    let committed = graph
+                        .write_with(policy, |txn| {
+                            let vid =
+                                txn.add_vertex("v", &["id"], &[FieldData::Int64(id as i64)])?;
+                            if first {
+                                first = false;
+                                barrier.wait();
+                            }
+                            Ok(vid)
+                        })
+                        .expect("conflicts should be retried");
+                    n_attempts.fetch_add(committed.attempts, std::sync::atomic::Ordering::Relaxed);
+                })
+            })
+            .collect();
+        jhs.into_iter().for_each(|j| j.join().unwrap());
+    });
+    assert!(n_attempts.load(std::sync::atomic::Ordering::Relaxed) > NTHREADS);
+    let num_vertices = graph.read(|txn| txn.num_vertices()).unwrap();
+    assert_eq!(num_vertices, NTHREADS);
+}
+
+#[test]
+fn test_write_abort_on_error() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    let added = graph
+        .add_vertex_label(
+            "v",
+            &[FieldSpec {
+                name: "id".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "id",
+        )
+        .unwrap();
+    assert!(added);
+
+    let res = graph.write_with(&RetryPolicy::no_retry(), |txn| {
+        txn.add_vertex("v", &["id"], &[FieldData::Int64(1)])?;
+        // label does not exist
+        txn.add_vertex("no_such_label", &["id"], &[FieldData::Int64(2)])
+    });
+    assert!(res.is_err());
+    assert_eq!(graph.read(|txn| txn.num_vertices()).unwrap(), 0);
+
+    let committed = graph
+        .write(|txn| txn.add_vertex("v", &["id"], &[FieldData::Int64(1)]))
+        .unwrap();
+    assert_eq!(committed.attempts, 1);
+    assert_eq!(graph.read(|txn| txn.num_vertices()).unwrap(), 1);
+}
+
+#[test]
+fn test_rw_txn_abort() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    let added = graph
+        .add_vertex_label(
+            "v",
+            &[FieldSpec {
+                name: "id".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "id",
+        )
+        .unwrap();
+    assert!(added);
+
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    rw_txn
+        .add_vertex("v", &["id"], &[FieldData::Int64(1)])
+        .unwrap();
+    rw_txn.abort().expect("abort should be ok");
+    assert_eq!(graph.create_ro_txn().unwrap().num_vertices().unwrap(), 0);
+
+    // a new write transaction can be created after the earlier one is aborted
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    rw_txn.set_unfinished_drop(UnfinishedDrop::Panic);
+    rw_txn
+        .add_vertex("v", &["id"], &[FieldData::Int64(1)])
+        .unwrap();
+    rw_txn.commit().unwrap();
+    assert_eq!(graph.create_ro_txn().unwrap().num_vertices().unwrap(), 1);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn test_rw_txn_drop_unfinished() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    let added = graph
+        .add_vertex_label(
+            "v",
+            &[FieldSpec {
+                name: "id".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "id",
+        )
+        .unwrap();
+    assert!(added);
+
+    let panicked = thread::scope(|s| {
+        s.spawn(|| {
+            let mut rw_txn = graph.create_rw_txn(false).unwrap();
+            rw_txn.set_unfinished_drop(UnfinishedDrop::Panic);
+            rw_txn
+                .add_vertex("v", &["id"], &[FieldData::Int64(1)])
+                .unwrap();
+        })
+        .join()
+        .is_err()
+    });
+    assert!(panicked);
+    // the dropped transaction is still rolled back
+    assert_eq!(graph.create_ro_txn().unwrap().num_vertices().unwrap(), 0);
+
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    rw_txn.set_unfinished_drop(UnfinishedDrop::Ignore);
+    rw_txn
+        .add_vertex("v", &["id"], &[FieldData::Int64(1)])
+        .unwrap();
+    drop(rw_txn);
+    assert_eq!(graph.create_ro_txn().unwrap().num_vertices().unwrap(), 0);
+}
+
+#[test]
+fn test_value_string_write() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    graph
+        .add_vertex_label(
+            "Person",
+            &[
+                FieldSpec {
+                    name: "name".into(),
+                    ty: FieldType::String,
+                    optional: false,
+                },
+                FieldSpec {
+                    name: "age".into(),
+                    ty: FieldType::Int32,
+                    optional: false,
+                },
+            ],
+            "name",
+        )
+        .unwrap();
+    graph
+        .add_edge_label(
+            "Knows",
+            &[FieldSpec {
+                name: "weight".into(),
+                ty: FieldType::Double,
+                optional: false,
+            }],
+            "",
+            [("Person", "Person")],
+        )
+        .unwrap();
+
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    let alice = rw_txn
+        .add_vertex_str("Person", &["name", "age"], &["alice", "30"])
+        .unwrap();
+    let bob = rw_txn
+        .add_vertex_str("Person", &["name", "age"], &["bob", "31"])
+        .unwrap();
+    assert!(rw_txn
+        .add_vertex_str("Person", &["name", "age"], &["carol", "not a number"])
+        .is_err());
+    let euid = rw_txn
+        .add_edge_str(alice, bob, "Knows", &["weight"], &["0.5"])
+        .unwrap();
+    assert_eq!((euid.src, euid.dst), (alice, bob));
+    let inserted = rw_txn
+        .upsert_edge_str(alice, bob, "Knows", &["weight"], &["1.5"])
+        .unwrap();
+    assert!(!inserted);
+    rw_txn.commit().unwrap();
+
+    let ro_txn = graph.create_ro_txn().unwrap();
+    let vcur = ro_txn
+        .unique_index_vertex_cur_str("Person", "name", "bob")
+        .unwrap();
+    assert!(vcur.is_valid());
+    assert_eq!(vcur.id().unwrap(), bob);
+    assert_eq!(vcur.field("age").unwrap(), FieldData::Int32(31));
+    assert_eq!(
+        ro_txn
+            .edges_between(alice, bob, "Knows")
+            .unwrap()
+            .field("weight")
+            .unwrap(),
+        FieldData::Double(1.5)
+    );
+}
+
+#[test]
+fn test_upsert_vertex() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    graph
+        .add_vertex_label(
+            "Person",
+            &[
+                FieldSpec {
+                    name: "name".into(),
+                    ty: FieldType::String,
+                    optional: false,
+                },
+                FieldSpec {
+                    name: "age".into(),
+                    ty: FieldType::Int32,
+                    optional: true,
+                },
+            ],
+            "name",
+        )
+        .unwrap();
+
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    let alice = FieldData::String("alice".into());
+    let (inserted, vid) = rw_txn
+        .upsert_vertex("Person", &["name", "age"], [&alice, &FieldData::Int32(30)])
+        .unwrap();
+    assert!(inserted);
+    let (inserted, updated_vid) = rw_txn
+        .upsert_vertex("Person", &["age", "name"], [&FieldData::Int32(31), &alice])
+        .unwrap();
+    assert!(!inserted);
+    assert_eq!(updated_vid, vid);
+    // primary field is missing
+    assert!(rw_txn
+        .upsert_vertex("Person", &["age"], [&FieldData::Int32(32)])
+        .is_err());
+    // lengths differ
+    assert!(rw_txn
+        .upsert_vertex("Person", &["name", "age"], [&alice])
+        .is_err());
+    rw_txn.commit().unwrap();
+
+    let ro_txn = graph.create_ro_txn().unwrap();
+    assert_eq!(ro_txn.num_vertices().unwrap(), 1);
+    let vcur = ro_txn
+        .unique_index_vertex_cur("Person", "name", &alice)
+        .unwrap();
+    assert_eq!(vcur.field("age").unwrap(), FieldData::Int32(31));
+}
+
+#[test]
+fn test_batch_add() {
+    let galaxy = common::open_galaxy_in_tmpdir().unwrap();
+    let graph = galaxy.open_graph("default", false).unwrap();
+    graph
+        .add_vertex_label(
+            "v",
+            &[FieldSpec {
+                name: "id".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "id",
+        )
+        .unwrap();
+    graph
+        .add_edge_label(
+            "e",
+            &[FieldSpec {
+                name: "weight".into(),
+                ty: FieldType::Int64,
+                optional: false,
+            }],
+            "",
+            [("v", "v")],
+        )
+        .unwrap();
+
+    let mut rw_txn = graph.create_rw_txn(false).unwrap();
+    let rows: Vec<_> = [0, 1, 1, 2]
+        .into_iter()
+        .map(|id| [FieldData::Int64(id)])
+        .collect();
+    let report = rw_txn.add_vertices("v", &["id"], &rows).unwrap();
+    assert_eq!(report.num_rows(), 4);
+    assert!(!report.is_all_succeeded());
+    assert_eq!(
+        report
+            .succeeded
+            .iter()
+            .map(|(pos, _)| *pos)
+            .collect::<Vec<_>>(),
+        vec![0, 1, 3]
+    );
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, 2);
+    assert!(rw_txn
+        .add_vertices("no_such_label", &["id"], &rows)
+        .is_err());
+
+    let vids: Vec<_> = report.succeeded.iter().map(|(_, vid)| *vid).collect();
+    let weight = [FieldData::Int64(1)];
+    let rows = vec![
+        (vids[0], vids[1], &weight),
+        (vids[1], vids[2], &weight),
+        (vids[0], i64::MAX, &weight),
+    ];
+    let report = rw_txn.add_edges("e", &["weight"], rows).unwrap();
+    assert_eq!(report.succeeded.len(), 2);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, 2);
+    assert_eq!(report.succeeded[0].1.src, vids[0]);
+    rw_txn.commit().unwrap();
+
+    const NUM_ROWS: i64 = 100;
+    let rows: Vec<_> = (3..3 + NUM_ROWS)
+        .chain([0])
+        .map(|id| [FieldData::Int64(id)])
+        .collect();
+    let report = graph.load_vertices("v", &["id"], &rows, 7).unwrap();
+    assert_eq!(report.succeeded.len(), NUM_ROWS as usize);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, NUM_ROWS as usize);
+    let ro_txn = graph.create_ro_txn().unwrap();
+    assert_eq!(ro_txn.num_vertices().unwrap(), 3 + NUM_ROWS as usize);
+}